@@ -0,0 +1,265 @@
+use std::{
+    io::{Stdout, Write},
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+use anyhow::{anyhow, Context, Error, Result};
+use common::{
+    animate::{Colour, Frame, Simulation},
+    point::Point,
+};
+use crossterm::{
+    cursor,
+    event::{self, Event, KeyCode, KeyEventKind, KeyModifiers},
+    style::{self, Color},
+    terminal, QueueableCommand,
+};
+
+/// Region of the puzzle to display, corners included
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Viewport {
+    pub min: Point<isize>,
+    pub max: Point<isize>,
+}
+
+impl FromStr for Viewport {
+    type Err = Error;
+
+    /// `x0,y0:x1,y1`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let point = |p: &str| -> Result<Point<isize>> {
+            let (x, y) = p.split_once(',').context("expected x,y")?;
+            Ok(Point::new(x.trim().parse()?, y.trim().parse()?))
+        };
+        let (a, b) = s.split_once(':').context("expected x0,y0:x1,y1")?;
+        let (a, b) = (point(a)?, point(b)?);
+        if a.x > b.x || a.y > b.y {
+            return Err(anyhow!("empty viewport {}", s));
+        }
+        Ok(Viewport { min: a, max: b })
+    }
+}
+
+/// Slowest frame rate the player goes down to
+pub const MIN_FPS: f64 = 0.5;
+
+/// Fastest frame rate the player goes up to
+pub const MAX_FPS: f64 = 1000.0;
+
+pub struct PlayerOptions {
+    pub fps: f64,
+    pub viewport: Option<Viewport>,
+    pub paused: bool,
+}
+
+/// Puts the terminal back in its normal state, even on error
+pub struct Screen {
+    pub out: Stdout,
+}
+
+impl Screen {
+    pub fn enter() -> Result<Self> {
+        let mut out = std::io::stdout();
+        terminal::enable_raw_mode().context("the animation needs an interactive terminal")?;
+        out.queue(terminal::EnterAlternateScreen)?
+            .queue(cursor::Hide)?
+            .flush()?;
+        Ok(Screen { out })
+    }
+}
+
+impl Drop for Screen {
+    fn drop(&mut self) {
+        let _ = self
+            .out
+            .queue(style::ResetColor)
+            .and_then(|o| o.queue(cursor::Show))
+            .and_then(|o| o.queue(terminal::LeaveAlternateScreen))
+            .and_then(|o| o.flush());
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+pub fn colour(c: Colour) -> Color {
+    match c {
+        Colour::Default => Color::Reset,
+        Colour::Grey => Color::DarkGrey,
+        Colour::Red => Color::Red,
+        Colour::Green => Color::Green,
+        Colour::Yellow => Color::Yellow,
+        Colour::Blue => Color::Blue,
+        Colour::Magenta => Color::Magenta,
+        Colour::Cyan => Color::Cyan,
+        Colour::White => Color::White,
+        Colour::Scale(v) => Color::AnsiValue(232 + (v as u16 * 23 / 255) as u8),
+    }
+}
+
+/// Region shown when no viewport is given: the whole frame if it fits, otherwise
+/// a window of the terminal size around the focus
+pub fn auto_viewport(frame: &Frame, cols: usize, rows: usize) -> Viewport {
+    let centre = |size: usize, origin: isize, len: usize, focus: Option<isize>| {
+        if len <= size {
+            return origin;
+        }
+        let start = match focus {
+            Some(f) => f - size as isize / 2,
+            None => origin,
+        };
+        start.clamp(origin, origin + (len - size) as isize)
+    };
+    let x = centre(cols, frame.origin.x, frame.width, frame.focus.map(|f| f.x));
+    let y = centre(rows, frame.origin.y, frame.height, frame.focus.map(|f| f.y));
+    Viewport {
+        min: Point::new(x, y),
+        max: Point::new(x + cols as isize - 1, y + rows as isize - 1),
+    }
+}
+
+/// Draw `width` by `height` cells of the frame from `min` at the top left of
+/// the screen, clearing the rest of each line
+pub fn draw_frame(
+    out: &mut Stdout,
+    frame: &Frame,
+    min: Point<isize>,
+    width: usize,
+    height: usize,
+) -> Result<()> {
+    out.queue(cursor::MoveTo(0, 0))?;
+    let mut current = None;
+    for y in 0..height as isize {
+        for x in 0..width as isize {
+            let pixel = frame.get(min + Point::new(x, y));
+            let (ch, c) = pixel.map_or((' ', Colour::Default), |p| (p.ch, p.colour));
+            if current != Some(c) {
+                out.queue(style::SetForegroundColor(colour(c)))?;
+                current = Some(c);
+            }
+            out.queue(style::Print(ch))?;
+        }
+        out.queue(terminal::Clear(terminal::ClearType::UntilNewLine))?
+            .queue(style::Print("\r\n"))?;
+    }
+    out.queue(terminal::Clear(terminal::ClearType::FromCursorDown))?
+        .queue(style::ResetColor)?;
+    Ok(())
+}
+
+struct Player {
+    sim: Box<dyn Simulation>,
+    fps: f64,
+    viewport: Option<Viewport>,
+    /// Panning applied on top of the viewport
+    offset: Point<isize>,
+    paused: bool,
+    finished: bool,
+    steps: usize,
+}
+
+impl Player {
+    fn draw(&self, screen: &mut Screen) -> Result<()> {
+        let frame = self.sim.frame();
+        let (cols, rows) = terminal::size()?;
+        let (cols, rows) = (cols as usize, (rows as usize).saturating_sub(2));
+        let viewport = self
+            .viewport
+            .unwrap_or_else(|| auto_viewport(&frame, cols, rows));
+        let min = viewport.min + self.offset;
+        let width = ((viewport.max.x - viewport.min.x + 1) as usize).min(cols);
+        let height = ((viewport.max.y - viewport.min.y + 1) as usize).min(rows);
+
+        let out = &mut screen.out;
+        draw_frame(out, &frame, min, width, height)?;
+
+        let state = match (self.finished, self.paused) {
+            (true, _) => "finished",
+            (false, true) => "paused",
+            (false, false) => "running",
+        };
+        out.queue(cursor::MoveTo(0, rows as u16))?
+            .queue(style::Print(format!(
+                "step {} | {:.0} fps | {} | view {},{} | {}",
+                self.steps, self.fps, state, min.x, min.y, frame.status
+            )))?
+            .queue(terminal::Clear(terminal::ClearType::UntilNewLine))?
+            .queue(cursor::MoveTo(0, rows as u16 + 1))?
+            .queue(style::SetForegroundColor(Color::DarkGrey))?
+            .queue(style::Print(
+                "space: pause  n: step  +/-: speed  arrows: pan  0: recentre  q: quit",
+            ))?
+            .queue(style::ResetColor)?;
+        out.flush()?;
+        Ok(())
+    }
+
+    fn step(&mut self) {
+        if self.finished {
+            return;
+        }
+        if self.sim.step() {
+            self.steps += 1;
+        } else {
+            self.finished = true;
+        }
+    }
+
+    /// Returns `false` when the user quits
+    fn handle(&mut self, code: KeyCode, modifiers: KeyModifiers) -> bool {
+        match code {
+            KeyCode::Char('q') | KeyCode::Esc => return false,
+            KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => return false,
+            KeyCode::Char(' ') => self.paused = !self.paused,
+            KeyCode::Char('n') => {
+                self.paused = true;
+                self.step();
+            }
+            KeyCode::Char('+') | KeyCode::Char('=') => self.fps = (self.fps * 2.0).min(MAX_FPS),
+            KeyCode::Char('-') => self.fps = (self.fps / 2.0).max(MIN_FPS),
+            KeyCode::Left => self.offset.x -= 1,
+            KeyCode::Right => self.offset.x += 1,
+            KeyCode::Up => self.offset.y -= 1,
+            KeyCode::Down => self.offset.y += 1,
+            KeyCode::Char('0') => self.offset = Point::default(),
+            _ => {}
+        }
+        true
+    }
+}
+
+/// Play the simulation in the terminal until it ends and the user quits
+pub fn play(sim: Box<dyn Simulation>, options: &PlayerOptions) -> Result<()> {
+    let mut player = Player {
+        sim,
+        fps: options.fps,
+        viewport: options.viewport,
+        offset: Point::default(),
+        paused: options.paused,
+        finished: false,
+        steps: 0,
+    };
+    let mut screen = Screen::enter()?;
+
+    let mut next_step = Instant::now();
+    loop {
+        player.draw(&mut screen)?;
+
+        let timeout = if player.paused || player.finished {
+            Duration::from_secs(3600)
+        } else {
+            next_step.saturating_duration_since(Instant::now())
+        };
+        if event::poll(timeout)? {
+            if let Event::Key(key) = event::read()? {
+                if key.kind != KeyEventKind::Release && !player.handle(key.code, key.modifiers) {
+                    return Ok(());
+                }
+            }
+            continue;
+        }
+        if !player.paused {
+            player.step();
+            next_step = Instant::now() + Duration::from_secs_f64(1.0 / player.fps);
+        }
+    }
+}
@@ -0,0 +1,163 @@
+use std::{path::Path, time::Duration};
+
+use anyhow::{anyhow, Context, Result};
+use common::bench::{BenchReport, Stats};
+use toml::{Table, Value};
+
+use crate::verify::sort_days;
+
+/// Saved benchmark results, in nanoseconds:
+///
+/// ```toml
+/// [day1.part1]
+/// min = 41200
+/// median = 42000
+/// mean = 42500
+/// stddev = 900
+/// ```
+#[derive(Debug, Default)]
+pub struct Results {
+    table: Table,
+}
+
+fn step_name(part: Option<u8>) -> String {
+    match part {
+        None => "parse".to_string(),
+        Some(p) => format!("part{}", p),
+    }
+}
+
+fn nanos(table: &Table, key: &str) -> Result<Duration> {
+    let v = table
+        .get(key)
+        .and_then(Value::as_integer)
+        .ok_or_else(|| anyhow!("missing {}", key))?;
+    Ok(Duration::from_nanos(u64::try_from(v)?))
+}
+
+impl Results {
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read benchmark file {}", path.display()))?;
+        let table = content
+            .parse::<Table>()
+            .with_context(|| format!("invalid benchmark file {}", path.display()))?;
+        Ok(Results { table })
+    }
+
+    /// Like [`Results::load`] but a missing file is empty
+    pub fn load_or_default(path: &Path) -> Result<Self> {
+        if path.exists() {
+            Self::load(path)
+        } else {
+            Ok(Results::default())
+        }
+    }
+
+    pub fn save(&mut self, path: &Path) -> Result<()> {
+        sort_days(&mut self.table);
+        let content = toml::to_string(&self.table)?;
+        std::fs::write(path, content)
+            .with_context(|| format!("failed to write benchmark file {}", path.display()))
+    }
+
+    /// `part` is `None` for the parsing step
+    pub fn get(&self, day: u8, part: Option<u8>) -> Result<Option<Stats>> {
+        let Some(step) = self
+            .table
+            .get(&format!("day{}", day))
+            .and_then(|d| d.get(step_name(part)))
+        else {
+            return Ok(None);
+        };
+        let step = step
+            .as_table()
+            .ok_or_else(|| anyhow!("day{}.{} is not a table", day, step_name(part)))?;
+        Ok(Some(Stats {
+            min: nanos(step, "min")?,
+            median: nanos(step, "median")?,
+            mean: nanos(step, "mean")?,
+            stddev: nanos(step, "stddev")?,
+        }))
+    }
+
+    /// Replace the steps of the report, keeping the other steps of the day
+    pub fn insert(&mut self, report: &BenchReport) {
+        let key = format!("day{}", report.day);
+        let mut day = match self.table.remove(&key) {
+            Some(Value::Table(day)) => day,
+            _ => Table::new(),
+        };
+        let steps = std::iter::once((None, report.parse))
+            .chain(report.parts.iter().map(|(p, s)| (Some(*p), *s)));
+        for (part, stats) in steps {
+            let mut step = Table::new();
+            for (key, d) in [
+                ("min", stats.min),
+                ("median", stats.median),
+                ("mean", stats.mean),
+                ("stddev", stats.stddev),
+            ] {
+                step.insert(key.to_string(), Value::Integer(d.as_nanos() as i64));
+            }
+            day.insert(step_name(part), Value::Table(step));
+        }
+        self.table.insert(key, Value::Table(day));
+    }
+}
+
+/// Relative change of the median, in percent
+pub fn median_change(baseline: &Stats, current: &Stats) -> f64 {
+    let base = baseline.median.as_secs_f64();
+    if base == 0.0 {
+        return 0.0;
+    }
+    (current.median.as_secs_f64() - base) / base * 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats(median: u64) -> Stats {
+        let d = Duration::from_nanos(median);
+        Stats {
+            min: d,
+            median: d,
+            mean: d,
+            stddev: Duration::ZERO,
+        }
+    }
+
+    #[test]
+    fn insert_keeps_other_steps() {
+        let mut results = Results::default();
+        results.insert(&BenchReport {
+            day: 3,
+            parse: stats(10),
+            parts: vec![(1, stats(100)), (2, stats(200))],
+        });
+        results.insert(&BenchReport {
+            day: 3,
+            parse: stats(11),
+            parts: vec![(1, stats(90))],
+        });
+        assert_eq!(results.get(3, None).unwrap(), Some(stats(11)));
+        assert_eq!(results.get(3, Some(1)).unwrap(), Some(stats(90)));
+        assert_eq!(results.get(3, Some(2)).unwrap(), Some(stats(200)));
+        assert_eq!(results.get(4, Some(1)).unwrap(), None);
+    }
+
+    #[test]
+    fn insert_over_invalid_day() {
+        let mut results = Results {
+            table: "day3 = 1".parse().unwrap(),
+        };
+        results.insert(&BenchReport {
+            day: 3,
+            parse: stats(10),
+            parts: vec![],
+        });
+        assert_eq!(results.get(3, None).unwrap(), Some(stats(10)));
+    }
+}
@@ -0,0 +1,452 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    thread,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{anyhow, Context, Result};
+use serde::Deserialize;
+
+pub const YEAR: u16 = 2022;
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+/// Environment variables overriding the config file
+pub const SESSION_VAR: &str = "AOC_SESSION";
+pub const BASE_URL_VAR: &str = "AOC_BASE_URL";
+/// The site asks automated clients to identify themselves
+const USER_AGENT: &str = "github.com/SilentVoid13/aoc2022 (aoc runner)";
+
+/// Settings of the site client, read from a TOML file:
+///
+/// ```toml
+/// session = "53616c74..."
+/// base_url = "https://adventofcode.com"
+/// interval = 5
+/// ```
+#[derive(Debug, Clone)]
+pub struct Config {
+    /// Value of the `session` cookie of a logged in browser
+    pub session: Option<String>,
+    pub base_url: String,
+    /// Minimum time between two requests, shared by every run of the client
+    pub interval: Duration,
+    /// File remembering when the last request was sent
+    pub stamp: PathBuf,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+struct ConfigFile {
+    session: Option<String>,
+    base_url: Option<String>,
+    /// Seconds
+    interval: Option<f64>,
+}
+
+/// Default config file, next to the default inputs
+pub fn default_config_path() -> PathBuf {
+    common::input::input_dir().join("aoc.toml")
+}
+
+impl Config {
+    /// A missing file leaves the defaults, the environment variables take
+    /// precedence over the file
+    pub fn load(path: &Path) -> Result<Self> {
+        let file = match path.exists() {
+            true => {
+                let content = fs::read_to_string(path)
+                    .with_context(|| format!("failed to read config file {}", path.display()))?;
+                toml::from_str(&content)
+                    .with_context(|| format!("invalid config file {}", path.display()))?
+            }
+            false => ConfigFile::default(),
+        };
+        let interval = file.interval.unwrap_or(5.0);
+        if !interval.is_finite() || interval < 0.0 {
+            return Err(anyhow!("the interval must be a positive number of seconds"));
+        }
+        Ok(Config {
+            session: env::var(SESSION_VAR)
+                .ok()
+                .or(file.session)
+                .filter(|s| !s.is_empty()),
+            base_url: env::var(BASE_URL_VAR)
+                .ok()
+                .or(file.base_url)
+                .unwrap_or_else(|| DEFAULT_BASE_URL.to_string()),
+            interval: Duration::from_secs_f64(interval),
+            stamp: env::temp_dir().join("aoc2022-last-request"),
+        })
+    }
+}
+
+/// Spaces the requests by the configured interval, even across processes
+struct Throttle {
+    interval: Duration,
+    stamp: PathBuf,
+}
+
+impl Throttle {
+    /// Sleep until the interval since the last request is over, then record
+    /// the new one
+    fn wait(&self) -> Result<()> {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?;
+        let last = fs::read_to_string(&self.stamp)
+            .ok()
+            .and_then(|s| s.trim().parse::<u64>().ok())
+            .map(Duration::from_millis);
+        let mut sent = now;
+        if let Some(remaining) = last.and_then(|last| (last + self.interval).checked_sub(now)) {
+            thread::sleep(remaining);
+            sent += remaining;
+        }
+        fs::write(&self.stamp, sent.as_millis().to_string())
+            .with_context(|| format!("failed to write {}", self.stamp.display()))
+    }
+}
+
+/// How the site judged a submitted answer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Submission {
+    Correct,
+    /// `too high` or `too low` when the site tells
+    Incorrect {
+        hint: Option<&'static str>,
+    },
+    /// An answer was submitted too recently, with the time left when the site tells
+    TooSoon {
+        wait: Option<Duration>,
+    },
+    /// The part is already solved, or locked
+    WrongLevel,
+}
+
+/// The `<article>` holding the message of an answer page
+fn article_text(html: &str) -> &str {
+    let start = html.find("<article").unwrap_or(0);
+    let end = html[start..]
+        .find("</article>")
+        .map_or(html.len(), |i| start + i);
+    &html[start..end]
+}
+
+/// `1h 2m 3s` style duration
+fn parse_wait(s: &str) -> Option<Duration> {
+    let mut total = 0;
+    for token in s.split_whitespace() {
+        let (value, unit) = token.split_at(token.find(|c: char| !c.is_ascii_digit())?);
+        let seconds = match unit {
+            "h" => 3600,
+            "m" => 60,
+            "s" => 1,
+            _ => return None,
+        };
+        total += value.parse::<u64>().ok()? * seconds;
+    }
+    Some(Duration::from_secs(total))
+}
+
+impl Submission {
+    /// Read the verdict from the page returned for an answer
+    pub fn parse(html: &str) -> Result<Self> {
+        let text = article_text(html);
+        if text.contains("That's the right answer") {
+            return Ok(Submission::Correct);
+        }
+        if text.contains("That's not the right answer") {
+            let hint = ["too high", "too low"]
+                .into_iter()
+                .find(|h| text.contains(&format!("your answer is {}", h)));
+            return Ok(Submission::Incorrect { hint });
+        }
+        if text.contains("You gave an answer too recently") {
+            let wait = text
+                .split_once("You have ")
+                .and_then(|(_, rest)| rest.split_once(" left to wait"))
+                .and_then(|(wait, _)| parse_wait(wait));
+            return Ok(Submission::TooSoon { wait });
+        }
+        if text.contains("You don't seem to be solving the right level") {
+            return Ok(Submission::WrongLevel);
+        }
+        let message: String = text
+            .split('<')
+            .map(|s| s.split_once('>').map_or(s, |(_, t)| t))
+            .collect();
+        Err(anyhow!("unexpected answer page: {}", message.trim()))
+    }
+}
+
+/// Client of the puzzle site, for the inputs and answers of the logged in user
+pub struct Client {
+    agent: ureq::Agent,
+    base_url: String,
+    session: String,
+    throttle: Throttle,
+}
+
+impl Client {
+    pub fn new(config: &Config) -> Result<Self> {
+        let session = config.session.clone().with_context(|| {
+            format!(
+                "no session token, set {} or `session` in the config file",
+                SESSION_VAR
+            )
+        })?;
+        Ok(Client {
+            agent: ureq::AgentBuilder::new()
+                .user_agent(USER_AGENT)
+                .timeout(Duration::from_secs(30))
+                .build(),
+            base_url: config.base_url.trim_end_matches('/').to_string(),
+            session,
+            throttle: Throttle {
+                interval: config.interval,
+                stamp: config.stamp.clone(),
+            },
+        })
+    }
+
+    /// Request for a page of a day, sent once the throttle allows it
+    fn request(&self, method: &str, day: u8, path: &str) -> Result<ureq::Request> {
+        self.throttle.wait()?;
+        let url = format!("{}/{}/day/{}{}", self.base_url, YEAR, day, path);
+        Ok(self
+            .agent
+            .request(method, &url)
+            .set("Cookie", &format!("session={}", self.session)))
+    }
+
+    /// Body of a response, with the error statuses turned into readable errors
+    fn body(day: u8, response: Result<ureq::Response, ureq::Error>) -> Result<String> {
+        match response {
+            Ok(response) => Ok(response.into_string()?),
+            Err(ureq::Error::Status(404, _)) => Err(anyhow!("day {} is not unlocked yet", day)),
+            Err(ureq::Error::Status(400 | 401 | 403 | 500, _)) => Err(anyhow!(
+                "request rejected, the session token may be invalid or expired"
+            )),
+            Err(ureq::Error::Status(code, _)) => {
+                Err(anyhow!("request failed with status {}", code))
+            }
+            Err(e) => Err(anyhow!(e).context("request failed")),
+        }
+    }
+
+    /// Puzzle input of a day
+    pub fn input(&self, day: u8) -> Result<String> {
+        let response = self.request("GET", day, "/input")?.call();
+        Client::body(day, response)
+    }
+
+    pub fn submit(&self, day: u8, part: u8, answer: &str) -> Result<Submission> {
+        let level = part.to_string();
+        let response = self
+            .request("POST", day, "/answer")?
+            .send_form(&[("level", &level), ("answer", answer)]);
+        Submission::parse(&Client::body(day, response)?)
+    }
+}
+
+/// Download the input of a day to `path`, unless it is already there.
+/// `false` when the cached input was kept
+pub fn fetch(client: &Client, day: u8, path: &Path, force: bool) -> Result<bool> {
+    let cached = fs::metadata(path).is_ok_and(|m| m.len() > 0);
+    if cached && !force {
+        return Ok(false);
+    }
+    let input = client.input(day)?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, input).with_context(|| format!("failed to write {}", path.display()))?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        sync::{Arc, Mutex},
+        time::Instant,
+    };
+
+    const SESSION: &str = "cafe";
+
+    /// Local server answering like the site, recording the requests it got
+    struct Stub {
+        base_url: String,
+        requests: Arc<Mutex<Vec<String>>>,
+        dir: PathBuf,
+    }
+
+    fn page(message: &str) -> String {
+        format!(
+            "<html><body><main>\n<article><p>{}</p></article>\n</main></body></html>",
+            message
+        )
+    }
+
+    /// The page of the site for an answer to day 1. `too-soon` gets the rate
+    /// limit page, the right answers are 24000 then 45000
+    fn answer_page(body: &str) -> String {
+        let field = |name: &str| {
+            body.split('&')
+                .find_map(|kv| kv.strip_prefix(&format!("{}=", name)))
+                .unwrap_or("")
+                .to_string()
+        };
+        let (level, answer) = (field("level"), field("answer"));
+        let expected =
+            match level.as_str() {
+                "1" => 24000,
+                "2" => 45000,
+                _ => return page(
+                    "You don't seem to be solving the right level.  Did you already complete it? \
+                     <a href=\"/2022/day/1\">[Return to Day 1]</a>",
+                ),
+            };
+        if answer == "too-soon" {
+            return page(
+                "You gave an answer too recently; you have to wait after submitting an \
+                 answer before trying again.  You have 1m 5s left to wait. \
+                 <a href=\"/2022/day/1\">[Return to Day 1]</a>",
+            );
+        }
+        match answer.parse::<i64>() {
+            Ok(a) if a == expected => page(
+                "That's the right answer!  You are <span class=\"day-success\">one gold star</span> \
+                 closer to collecting enough star fruit. <a href=\"/2022/day/1\">[Continue to Part Two]</a>",
+            ),
+            Ok(a) => page(&format!(
+                "That's not the right answer; your answer is too {}.  If you're stuck, make sure \
+                 you're using the full input data. Please wait one minute before trying again. \
+                 <a href=\"/2022/day/1\">[Return to Day 1]</a>",
+                if a > expected { "high" } else { "low" }
+            )),
+            Err(_) => page("Something else entirely."),
+        }
+    }
+
+    fn stub(name: &str) -> Stub {
+        let server = tiny_http::Server::http("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", server.server_addr().to_ip().unwrap());
+        let requests = Arc::new(Mutex::new(vec![]));
+        let log = requests.clone();
+        thread::spawn(move || {
+            for mut request in server.incoming_requests() {
+                let mut body = String::new();
+                request.as_reader().read_to_string(&mut body).unwrap();
+                let url = request.url().to_string();
+                log.lock()
+                    .unwrap()
+                    .push(format!("{} {}", request.method(), url));
+                let logged_in = request.headers().iter().any(|h| {
+                    h.field.equiv("Cookie") && h.value.as_str() == format!("session={}", SESSION)
+                });
+                let (status, content) = match (request.method(), url.as_str()) {
+                    _ if !logged_in => (
+                        400,
+                        "Puzzle inputs differ by user.  Please log in to get your puzzle input.\n"
+                            .to_string(),
+                    ),
+                    (tiny_http::Method::Get, "/2022/day/1/input") => {
+                        (200, "1000\n2000\n\n4000\n".to_string())
+                    }
+                    (tiny_http::Method::Post, "/2022/day/1/answer") => (200, answer_page(&body)),
+                    _ => (404, "404 Not Found".to_string()),
+                };
+                let response = tiny_http::Response::from_string(content).with_status_code(status);
+                request.respond(response).unwrap();
+            }
+        });
+        let dir = env::temp_dir().join(format!("aoc-client-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        Stub {
+            base_url,
+            requests,
+            dir,
+        }
+    }
+
+    impl Stub {
+        fn client(&self, session: &str, interval: Duration) -> Client {
+            Client::new(&Config {
+                session: Some(session.to_string()),
+                base_url: self.base_url.clone() + "/",
+                interval,
+                stamp: self.dir.join("stamp"),
+            })
+            .unwrap()
+        }
+
+        fn requests(&self) -> Vec<String> {
+            self.requests.lock().unwrap().clone()
+        }
+    }
+
+    #[test]
+    fn fetch_caches_input() {
+        let stub = stub("fetch");
+        let client = stub.client(SESSION, Duration::ZERO);
+        let path = stub.dir.join("day1/input");
+        assert!(fetch(&client, 1, &path, false).unwrap());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "1000\n2000\n\n4000\n");
+        assert!(!fetch(&client, 1, &path, false).unwrap());
+        assert_eq!(stub.requests(), vec!["GET /2022/day/1/input"]);
+        assert!(fetch(&client, 1, &path, true).unwrap());
+        assert_eq!(stub.requests().len(), 2);
+    }
+
+    #[test]
+    fn errors() {
+        let stub = stub("errors");
+        let client = stub.client(SESSION, Duration::ZERO);
+        let err = client.input(2).unwrap_err();
+        assert_eq!(err.to_string(), "day 2 is not unlocked yet");
+        let err = stub.client("stale", Duration::ZERO).input(1).unwrap_err();
+        assert!(err.to_string().contains("session token"));
+        let err = client.submit(1, 1, "oops").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "unexpected answer page: Something else entirely."
+        );
+    }
+
+    #[test]
+    fn submit() {
+        let stub = stub("submit");
+        let client = stub.client(SESSION, Duration::ZERO);
+        assert_eq!(client.submit(1, 1, "24000").unwrap(), Submission::Correct);
+        assert_eq!(
+            client.submit(1, 2, "50000").unwrap(),
+            Submission::Incorrect {
+                hint: Some("too high")
+            }
+        );
+        assert_eq!(
+            client.submit(1, 2, "1").unwrap(),
+            Submission::Incorrect {
+                hint: Some("too low")
+            }
+        );
+        assert_eq!(
+            client.submit(1, 1, "too-soon").unwrap(),
+            Submission::TooSoon {
+                wait: Some(Duration::from_secs(65))
+            }
+        );
+        assert_eq!(client.submit(1, 3, "1").unwrap(), Submission::WrongLevel);
+    }
+
+    #[test]
+    fn throttle() {
+        let stub = stub("throttle");
+        let interval = Duration::from_millis(300);
+        let start = Instant::now();
+        stub.client(SESSION, interval).input(1).unwrap();
+        // A new client, as for another run of the command
+        stub.client(SESSION, interval).input(1).unwrap();
+        assert!(start.elapsed() >= interval);
+    }
+}
@@ -0,0 +1,133 @@
+use std::ops::Range;
+
+use anyhow::Result;
+use common::{
+    animate::{Animate, Simulation},
+    bench::{BenchConfig, BenchReport},
+    diff::{Differential, Mismatch},
+    gen::Generate,
+    solution::Report,
+};
+
+pub type ExecuteFn = fn(&str, &[u8]) -> Result<Report>;
+pub type BenchFn = fn(&str, &[u8], &BenchConfig) -> Result<BenchReport>;
+pub type AnimateFn = fn(&str, u8) -> Result<Box<dyn Simulation>>;
+pub type GenerateFn = fn(u64, Option<usize>) -> String;
+pub type DiffFn = fn(Range<u64>, Option<usize>) -> Result<(), Mismatch>;
+
+pub struct Day {
+    pub day: u8,
+    pub parts: u8,
+    pub execute: ExecuteFn,
+    pub bench: BenchFn,
+    pub generate: GenerateFn,
+    /// Only for the days implementing [`Animate`]
+    pub animate: Option<AnimateFn>,
+    /// Only for the days implementing [`Differential`]
+    pub diff: Option<DiffFn>,
+}
+
+impl Day {
+    pub const fn new<S: Generate>() -> Self {
+        Day {
+            day: S::DAY,
+            parts: S::PARTS,
+            execute: common::solution::execute::<S>,
+            bench: common::bench::bench::<S>,
+            generate: common::gen::generate::<S>,
+            animate: None,
+            diff: None,
+        }
+    }
+
+    pub const fn animated<S: Animate + Generate>() -> Self {
+        Day {
+            animate: Some(common::animate::simulation::<S>),
+            ..Day::new::<S>()
+        }
+    }
+
+    pub const fn differential<S: Differential>() -> Self {
+        Day {
+            diff: Some(common::diff::check::<S>),
+            ..Day::new::<S>()
+        }
+    }
+
+    pub const fn animated_differential<S: Animate + Differential>() -> Self {
+        Day {
+            animate: Some(common::animate::simulation::<S>),
+            ..Day::differential::<S>()
+        }
+    }
+}
+
+pub const DAYS: &[Day] = &[
+    Day::new::<day1::Day1>(),
+    Day::new::<day2::Day2>(),
+    Day::new::<day3::Day3>(),
+    Day::new::<day4::Day4>(),
+    Day::animated_differential::<day5::Day5>(),
+    Day::new::<day6::Day6>(),
+    Day::new::<day7::Day7>(),
+    Day::animated::<day8::Day8>(),
+    Day::animated::<day9::Day9>(),
+    Day::animated::<day10::Day10>(),
+    Day::new::<day11::Day11>(),
+    Day::animated::<day12::Day12>(),
+    Day::new::<day13::Day13>(),
+    Day::animated::<day14::Day14>(),
+    Day::animated::<day15::Day15>(),
+    Day::differential::<day16::Day16>(),
+    Day::animated::<day17::Day17>(),
+    Day::new::<day18::Day18>(),
+    Day::new::<day19::Day19>(),
+    Day::new::<day20::Day20>(),
+    Day::new::<day21::Day21>(),
+    Day::animated::<day22::Day22>(),
+    Day::animated::<day23::Day23>(),
+    Day::animated::<day24::Day24>(),
+    Day::new::<day25::Day25>(),
+];
+
+pub fn get(day: u8) -> Option<&'static Day> {
+    DAYS.iter().find(|d| d.day == day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Input size and parts for the days too slow for debug builds otherwise
+    const LIMITS: &[(u8, Option<usize>, &[u8])] = &[
+        (15, None, &[2]),
+        (16, Some(24), &[1, 2]),
+        (17, Some(500), &[1, 2]),
+        (19, Some(3), &[1]),
+        (20, Some(500), &[1, 2]),
+        (22, Some(7), &[1, 2]),
+        (23, Some(20), &[1, 2]),
+        (24, Some(30), &[1, 2]),
+    ];
+
+    /// Every day answers on an input of its generator
+    #[test]
+    fn generated_inputs() {
+        for day in DAYS {
+            let all: Vec<u8> = (1..=day.parts).collect();
+            let (size, parts) = match LIMITS.iter().find(|(d, _, _)| *d == day.day) {
+                Some(&(_, size, parts)) => (size, parts),
+                None => (None, all.as_slice()),
+            };
+            let input = (day.generate)(1, size);
+            let report =
+                (day.execute)(&input, parts).unwrap_or_else(|e| panic!("day {}: {:#}", day.day, e));
+            assert_eq!(report.parts.len(), parts.len());
+            for part in report.parts {
+                if let Err(e) = part.answer {
+                    panic!("day {} part {}: {:#}", day.day, part.part, e);
+                }
+            }
+        }
+    }
+}
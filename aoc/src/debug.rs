@@ -0,0 +1,282 @@
+use std::{collections::BTreeMap, io::Write};
+
+use anyhow::Result;
+use common::animate::Simulation;
+use crossterm::{
+    cursor,
+    event::{self, Event, KeyCode, KeyEventKind, KeyModifiers},
+    style::{self, Color},
+    terminal, QueueableCommand,
+};
+
+use crate::animate::{self, Screen};
+
+/// Steps between two copies of the simulation kept to go back quickly
+const CHECKPOINT_EVERY: usize = 64;
+
+/// Columns of the state panel, on the right of the frame
+const PANEL_WIDTH: usize = 36;
+
+/// The steps of a simulation, gone through in both directions. Going back
+/// restarts from the closest earlier checkpoint, or from the beginning when
+/// the simulation cannot be copied
+pub struct Timeline<F> {
+    start: F,
+    sim: Box<dyn Simulation>,
+    step: usize,
+    /// Last step, once reached
+    end: Option<usize>,
+    checkpoints: BTreeMap<usize, Box<dyn Simulation>>,
+}
+
+impl<F: Fn() -> Result<Box<dyn Simulation>>> Timeline<F> {
+    pub fn new(start: F) -> Result<Self> {
+        let sim = start()?;
+        let checkpoints = sim.snapshot().map(|s| (0, s)).into_iter().collect();
+        Ok(Timeline {
+            start,
+            sim,
+            step: 0,
+            end: None,
+            checkpoints,
+        })
+    }
+
+    pub fn step(&self) -> usize {
+        self.step
+    }
+
+    pub fn end(&self) -> Option<usize> {
+        self.end
+    }
+
+    pub fn sim(&self) -> &dyn Simulation {
+        self.sim.as_ref()
+    }
+
+    /// Go to step `target`, or to the last one when the simulation ends before
+    pub fn seek(&mut self, target: usize) -> Result<()> {
+        let target = self.end.map_or(target, |end| target.min(end));
+        if target < self.step {
+            let checkpoint = self
+                .checkpoints
+                .range(..=target)
+                .next_back()
+                .and_then(|(&step, sim)| Some((step, sim.snapshot()?)));
+            (self.step, self.sim) = match checkpoint {
+                Some(checkpoint) => checkpoint,
+                None => (0, (self.start)()?),
+            };
+        }
+        while self.step < target {
+            if !self.sim.step() {
+                self.end = Some(self.step);
+                break;
+            }
+            self.step += 1;
+            if self.step.is_multiple_of(CHECKPOINT_EVERY)
+                && !self.checkpoints.contains_key(&self.step)
+            {
+                if let Some(sim) = self.sim.snapshot() {
+                    self.checkpoints.insert(self.step, sim);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+struct Debugger<F> {
+    timeline: Timeline<F>,
+    /// Count typed before a command
+    count: Option<usize>,
+    message: String,
+}
+
+impl<F: Fn() -> Result<Box<dyn Simulation>>> Debugger<F> {
+    fn draw(&self, screen: &mut Screen) -> Result<()> {
+        let sim = self.timeline.sim();
+        let frame = sim.frame();
+        let (cols, rows) = terminal::size()?;
+        let (cols, rows) = (cols as usize, (rows as usize).saturating_sub(2));
+        let frame_cols = cols.saturating_sub(PANEL_WIDTH + 1);
+        let viewport = animate::auto_viewport(&frame, frame_cols, rows);
+        let width = ((viewport.max.x - viewport.min.x + 1) as usize).min(frame_cols);
+        let height = ((viewport.max.y - viewport.min.y + 1) as usize).min(rows);
+
+        let out = &mut screen.out;
+        animate::draw_frame(out, &frame, viewport.min, width, height)?;
+
+        let end = match self.timeline.end() {
+            Some(end) => end.to_string(),
+            None => "?".to_string(),
+        };
+        let mut lines = vec![
+            ("step".to_string(), self.timeline.step().to_string()),
+            ("end".to_string(), end),
+            (String::new(), String::new()),
+        ];
+        lines.extend(sim.inspect().into_iter().map(|(k, v)| (k.to_string(), v)));
+        if !frame.status.is_empty() {
+            lines.push((String::new(), String::new()));
+            lines.push((String::new(), frame.status.clone()));
+        }
+        let key_width = lines.iter().map(|(k, _)| k.len()).max().unwrap_or(0);
+        let x = cols.saturating_sub(PANEL_WIDTH) as u16;
+        for (y, (key, value)) in lines.iter().take(rows).enumerate() {
+            let line = match key.is_empty() {
+                true => value.clone(),
+                false => format!("{:>w$}  {}", key, value, w = key_width),
+            };
+            let line: String = line.chars().take(PANEL_WIDTH).collect();
+            out.queue(cursor::MoveTo(x, y as u16))?
+                .queue(style::Print(line))?
+                .queue(terminal::Clear(terminal::ClearType::UntilNewLine))?;
+        }
+
+        let count = self.count.map(|c| format!("{} ", c)).unwrap_or_default();
+        out.queue(cursor::MoveTo(0, rows as u16))?
+            .queue(style::Print(format!("{}{}", count, self.message)))?
+            .queue(terminal::Clear(terminal::ClearType::UntilNewLine))?
+            .queue(cursor::MoveTo(0, rows as u16 + 1))?
+            .queue(style::SetForegroundColor(Color::DarkGrey))?
+            .queue(style::Print(
+                "[N] n/l/right: forward  [N] b/h/left: back  [N] g: go to step  G: end  q: quit",
+            ))?
+            .queue(style::ResetColor)?;
+        out.flush()?;
+        Ok(())
+    }
+
+    fn seek(&mut self, target: usize) -> Result<()> {
+        self.timeline.seek(target)?;
+        self.message = match self.timeline.end() {
+            Some(end) if target > end => format!("the simulation ends at step {}", end),
+            _ => String::new(),
+        };
+        Ok(())
+    }
+
+    /// Returns `false` when the user quits
+    fn handle(&mut self, code: KeyCode, modifiers: KeyModifiers) -> Result<bool> {
+        let count = self.count.take();
+        let step = self.timeline.step();
+        match code {
+            KeyCode::Char('q') | KeyCode::Esc => return Ok(false),
+            KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => return Ok(false),
+            KeyCode::Char(c @ '0'..='9') => {
+                let digit = c.to_digit(10).unwrap_or(0) as usize;
+                self.count = Some(count.unwrap_or(0).saturating_mul(10).saturating_add(digit));
+            }
+            KeyCode::Backspace => self.count = count.map(|c| c / 10).filter(|&c| c > 0),
+            KeyCode::Char('n') | KeyCode::Char('l') | KeyCode::Right => {
+                self.seek(step.saturating_add(count.unwrap_or(1)))?
+            }
+            KeyCode::Char('b') | KeyCode::Char('h') | KeyCode::Left => {
+                self.seek(step.saturating_sub(count.unwrap_or(1)))?
+            }
+            KeyCode::Char('g') | KeyCode::Home => self.seek(count.unwrap_or(0))?,
+            KeyCode::Char('G') | KeyCode::End => self.seek(usize::MAX)?,
+            _ => {}
+        }
+        Ok(true)
+    }
+}
+
+/// Step through the simulation in the terminal until the user quits
+pub fn debug(start: impl Fn() -> Result<Box<dyn Simulation>>) -> Result<()> {
+    let mut debugger = Debugger {
+        timeline: Timeline::new(start)?,
+        count: None,
+        message: String::new(),
+    };
+    let mut screen = Screen::enter()?;
+    loop {
+        debugger.draw(&mut screen)?;
+        if let Event::Key(key) = event::read()? {
+            if key.kind != KeyEventKind::Release && !debugger.handle(key.code, key.modifiers)? {
+                return Ok(());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::{animate::Frame, point::Point};
+    use std::cell::Cell;
+
+    /// Counts up to a limit, copyable or not
+    #[derive(Clone)]
+    struct Counter {
+        n: usize,
+        limit: usize,
+        copyable: bool,
+    }
+
+    impl Simulation for Counter {
+        fn step(&mut self) -> bool {
+            if self.n == self.limit {
+                return false;
+            }
+            self.n += 1;
+            true
+        }
+
+        fn frame(&self) -> Frame {
+            Frame::new(Point::new(0, 0), 1, 1).with_status(self.n.to_string())
+        }
+
+        fn snapshot(&self) -> Option<Box<dyn Simulation>> {
+            match self.copyable {
+                true => Some(Box::new(self.clone())),
+                false => None,
+            }
+        }
+    }
+
+    fn state<F: Fn() -> Result<Box<dyn Simulation>>>(timeline: &Timeline<F>) -> String {
+        timeline.sim().frame().status
+    }
+
+    #[test]
+    fn seek_both_ways() {
+        for copyable in [true, false] {
+            let starts = Cell::new(0);
+            let start = || {
+                starts.set(starts.get() + 1);
+                Ok(Box::new(Counter {
+                    n: 0,
+                    limit: 200,
+                    copyable,
+                }) as Box<dyn Simulation>)
+            };
+            let mut timeline = Timeline::new(start).unwrap();
+            timeline.seek(150).unwrap();
+            assert_eq!(
+                (timeline.step(), state(&timeline)),
+                (150, "150".to_string())
+            );
+            assert_eq!(timeline.end(), None);
+
+            timeline.seek(100).unwrap();
+            assert_eq!(
+                (timeline.step(), state(&timeline)),
+                (100, "100".to_string())
+            );
+            timeline.seek(3).unwrap();
+            assert_eq!((timeline.step(), state(&timeline)), (3, "3".to_string()));
+            // Copies replace the restarts
+            assert_eq!(starts.get(), if copyable { 1 } else { 3 });
+
+            timeline.seek(usize::MAX).unwrap();
+            assert_eq!((timeline.step(), timeline.end()), (200, Some(200)));
+            timeline.seek(250).unwrap();
+            assert_eq!(
+                (timeline.step(), state(&timeline)),
+                (200, "200".to_string())
+            );
+        }
+    }
+}
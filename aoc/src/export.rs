@@ -0,0 +1,581 @@
+use std::{
+    borrow::Cow,
+    fs::File,
+    io::BufWriter,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use anyhow::{anyhow, Context, Error, Result};
+use common::{
+    animate::{Colour, Frame, Simulation},
+    point::Point,
+};
+
+type Rgb = [u8; 3];
+
+/// Shades of [`Colour::Scale`] kept in the GIF palettes
+const GIF_SHADES: usize = 64;
+
+/// Colours of the exported images
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Palette {
+    /// Around and outside of the frames
+    background: Rgb,
+    default: Rgb,
+    grey: Rgb,
+    red: Rgb,
+    green: Rgb,
+    yellow: Rgb,
+    blue: Rgb,
+    magenta: Rgb,
+    cyan: Rgb,
+    white: Rgb,
+    /// Ends of the [`Colour::Scale`] gradient
+    low: Rgb,
+    high: Rgb,
+}
+
+impl Palette {
+    pub fn dark() -> Self {
+        Palette {
+            background: [0x0f, 0x0f, 0x23],
+            default: [0xcc, 0xcc, 0xcc],
+            grey: [0x33, 0x33, 0x40],
+            red: [0xe0, 0x3c, 0x3c],
+            green: [0x00, 0xcc, 0x00],
+            yellow: [0xff, 0xff, 0x66],
+            blue: [0x33, 0x66, 0xcc],
+            magenta: [0xcc, 0x44, 0xcc],
+            cyan: [0x44, 0xcc, 0xcc],
+            white: [0xf0, 0xf0, 0xf0],
+            low: [0x10, 0x30, 0x10],
+            high: [0xa0, 0xff, 0xa0],
+        }
+    }
+
+    pub fn light() -> Self {
+        Palette {
+            background: [0xff, 0xff, 0xff],
+            default: [0x20, 0x20, 0x20],
+            grey: [0xe8, 0xe8, 0xe8],
+            red: [0xd0, 0x20, 0x20],
+            green: [0x20, 0x90, 0x20],
+            yellow: [0xe0, 0xa0, 0x00],
+            blue: [0x20, 0x50, 0xc0],
+            magenta: [0xa0, 0x20, 0xa0],
+            cyan: [0x10, 0x90, 0x90],
+            white: [0x40, 0x40, 0x40],
+            low: [0xd0, 0xf0, 0xd0],
+            high: [0x10, 0x50, 0x10],
+        }
+    }
+
+    fn entry(&mut self, name: &str) -> Option<&mut Rgb> {
+        Some(match name {
+            "background" => &mut self.background,
+            "default" => &mut self.default,
+            "grey" => &mut self.grey,
+            "red" => &mut self.red,
+            "green" => &mut self.green,
+            "yellow" => &mut self.yellow,
+            "blue" => &mut self.blue,
+            "magenta" => &mut self.magenta,
+            "cyan" => &mut self.cyan,
+            "white" => &mut self.white,
+            "low" => &mut self.low,
+            "high" => &mut self.high,
+            _ => return None,
+        })
+    }
+
+    fn rgb(&self, colour: Option<Colour>) -> Rgb {
+        match colour {
+            None => self.background,
+            Some(Colour::Default) => self.default,
+            Some(Colour::Grey) => self.grey,
+            Some(Colour::Red) => self.red,
+            Some(Colour::Green) => self.green,
+            Some(Colour::Yellow) => self.yellow,
+            Some(Colour::Blue) => self.blue,
+            Some(Colour::Magenta) => self.magenta,
+            Some(Colour::Cyan) => self.cyan,
+            Some(Colour::White) => self.white,
+            Some(Colour::Scale(v)) => {
+                let mix = |a: u8, b: u8| (a as u32 * (255 - v as u32) + b as u32 * v as u32) / 255;
+                [0, 1, 2].map(|i| mix(self.low[i], self.high[i]) as u8)
+            }
+        }
+    }
+
+    /// Index in [`Palette::gif_table`]
+    fn gif_index(colour: Option<Colour>) -> u8 {
+        match colour {
+            None => 0,
+            Some(Colour::Default) => 1,
+            Some(Colour::Grey) => 2,
+            Some(Colour::Red) => 3,
+            Some(Colour::Green) => 4,
+            Some(Colour::Yellow) => 5,
+            Some(Colour::Blue) => 6,
+            Some(Colour::Magenta) => 7,
+            Some(Colour::Cyan) => 8,
+            Some(Colour::White) => 9,
+            Some(Colour::Scale(v)) => 10 + (v as usize * (GIF_SHADES - 1) / 255) as u8,
+        }
+    }
+
+    /// Global colour table of the GIFs, the shades being reduced to [`GIF_SHADES`]
+    fn gif_table(&self) -> Vec<u8> {
+        let named = [
+            None,
+            Some(Colour::Default),
+            Some(Colour::Grey),
+            Some(Colour::Red),
+            Some(Colour::Green),
+            Some(Colour::Yellow),
+            Some(Colour::Blue),
+            Some(Colour::Magenta),
+            Some(Colour::Cyan),
+            Some(Colour::White),
+        ];
+        let shades =
+            (0..GIF_SHADES).map(|i| Some(Colour::Scale((i * 255 / (GIF_SHADES - 1)) as u8)));
+        named
+            .into_iter()
+            .chain(shades)
+            .flat_map(|c| self.rgb(c))
+            .collect()
+    }
+}
+
+impl FromStr for Palette {
+    type Err = Error;
+
+    /// `dark` or `light`, optionally followed by overrides: `dark,red=ff8000,background=000000`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut specs = s.split(',');
+        let mut palette = match specs.next().unwrap_or_default().trim() {
+            "dark" => Palette::dark(),
+            "light" => Palette::light(),
+            name => return Err(anyhow!("unknown palette {}, expected dark or light", name)),
+        };
+        for spec in specs {
+            let (name, hex) = spec.split_once('=').context("expected name=rrggbb")?;
+            let entry = palette
+                .entry(name.trim())
+                .with_context(|| format!("unknown palette colour {}", name))?;
+            let hex = hex.trim().trim_start_matches('#');
+            if hex.len() != 6 {
+                return Err(anyhow!("invalid colour {}, expected rrggbb", hex));
+            }
+            for (i, c) in entry.iter_mut().enumerate() {
+                *c = u8::from_str_radix(&hex[2 * i..2 * i + 2], 16)
+                    .with_context(|| format!("invalid colour {}", hex))?;
+            }
+        }
+        Ok(palette)
+    }
+}
+
+pub struct ExportOptions {
+    pub output: PathBuf,
+    /// Side of the square drawn for each pixel of the frames
+    pub scale: usize,
+    pub palette: Palette,
+    /// Keep one step out of `every`, defaults to a value fitting `max_frames`
+    pub every: Option<usize>,
+    pub max_frames: usize,
+    /// Display time of each GIF frame, in milliseconds
+    pub delay: u16,
+}
+
+/// What gets written, decided by the output path
+enum Output {
+    /// Final state as a single PNG
+    Png,
+    /// One PNG per kept step, the `{}` in the path replaced by the step number
+    PngSequence,
+    /// Every kept step in an animated GIF
+    Gif,
+}
+
+impl Output {
+    fn of(path: &Path) -> Result<Self> {
+        let extension = path.extension().and_then(|e| e.to_str());
+        match extension.map(|e| e.to_ascii_lowercase()).as_deref() {
+            Some("png") if path.to_string_lossy().contains("{}") => Ok(Output::PngSequence),
+            Some("png") => Ok(Output::Png),
+            Some("gif") => Ok(Output::Gif),
+            _ => Err(anyhow!(
+                "unsupported output {}, expected a .png or .gif file",
+                path.display()
+            )),
+        }
+    }
+}
+
+/// Region covered by the images, in the coordinates of the frames
+#[derive(Debug, Clone, Copy)]
+struct Canvas {
+    origin: Point<isize>,
+    width: usize,
+    height: usize,
+}
+
+impl Canvas {
+    fn of(frame: &Frame) -> Self {
+        Canvas {
+            origin: frame.origin,
+            width: frame.width,
+            height: frame.height,
+        }
+    }
+
+    /// Smallest canvas holding both
+    fn union(self, other: Canvas) -> Self {
+        if self.width == 0 || self.height == 0 {
+            return other;
+        }
+        if other.width == 0 || other.height == 0 {
+            return self;
+        }
+        let min = Point::new(
+            self.origin.x.min(other.origin.x),
+            self.origin.y.min(other.origin.y),
+        );
+        let max = Point::new(
+            (self.origin.x + self.width as isize).max(other.origin.x + other.width as isize),
+            (self.origin.y + self.height as isize).max(other.origin.y + other.height as isize),
+        );
+        Canvas {
+            origin: min,
+            width: (max.x - min.x) as usize,
+            height: (max.y - min.y) as usize,
+        }
+    }
+
+    /// Colours of the scaled image, row by row
+    fn colours(&self, frame: &Frame, scale: usize) -> Vec<Option<Colour>> {
+        let mut colours = Vec::with_capacity(self.width * self.height * scale * scale);
+        for y in 0..self.height as isize {
+            let row: Vec<_> = (0..self.width as isize)
+                .map(|x| frame.get(self.origin + Point::new(x, y)).map(|p| p.colour))
+                .collect();
+            for _ in 0..scale {
+                for &c in row.iter() {
+                    colours.extend(std::iter::repeat_n(c, scale));
+                }
+            }
+        }
+        colours
+    }
+}
+
+fn write_png(path: &Path, canvas: &Canvas, frame: &Frame, options: &ExportOptions) -> Result<()> {
+    let (width, height) = (canvas.width * options.scale, canvas.height * options.scale);
+    let file =
+        File::create(path).with_context(|| format!("failed to create {}", path.display()))?;
+    let mut encoder = png::Encoder::new(BufWriter::new(file), width as u32, height as u32);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+    let data: Vec<u8> = canvas
+        .colours(frame, options.scale)
+        .into_iter()
+        .flat_map(|c| options.palette.rgb(c))
+        .collect();
+    encoder.write_header()?.write_image_data(&data)?;
+    Ok(())
+}
+
+/// Run the simulation to its end, returning the final frame and the canvas
+/// holding every frame along the way
+fn survey(sim: &mut dyn Simulation) -> (Frame, Canvas, usize) {
+    let mut frame = sim.frame();
+    let mut canvas = Canvas::of(&frame);
+    let mut steps = 0;
+    while sim.step() {
+        steps += 1;
+        frame = sim.frame();
+        canvas = canvas.union(Canvas::of(&frame));
+    }
+    (frame, canvas, steps)
+}
+
+/// Write the images of a simulation, returning the number of frames written.
+///
+/// `start` creates the simulation: it is run twice for animations, once to
+/// find the area covered by all the frames and once to draw them.
+pub fn export(
+    start: impl Fn() -> Result<Box<dyn Simulation>>,
+    options: &ExportOptions,
+) -> Result<usize> {
+    let output = Output::of(&options.output)?;
+    let (last, canvas, steps) = survey(start()?.as_mut());
+    if canvas.width == 0 || canvas.height == 0 {
+        return Err(anyhow!("the simulation has nothing to draw"));
+    }
+    if let Output::Png = output {
+        write_png(&options.output, &Canvas::of(&last), &last, options)?;
+        return Ok(1);
+    }
+
+    let every = options
+        .every
+        .unwrap_or_else(|| (steps + 1).div_ceil(options.max_frames.max(1)))
+        .max(1);
+    let mut gif = match output {
+        Output::Gif => {
+            let (width, height) = (canvas.width * options.scale, canvas.height * options.scale);
+            if width > u16::MAX as usize || height > u16::MAX as usize {
+                return Err(anyhow!(
+                    "a GIF cannot be larger than 65535x65535, got {}x{}",
+                    width,
+                    height
+                ));
+            }
+            let file = File::create(&options.output)
+                .with_context(|| format!("failed to create {}", options.output.display()))?;
+            let table = options.palette.gif_table();
+            let mut encoder =
+                gif::Encoder::new(BufWriter::new(file), width as u16, height as u16, &table)?;
+            encoder.set_repeat(gif::Repeat::Infinite)?;
+            Some((encoder, width as u16, height as u16))
+        }
+        _ => None,
+    };
+
+    let mut sim = start()?;
+    let mut written = 0;
+    for step in 0..=steps {
+        if step > 0 {
+            sim.step();
+        }
+        if step % every != 0 && step != steps {
+            continue;
+        }
+        let frame = sim.frame();
+        match &mut gif {
+            Some((encoder, width, height)) => {
+                let buffer = canvas
+                    .colours(&frame, options.scale)
+                    .into_iter()
+                    .map(Palette::gif_index)
+                    .collect::<Vec<_>>();
+                let image = gif::Frame {
+                    width: *width,
+                    height: *height,
+                    delay: options.delay / 10,
+                    buffer: Cow::Owned(buffer),
+                    ..Default::default()
+                };
+                encoder.write_frame(&image)?;
+            }
+            None => {
+                let name = options
+                    .output
+                    .to_string_lossy()
+                    .replace("{}", &format!("{:06}", step));
+                write_png(Path::new(&name), &canvas, &frame, options)?;
+            }
+        }
+        written += 1;
+    }
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use common::animate::Pixel;
+
+    use super::*;
+
+    /// A red pixel moving right for `steps` steps, over a grey one
+    struct Walk {
+        step: usize,
+        steps: usize,
+    }
+
+    impl Simulation for Walk {
+        fn step(&mut self) -> bool {
+            if self.step == self.steps {
+                return false;
+            }
+            self.step += 1;
+            true
+        }
+
+        fn frame(&self) -> Frame {
+            let mut frame = Frame::new(Point::new(self.step as isize, 0), 2, 1);
+            frame.set(frame.origin, Pixel::new('#', Colour::Red));
+            frame
+        }
+    }
+
+    fn walk(steps: usize) -> impl Fn() -> Result<Box<dyn Simulation>> {
+        move || Ok(Box::new(Walk { step: 0, steps }) as Box<dyn Simulation>)
+    }
+
+    fn options(dir: &Path, name: &str) -> ExportOptions {
+        ExportOptions {
+            output: dir.join(name),
+            scale: 2,
+            palette: Palette::dark(),
+            every: None,
+            max_frames: 100,
+            delay: 50,
+        }
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("aoc-export-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn palettes() {
+        let palette: Palette = "light, red=#ff8000 ,background=000000".parse().unwrap();
+        assert_eq!(palette.rgb(Some(Colour::Red)), [0xff, 0x80, 0x00]);
+        assert_eq!(palette.rgb(None), [0, 0, 0]);
+        assert_eq!(palette.rgb(Some(Colour::Blue)), Palette::light().blue);
+        assert_eq!(palette.rgb(Some(Colour::Scale(0))), palette.low);
+        assert_eq!(palette.rgb(Some(Colour::Scale(255))), palette.high);
+
+        let error = |s: &str| s.parse::<Palette>().unwrap_err().to_string();
+        assert_eq!(
+            error("sepia"),
+            "unknown palette sepia, expected dark or light"
+        );
+        assert_eq!(error("dark,red"), "expected name=rrggbb");
+        assert_eq!(error("dark,pink=ffffff"), "unknown palette colour pink");
+        assert_eq!(error("dark,red=fff"), "invalid colour fff, expected rrggbb");
+        assert_eq!(error("dark,red=gg0000"), "invalid colour gg0000");
+    }
+
+    #[test]
+    fn gif_colours() {
+        let table = Palette::dark().gif_table();
+        assert_eq!(table.len(), 3 * (10 + GIF_SHADES));
+        for colour in [None, Some(Colour::Cyan), Some(Colour::Scale(255))] {
+            let i = Palette::gif_index(colour) as usize;
+            assert_eq!(table[3 * i..3 * i + 3], Palette::dark().rgb(colour));
+        }
+    }
+
+    #[test]
+    fn outputs() {
+        let output = |path: &str| Output::of(Path::new(path)).map_err(|e| e.to_string());
+        assert!(matches!(output("a.PNG"), Ok(Output::Png)));
+        assert!(matches!(output("frames/{}.png"), Ok(Output::PngSequence)));
+        assert!(matches!(output("a.gif"), Ok(Output::Gif)));
+        assert_eq!(
+            output("a.jpg").err().unwrap(),
+            "unsupported output a.jpg, expected a .png or .gif file"
+        );
+    }
+
+    #[test]
+    fn canvas() {
+        let frame = Walk { step: 0, steps: 0 }.frame();
+        let canvas = Canvas::of(&frame).union(Canvas {
+            origin: Point::new(3, -1),
+            width: 1,
+            height: 1,
+        });
+        assert_eq!(
+            (canvas.origin, canvas.width, canvas.height),
+            (Point::new(0, -1), 4, 2)
+        );
+        let empty = Canvas::of(&Frame::new(Point::new(9, 9), 0, 0));
+        assert_eq!(empty.union(Canvas::of(&frame)).origin, Point::new(0, 0));
+
+        // Scaled row by row, outside of the frame is the background
+        let red = Some(Colour::Red);
+        let grey = Some(Colour::Grey);
+        let colours = Canvas::of(&frame).union(empty).colours(&frame, 2);
+        assert_eq!(colours, vec![red, red, grey, grey, red, red, grey, grey]);
+        let wide = Canvas {
+            origin: Point::new(-1, 0),
+            width: 2,
+            height: 1,
+        };
+        assert_eq!(wide.colours(&frame, 1), vec![None, red]);
+    }
+
+    #[test]
+    fn png() {
+        let dir = temp_dir("png");
+        let options = options(&dir, "last.png");
+        assert_eq!(export(walk(3), &options).unwrap(), 1);
+
+        let decoder = png::Decoder::new(File::open(&options.output).unwrap());
+        let mut reader = decoder.read_info().unwrap();
+        let mut data = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut data).unwrap();
+        // Only the last frame, which is 2x1
+        assert_eq!((info.width, info.height), (4, 2));
+        assert_eq!(data[..3], Palette::dark().red);
+        assert_eq!(data[6..9], Palette::dark().grey);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn png_sequence() {
+        let dir = temp_dir("sequence");
+        let mut options = options(&dir, "{}.png");
+        options.every = Some(2);
+        // Every other step, and the last one
+        assert_eq!(export(walk(5), &options).unwrap(), 4);
+        let mut names: Vec<String> = std::fs::read_dir(&dir)
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().to_string())
+            .collect();
+        names.sort();
+        assert_eq!(
+            names,
+            ["000000.png", "000002.png", "000004.png", "000005.png"]
+        );
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn gif() {
+        let dir = temp_dir("gif");
+        let mut options = options(&dir, "walk.gif");
+        options.max_frames = 3;
+        // 10 steps and the start, one step out of 4 is kept
+        assert_eq!(export(walk(10), &options).unwrap(), 4);
+
+        let file = File::open(&options.output).unwrap();
+        let mut decoder = gif::DecodeOptions::new().read_info(file).unwrap();
+        // The canvas covers every frame
+        assert_eq!((decoder.width(), decoder.height()), (24, 2));
+        let mut frames = 0;
+        while let Some(frame) = decoder.read_next_frame().unwrap() {
+            assert_eq!(frame.delay, 5);
+            frames += 1;
+        }
+        assert_eq!(frames, 4);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn nothing_to_draw() {
+        struct Empty;
+        impl Simulation for Empty {
+            fn step(&mut self) -> bool {
+                false
+            }
+            fn frame(&self) -> Frame {
+                Frame::new(Point::default(), 0, 0)
+            }
+        }
+        let dir = temp_dir("empty");
+        let start = || Ok(Box::new(Empty) as Box<dyn Simulation>);
+        let error = export(start, &options(&dir, "a.gif")).unwrap_err();
+        assert_eq!(error.to_string(), "the simulation has nothing to draw");
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
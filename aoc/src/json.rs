@@ -0,0 +1,240 @@
+use anyhow::Error;
+use common::{alloc::AllocStats, parse::ParseError, solution::Report, Answer};
+use serde::Serialize;
+use serde_json::Value;
+
+/// Output of `aoc run --format json`
+#[derive(Debug, Default, Serialize)]
+pub struct RunOutput {
+    pub days: Vec<DayOutput>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DayOutput {
+    pub day: u8,
+    /// Missing when the input could not be loaded or parsed
+    pub parse_ns: Option<u128>,
+    /// Only with `--alloc`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parse_alloc: Option<AllocOutput>,
+    pub parts: Vec<PartOutput>,
+    pub error: Option<ErrorOutput>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PartOutput {
+    pub part: u8,
+    pub answer: Option<Value>,
+    #[serde(rename = "type")]
+    pub answer_type: Option<&'static str>,
+    pub time_ns: u128,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alloc: Option<AllocOutput>,
+    pub error: Option<ErrorOutput>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AllocOutput {
+    pub allocations: u64,
+    pub bytes: u64,
+    pub peak_bytes: u64,
+}
+
+impl From<AllocStats> for AllocOutput {
+    fn from(a: AllocStats) -> Self {
+        AllocOutput {
+            allocations: a.allocations,
+            bytes: a.bytes,
+            peak_bytes: a.peak,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ErrorOutput {
+    /// Outermost error message
+    pub message: String,
+    /// Underlying causes, outermost first
+    pub causes: Vec<String>,
+    /// Location of the offending text for parse errors, 1-based
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+}
+
+impl From<&Error> for ErrorOutput {
+    fn from(e: &Error) -> Self {
+        let parse_error = e.chain().find_map(|c| c.downcast_ref::<ParseError>());
+        ErrorOutput {
+            message: e.to_string(),
+            causes: e.chain().skip(1).map(|c| c.to_string()).collect(),
+            line: parse_error.and_then(|p| p.line),
+            column: parse_error.and_then(|p| p.column),
+        }
+    }
+}
+
+/// Numbers stay numbers, everything else is a string
+fn answer_value(answer: &Answer) -> Value {
+    match answer {
+        Answer::Int(v) => Value::from(*v),
+        Answer::UInt(v) => Value::from(*v),
+        Answer::Float(v) => Value::from(*v),
+        Answer::Text(v) => Value::from(v.as_str()),
+    }
+}
+
+impl DayOutput {
+    pub fn from_report(report: &Report) -> Self {
+        let parts = report
+            .parts
+            .iter()
+            .map(|part| {
+                let (answer, answer_type, error) = match &part.answer {
+                    Ok(answer) => (Some(answer_value(answer)), Some(answer.type_name()), None),
+                    Err(e) => (None, None, Some(e.into())),
+                };
+                PartOutput {
+                    part: part.part,
+                    answer,
+                    answer_type,
+                    time_ns: part.time.as_nanos(),
+                    alloc: part.alloc.map(AllocOutput::from),
+                    error,
+                }
+            })
+            .collect();
+        DayOutput {
+            day: report.day,
+            parse_ns: Some(report.parse_time.as_nanos()),
+            parse_alloc: report.parse_alloc.map(AllocOutput::from),
+            parts,
+            error: None,
+        }
+    }
+
+    pub fn from_error(day: u8, e: &Error) -> Self {
+        DayOutput {
+            day,
+            parse_ns: None,
+            parse_alloc: None,
+            parts: vec![],
+            error: Some(e.into()),
+        }
+    }
+
+    pub fn success(&self) -> bool {
+        self.error.is_none() && self.parts.iter().all(|p| p.error.is_none())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use anyhow::Context;
+    use common::solution::PartReport;
+    use serde_json::json;
+
+    use super::*;
+
+    fn part(part: u8, answer: anyhow::Result<Answer>) -> PartReport {
+        PartReport {
+            part,
+            answer,
+            time: Duration::from_nanos(200),
+            alloc: None,
+        }
+    }
+
+    #[test]
+    fn report_shape() {
+        let report = Report {
+            day: 3,
+            parse_time: Duration::from_nanos(100),
+            parse_alloc: None,
+            parts: vec![
+                part(1, Ok(Answer::UInt(42))),
+                part(2, Ok(Answer::Text("ABC".to_string()))),
+            ],
+        };
+        let output = DayOutput::from_report(&report);
+        assert!(output.success());
+        let expected = json!({
+            "day": 3,
+            "parse_ns": 100,
+            "parts": [
+                {"part": 1, "answer": 42, "type": "uint", "time_ns": 200, "error": null},
+                {"part": 2, "answer": "ABC", "type": "text", "time_ns": 200, "error": null},
+            ],
+            "error": null,
+        });
+        assert_eq!(serde_json::to_value(&output).unwrap(), expected);
+    }
+
+    #[test]
+    fn allocations() {
+        let stats = AllocStats {
+            allocations: 2,
+            bytes: 64,
+            peak: 32,
+        };
+        let mut answer = part(1, Ok(Answer::Int(-1)));
+        answer.alloc = Some(stats);
+        let report = Report {
+            day: 1,
+            parse_time: Duration::ZERO,
+            parse_alloc: Some(AllocStats::default()),
+            parts: vec![answer],
+        };
+        let value = serde_json::to_value(DayOutput::from_report(&report)).unwrap();
+        let alloc = json!({"allocations": 2, "bytes": 64, "peak_bytes": 32});
+        assert_eq!(value["parts"][0]["alloc"], alloc);
+        assert_eq!(value["parts"][0]["answer"], json!(-1));
+        assert_eq!(value["parse_alloc"]["allocations"], json!(0));
+    }
+
+    #[test]
+    fn errors() {
+        let parse_error = ParseError::new("invalid number", "x").at(2, 5);
+        let error = Err::<(), _>(parse_error)
+            .context("failed to parse input")
+            .unwrap_err();
+        let output = DayOutput::from_error(7, &error);
+        assert!(!output.success());
+        let expected = json!({
+            "day": 7,
+            "parse_ns": null,
+            "parts": [],
+            "error": {
+                "message": "failed to parse input",
+                "causes": ["line 2, column 5: invalid number in \"x\""],
+                "line": 2,
+                "column": 5,
+            },
+        });
+        assert_eq!(serde_json::to_value(&output).unwrap(), expected);
+
+        // A failed part fails the day, without location for other errors
+        let report = Report {
+            day: 7,
+            parse_time: Duration::ZERO,
+            parse_alloc: None,
+            parts: vec![
+                part(1, Ok(Answer::Float(0.5))),
+                part(2, Err(anyhow::anyhow!("no path found"))),
+            ],
+        };
+        let output = DayOutput::from_report(&report);
+        assert!(!output.success());
+        let value = serde_json::to_value(&output).unwrap();
+        assert_eq!(value["parts"][0]["answer"], json!(0.5));
+        let expected = json!({
+            "part": 2,
+            "answer": null,
+            "type": null,
+            "time_ns": 200,
+            "error": {"message": "no path found", "causes": [], "line": null, "column": null},
+        });
+        assert_eq!(value["parts"][1], expected);
+    }
+}
@@ -0,0 +1,859 @@
+mod animate;
+mod bench;
+mod client;
+mod days;
+mod debug;
+mod export;
+mod json;
+mod pool;
+mod scaffold;
+mod verify;
+
+#[global_allocator]
+static ALLOCATOR: common::alloc::Counting = common::alloc::Counting;
+
+use std::{
+    fs,
+    io::{self, Write},
+    ops::Range,
+    path::{Path, PathBuf},
+    process::ExitCode,
+    str::FromStr,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{anyhow, Context, Error, Result};
+use clap::{Parser, Subcommand, ValueEnum};
+use common::{alloc::AllocStats, bench::BenchConfig, input::InputSource, solution::Report};
+use verify::{Answers, Tally, Verdict};
+
+#[derive(Parser)]
+#[command(name = "aoc", about = "Advent of Code 2022 solutions runner")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+    /// Trace the solutions on stderr: `-v` for the steps and their outcome,
+    /// `-vv` for every iteration of the main loops, `-vvv` for whole states.
+    /// Needs the `trace` feature
+    #[arg(short, long, action = clap::ArgAction::Count, global = true)]
+    verbose: u8,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Run the solutions of one or more days
+    Run {
+        /// Days to run: `17`, `1-5`, `1,3,7` or `all`
+        days: DaySelection,
+        /// Only run the given part
+        #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: Option<u8>,
+        /// Input file, `-` for stdin. Defaults to `dayN/input` in `$AOC_INPUT` or the workspace
+        #[arg(short, long)]
+        input: Option<String>,
+        /// Output format
+        #[arg(short, long, value_enum, default_value_t = Format::Text)]
+        format: Format,
+        /// Days run at the same time. Defaults to the number of cores, the
+        /// timings are only comparable with `-j 1`
+        #[arg(short, long, value_parser = clap::value_parser!(u16).range(1..))]
+        jobs: Option<u16>,
+        /// Also report the allocations, bytes allocated and peak heap of each
+        /// step. The days then run one at a time
+        #[arg(long)]
+        alloc: bool,
+    },
+    /// Check the answers of one or more days against the expected answers file
+    Verify {
+        /// Days to verify: `17`, `1-5`, `1,3,7` or `all`
+        days: DaySelection,
+        /// Input file, `-` for stdin. Defaults to `dayN/input` in `$AOC_INPUT` or the workspace
+        #[arg(short, long)]
+        input: Option<String>,
+        /// Expected answers file. Defaults to `answers.toml` in `$AOC_INPUT` or the workspace
+        #[arg(short, long)]
+        answers: Option<PathBuf>,
+        /// Record the current answers as the expected ones instead of checking them
+        #[arg(long)]
+        record: bool,
+        /// Days run at the same time. Defaults to the number of cores
+        #[arg(short, long, value_parser = clap::value_parser!(u16).range(1..))]
+        jobs: Option<u16>,
+    },
+    /// Time the parsing and parts of one or more days over repeated runs
+    Bench {
+        /// Days to benchmark: `17`, `1-5`, `1,3,7` or `all`
+        days: DaySelection,
+        /// Only benchmark the given part
+        #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: Option<u8>,
+        /// Input file, `-` for stdin. Defaults to `dayN/input` in `$AOC_INPUT` or the workspace
+        #[arg(short, long)]
+        input: Option<String>,
+        /// Untimed runs before sampling
+        #[arg(long, default_value_t = BenchConfig::default().warmup)]
+        warmup: usize,
+        /// Timed runs per step
+        #[arg(long, default_value_t = BenchConfig::default().samples)]
+        samples: usize,
+        /// Save the results to this file, merged with the days already in it
+        #[arg(long)]
+        save: Option<PathBuf>,
+        /// Compare the medians against results saved with `--save`
+        #[arg(long)]
+        baseline: Option<PathBuf>,
+        /// Slowdown of the median, in percent, reported as a regression
+        #[arg(long, default_value_t = 10.0)]
+        threshold: f64,
+    },
+    /// Watch the simulation of a day step by step in the terminal
+    Animate {
+        /// Day to animate: 5, 8, 9, 10, 12, 14, 15, 17, 22, 23 or 24
+        day: u8,
+        /// Part whose simulation is shown
+        #[arg(short, long, default_value_t = 1, value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: u8,
+        /// Input file, `-` for stdin. Defaults to `dayN/input` in `$AOC_INPUT` or the workspace
+        #[arg(short, long)]
+        input: Option<String>,
+        /// Steps per second, from 0.5 to 1000. `+` and `-` change it while playing
+        #[arg(long, default_value_t = 30.0)]
+        fps: f64,
+        /// Region to show as `x0,y0:x1,y1` in the coordinates of the simulation.
+        /// Defaults to the whole frame, or a terminal sized window around the action
+        #[arg(long)]
+        viewport: Option<animate::Viewport>,
+        /// Start paused, `n` steps and space resumes
+        #[arg(long)]
+        paused: bool,
+    },
+    /// Step through the simulation of a day in the terminal, forward and back,
+    /// with its state next to it
+    Debug {
+        /// Day to debug: 5, 8, 9, 10, 12, 14, 15, 17, 22, 23 or 24
+        day: u8,
+        /// Part whose simulation is debugged
+        #[arg(short, long, default_value_t = 1, value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: u8,
+        /// Input file, `-` for stdin. Defaults to `dayN/input` in `$AOC_INPUT` or the workspace
+        #[arg(short, long)]
+        input: Option<String>,
+    },
+    /// Draw the simulation of a day to image files
+    Export {
+        /// Day to draw: 5, 8, 9, 10, 12, 14, 15, 17, 22, 23 or 24
+        day: u8,
+        /// Part whose simulation is drawn
+        #[arg(short, long, default_value_t = 1, value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: u8,
+        /// Input file, `-` for stdin. Defaults to `dayN/input` in `$AOC_INPUT` or the workspace
+        #[arg(short, long)]
+        input: Option<String>,
+        /// `out.png` for the final state, `out.gif` for an animation of every step,
+        /// `frames/{}.png` for one PNG per step
+        #[arg(short, long)]
+        output: PathBuf,
+        /// Size in image pixels of each cell of the puzzle
+        #[arg(long, default_value_t = 4, value_parser = clap::value_parser!(u16).range(1..))]
+        scale: u16,
+        /// `dark` or `light`, with overrides such as `dark,red=ff8000,background=000000`
+        #[arg(long, default_value = "dark")]
+        palette: export::Palette,
+        /// Keep one step out of N. Defaults to fitting `--max-frames`
+        #[arg(long, value_parser = clap::value_parser!(u64).range(1..))]
+        every: Option<u64>,
+        /// Frames of the animations when `--every` is not given
+        #[arg(long, default_value_t = 500)]
+        max_frames: usize,
+        /// Display time of each GIF frame, in milliseconds
+        #[arg(long, default_value_t = 50)]
+        delay: u16,
+    },
+    /// Create a day from the template and register it in the workspace, the
+    /// runner and the answers file
+    New {
+        day: u8,
+        /// Answers file to add the day to. Defaults to `answers.toml` in `$AOC_INPUT` or the workspace
+        #[arg(short, long)]
+        answers: Option<PathBuf>,
+    },
+    /// Write a random input for a day, in the format of the real ones
+    Gen {
+        day: u8,
+        /// Seed of the generator. Defaults to a random one, reported on stderr
+        #[arg(short, long)]
+        seed: Option<u64>,
+        /// Size of the input, what it counts depends on the day. Defaults to
+        /// the size of the real inputs
+        #[arg(long)]
+        size: Option<usize>,
+        /// File to write, defaults to stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Check on generated inputs that the implementations of the parts solved
+    /// in several ways agree, shrinking the inputs they disagree on
+    Diff {
+        /// Days to check: `17`, `1-5`, `1,3,7` or `all`. Only the days with
+        /// several implementations are checked
+        days: DaySelection,
+        /// Inputs generated per day
+        #[arg(short = 'n', long, default_value_t = 20)]
+        inputs: u64,
+        /// Seed of the first input, the next ones use the following seeds
+        #[arg(short, long, default_value_t = 0)]
+        seed: u64,
+        /// Size of the inputs, what it counts depends on the day. Defaults to
+        /// a size the slowest implementation of the day runs quickly on
+        #[arg(long)]
+        size: Option<usize>,
+    },
+    /// Download the puzzle inputs of one or more days to `dayN/input` in
+    /// `$AOC_INPUT` or the workspace
+    Fetch {
+        /// Days to fetch: `17`, `1-5`, `1,3,7` or `all`
+        days: DaySelection,
+        /// Download again the inputs already there
+        #[arg(long)]
+        force: bool,
+        /// Client config file with the session token. Defaults to `aoc.toml` in `$AOC_INPUT` or the workspace
+        #[arg(short, long)]
+        config: Option<PathBuf>,
+    },
+    /// Submit the answer to a part, recording it in the answers file when it is right
+    Submit {
+        day: u8,
+        #[arg(value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: u8,
+        /// Answer to submit. Defaults to running the part
+        answer: Option<String>,
+        /// Input file to run the part on, `-` for stdin. Defaults to `dayN/input` in `$AOC_INPUT` or the workspace
+        #[arg(short, long)]
+        input: Option<String>,
+        /// Answers file. Defaults to `answers.toml` in `$AOC_INPUT` or the workspace
+        #[arg(short, long)]
+        answers: Option<PathBuf>,
+        /// Client config file with the session token. Defaults to `aoc.toml` in `$AOC_INPUT` or the workspace
+        #[arg(short, long)]
+        config: Option<PathBuf>,
+    },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum Format {
+    /// One line per step
+    Text,
+    /// A single JSON document with the answers, their types, timings and errors
+    Json,
+}
+
+#[derive(Clone, Debug)]
+struct DaySelection(Vec<u8>);
+
+impl FromStr for DaySelection {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "all" {
+            return Ok(DaySelection(days::DAYS.iter().map(|d| d.day).collect()));
+        }
+        let mut selected = vec![];
+        for spec in s.split(',') {
+            let (start, end) = match spec.split_once('-') {
+                Some((a, b)) => (a.trim().parse::<u8>()?, b.trim().parse::<u8>()?),
+                None => {
+                    let d = spec.trim().parse::<u8>()?;
+                    (d, d)
+                }
+            };
+            if start > end {
+                return Err(anyhow!("invalid day range {}", spec));
+            }
+            for day in start..=end {
+                if days::get(day).is_none() {
+                    return Err(anyhow!("day {} is not registered", day));
+                }
+                if !selected.contains(&day) {
+                    selected.push(day);
+                }
+            }
+        }
+        Ok(DaySelection(selected))
+    }
+}
+
+impl DaySelection {
+    fn days(&self) -> Vec<&'static days::Day> {
+        self.0.iter().filter_map(|&d| days::get(d)).collect()
+    }
+}
+
+fn check_source(selection: &DaySelection, source: &InputSource) -> Result<()> {
+    if *source != InputSource::Default && selection.0.len() != 1 {
+        return Err(anyhow!("an input file can only be given when running a single day"));
+    }
+    Ok(())
+}
+
+fn execute(day: &days::Day, part: Option<u8>, source: &InputSource) -> Result<Report> {
+    let input = source.load(day.day)?;
+    let parts = match part {
+        Some(p) => vec![p],
+        None => (1..=day.parts).collect(),
+    };
+    (day.execute)(&input, &parts).context("failed to parse input")
+}
+
+/// Time of a step, and its allocations when they are counted
+fn cost(time: Duration, alloc: Option<AllocStats>) -> String {
+    match alloc {
+        Some(alloc) => format!("{:?}, {}", time, alloc),
+        None => format!("{:?}", time),
+    }
+}
+
+fn run(
+    selection: &DaySelection,
+    part: Option<u8>,
+    source: &InputSource,
+    format: Format,
+    jobs: usize,
+) -> Result<bool> {
+    check_source(selection, source)?;
+    if format == Format::Json {
+        return run_json(selection, part, source, jobs);
+    }
+
+    let mut success = true;
+    let work = |day: &&days::Day| execute(day, part, source);
+    pool::ordered(&selection.days(), jobs, work, |day, report| {
+        let report = match report {
+            Ok(report) => report,
+            Err(e) => {
+                println!("[!] day {}: {:#}", day.day, e);
+                success = false;
+                return Ok(());
+            }
+        };
+        let parse_cost = cost(report.parse_time, report.parse_alloc);
+        println!("[*] day {} parse: ({})", day.day, parse_cost);
+        for part in report.parts {
+            let part_cost = cost(part.time, part.alloc);
+            match part.answer {
+                Ok(answer) => {
+                    let answer = answer.to_string();
+                    if answer.contains('\n') {
+                        println!("[*] day {} part {}: ({})\n{}", day.day, part.part, part_cost, answer)
+                    } else {
+                        println!("[*] day {} part {}: {} ({})", day.day, part.part, answer, part_cost)
+                    }
+                }
+                Err(e) => {
+                    println!("[!] day {} part {}: {:#}", day.day, part.part, e);
+                    success = false;
+                }
+            }
+        }
+        Ok(())
+    })?;
+    Ok(success)
+}
+
+fn run_json(
+    selection: &DaySelection,
+    part: Option<u8>,
+    source: &InputSource,
+    jobs: usize,
+) -> Result<bool> {
+    let mut output = json::RunOutput::default();
+    let work = |day: &&days::Day| execute(day, part, source);
+    pool::ordered(&selection.days(), jobs, work, |day, report| {
+        output.days.push(match report {
+            Ok(report) => json::DayOutput::from_report(&report),
+            Err(e) => json::DayOutput::from_error(day.day, &e),
+        });
+        Ok(())
+    })?;
+    println!("{}", serde_json::to_string_pretty(&output)?);
+    Ok(output.days.iter().all(|d| d.success()))
+}
+
+fn verify(
+    selection: &DaySelection,
+    source: &InputSource,
+    answers_path: &std::path::Path,
+    record: bool,
+    jobs: usize,
+) -> Result<bool> {
+    check_source(selection, source)?;
+    let mut answers = Answers::load(answers_path)?;
+
+    let mut tally = Tally::default();
+    let work = |day: &&days::Day| execute(day, None, source);
+    pool::ordered(&selection.days(), jobs, work, |day, report| {
+        let report = match report {
+            Ok(report) => report,
+            Err(e) => {
+                println!("[!] day {}: {:#}", day.day, e);
+                tally.errors(day.parts.into());
+                return Ok(());
+            }
+        };
+        for part in report.parts {
+            let answer = match part.answer {
+                Ok(answer) => answer.to_string(),
+                Err(e) => {
+                    println!("[!] day {} part {}: {:#}", day.day, part.part, e);
+                    tally.errors(1);
+                    continue;
+                }
+            };
+            if record {
+                println!("[*] day {} part {}: recorded", day.day, part.part);
+                answers.set(day.day, part.part, answer);
+                continue;
+            }
+            let verdict = Verdict::check(&answers, day.day, part.part, &answer)?;
+            match &verdict {
+                Verdict::Pass => println!("[*] day {} part {}: pass", day.day, part.part),
+                Verdict::Fail { expected } => println!(
+                    "[!] day {} part {}: fail, expected {:?} got {:?}",
+                    day.day, part.part, expected, answer
+                ),
+                Verdict::Missing => {
+                    println!("[?] day {} part {}: missing, got {:?}", day.day, part.part, answer)
+                }
+            }
+            tally.count(&verdict);
+        }
+        Ok(())
+    })?;
+
+    if record {
+        answers.sort();
+        answers.save(answers_path)?;
+        println!("[*] answers saved to {}", answers_path.display());
+    } else {
+        println!("[*] {}", tally);
+    }
+    Ok(tally.failed == 0)
+}
+
+struct BenchOptions {
+    config: BenchConfig,
+    save: Option<PathBuf>,
+    baseline: Option<PathBuf>,
+    threshold: f64,
+}
+
+fn bench(
+    selection: &DaySelection,
+    part: Option<u8>,
+    source: &InputSource,
+    options: &BenchOptions,
+) -> Result<bool> {
+    check_source(selection, source)?;
+    let baseline = options
+        .baseline
+        .as_deref()
+        .map(bench::Results::load)
+        .transpose()?;
+    let mut results = match &options.save {
+        Some(path) => Some(bench::Results::load_or_default(path)?),
+        None => None,
+    };
+
+    let mut success = true;
+    for day in selection.0.iter().filter_map(|&d| days::get(d)) {
+        let parts = match part {
+            Some(p) => vec![p],
+            None => (1..=day.parts).collect(),
+        };
+        let report = match source
+            .load(day.day)
+            .and_then(|input| (day.bench)(&input, &parts, &options.config))
+        {
+            Ok(report) => report,
+            Err(e) => {
+                println!("[!] day {}: {:#}", day.day, e);
+                success = false;
+                continue;
+            }
+        };
+
+        let steps = std::iter::once((None, report.parse))
+            .chain(report.parts.iter().map(|(p, s)| (Some(*p), *s)));
+        for (part, stats) in steps {
+            let name = match part {
+                None => "parse".to_string(),
+                Some(p) => format!("part {}", p),
+            };
+            let line = format!(
+                "day {} {}: min {:?}, median {:?}, mean {:?}, stddev {:?}",
+                day.day, name, stats.min, stats.median, stats.mean, stats.stddev
+            );
+            let base = match &baseline {
+                Some(baseline) => baseline.get(day.day, part)?,
+                None => None,
+            };
+            match base {
+                Some(base) => {
+                    let change = bench::median_change(&base, &stats);
+                    if change > options.threshold {
+                        println!("[!] {} ({:+.1}% vs baseline, regression)", line, change);
+                        success = false;
+                    } else {
+                        println!("[*] {} ({:+.1}% vs baseline)", line, change);
+                    }
+                }
+                None => println!("[*] {}", line),
+            }
+        }
+
+        if let Some(results) = &mut results {
+            results.insert(&report);
+        }
+    }
+
+    if let (Some(results), Some(path)) = (&mut results, &options.save) {
+        results.save(path)?;
+        println!("[*] results saved to {}", path.display());
+    }
+    Ok(success)
+}
+
+fn animate(
+    day: u8,
+    part: u8,
+    source: &InputSource,
+    options: &animate::PlayerOptions,
+) -> Result<()> {
+    let day = days::get(day).with_context(|| format!("day {} is not registered", day))?;
+    let simulation = day
+        .animate
+        .with_context(|| format!("day {} has no animation", day.day))?;
+    let input = source.load(day.day)?;
+    let sim = simulation(&input, part).context("failed to parse input")?;
+    animate::play(sim, options)
+}
+
+fn debug(day: u8, part: u8, source: &InputSource) -> Result<()> {
+    let day = days::get(day).with_context(|| format!("day {} is not registered", day))?;
+    let simulation = day
+        .animate
+        .with_context(|| format!("day {} has no simulation to debug", day.day))?;
+    let input = source.load(day.day)?;
+    debug::debug(|| simulation(&input, part).context("failed to parse input"))
+}
+
+fn export(day: u8, part: u8, source: &InputSource, options: &export::ExportOptions) -> Result<()> {
+    let day = days::get(day).with_context(|| format!("day {} is not registered", day))?;
+    let simulation = day
+        .animate
+        .with_context(|| format!("day {} has no simulation to draw", day.day))?;
+    let input = source.load(day.day)?;
+    let start = || simulation(&input, part).context("failed to parse input");
+    let frames = export::export(start, options)?;
+    println!("[*] {} frames written to {}", frames, options.output.display());
+    Ok(())
+}
+
+fn gen(day: u8, seed: Option<u64>, size: Option<usize>, output: Option<&Path>) -> Result<()> {
+    let day = days::get(day).with_context(|| format!("day {} is not registered", day))?;
+    let seed = seed.unwrap_or_else(|| {
+        let seed = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_nanos() as u64);
+        eprintln!("[*] seed {}", seed);
+        seed
+    });
+    let input = (day.generate)(seed, size);
+    match output {
+        Some(path) => fs::write(path, input)
+            .with_context(|| format!("failed to write {}", path.display()))?,
+        None => io::stdout().write_all(input.as_bytes())?,
+    }
+    Ok(())
+}
+
+fn diff(selection: &DaySelection, seeds: Range<u64>, size: Option<usize>) -> Result<bool> {
+    let checked: Vec<_> = selection
+        .days()
+        .into_iter()
+        .filter_map(|d| Some((d.day, d.diff?)))
+        .collect();
+    if checked.is_empty() {
+        return Err(anyhow!("none of the days has several implementations"));
+    }
+    let mut success = true;
+    for (day, check) in checked {
+        match check(seeds.clone(), size) {
+            Ok(()) => println!("[*] day {}: {} inputs, implementations agree", day, seeds.end - seeds.start),
+            Err(mismatch) => {
+                println!("[!] {}", mismatch);
+                success = false;
+            }
+        }
+    }
+    Ok(success)
+}
+
+fn fetch(selection: &DaySelection, force: bool, config: &Path) -> Result<bool> {
+    let client = client::Client::new(&client::Config::load(config)?)?;
+    let mut success = true;
+    for day in selection.0.iter().copied() {
+        let path = common::input::default_path(day);
+        match client::fetch(&client, day, &path, force) {
+            Ok(true) => println!("[*] day {}: input saved to {}", day, path.display()),
+            Ok(false) => println!("[*] day {}: input already in {}", day, path.display()),
+            Err(e) => {
+                println!("[!] day {}: {:#}", day, e);
+                success = false;
+            }
+        }
+    }
+    Ok(success)
+}
+
+struct SubmitOptions {
+    answer: Option<String>,
+    source: InputSource,
+    answers: PathBuf,
+    config: PathBuf,
+}
+
+fn submit(day: u8, part: u8, options: &SubmitOptions) -> Result<bool> {
+    let registered = days::get(day).with_context(|| format!("day {} is not registered", day))?;
+    if part > registered.parts {
+        return Err(anyhow!("day {} has no part {}", day, part));
+    }
+    let client = client::Client::new(&client::Config::load(&options.config)?)?;
+    let answer = match &options.answer {
+        Some(answer) => answer.clone(),
+        None => {
+            let report = execute(registered, Some(part), &options.source)?;
+            let answer = report
+                .parts
+                .into_iter()
+                .next()
+                .context("the part was not run")?
+                .answer?
+                .to_string();
+            println!("[*] day {} part {}: {}", day, part, answer);
+            answer
+        }
+    };
+
+    match client.submit(day, part, &answer)? {
+        client::Submission::Correct => {
+            println!("[*] day {} part {}: correct", day, part);
+            let mut answers = Answers::load(&options.answers)?;
+            answers.set(day, part, answer);
+            answers.sort();
+            answers.save(&options.answers)?;
+            println!("[*] answer saved to {}", options.answers.display());
+            Ok(true)
+        }
+        client::Submission::Incorrect { hint } => {
+            match hint {
+                Some(hint) => println!("[!] day {} part {}: incorrect, {}", day, part, hint),
+                None => println!("[!] day {} part {}: incorrect", day, part),
+            }
+            Ok(false)
+        }
+        client::Submission::TooSoon { wait } => {
+            match wait {
+                Some(wait) => println!("[!] day {} part {}: too soon, wait {:?}", day, part, wait),
+                None => println!("[!] day {} part {}: too soon", day, part),
+            }
+            Ok(false)
+        }
+        client::Submission::WrongLevel => {
+            println!("[!] day {} part {}: already solved or locked", day, part);
+            Ok(false)
+        }
+    }
+}
+
+/// Print the spans and events up to the level of verbosity on stderr, with
+/// the time spent in each span when it closes
+#[cfg(feature = "trace")]
+fn init_tracing(verbose: u8) {
+    use std::io::IsTerminal;
+    use tracing_subscriber::fmt::format::FmtSpan;
+
+    let level = match verbose {
+        0 => return,
+        1 => tracing_subscriber::filter::LevelFilter::INFO,
+        2 => tracing_subscriber::filter::LevelFilter::DEBUG,
+        _ => tracing_subscriber::filter::LevelFilter::TRACE,
+    };
+    tracing_subscriber::fmt()
+        .with_max_level(level)
+        .with_span_events(FmtSpan::CLOSE)
+        .with_writer(io::stderr)
+        .with_ansi(io::stderr().is_terminal())
+        .init();
+}
+
+#[cfg(not(feature = "trace"))]
+fn init_tracing(verbose: u8) {
+    if verbose > 0 {
+        eprintln!("[!] built without the trace feature, -v has no effect");
+    }
+}
+
+fn main() -> Result<ExitCode> {
+    let cli = Cli::parse();
+    init_tracing(cli.verbose);
+    let success = match cli.command {
+        Command::Run {
+            days,
+            part,
+            input,
+            format,
+            jobs,
+            alloc,
+        } => {
+            // The allocation counters are shared by all the threads
+            let jobs = match alloc {
+                true => 1,
+                false => jobs.map_or_else(pool::default_jobs, usize::from),
+            };
+            common::alloc::enable(alloc);
+            run(&days, part, &InputSource::from_arg(input.as_deref()), format, jobs)?
+        }
+        Command::Verify {
+            days,
+            input,
+            answers,
+            record,
+            jobs,
+        } => {
+            let answers = answers.unwrap_or_else(verify::default_path);
+            let jobs = jobs.map_or_else(pool::default_jobs, usize::from);
+            let source = InputSource::from_arg(input.as_deref());
+            verify(&days, &source, &answers, record, jobs)?
+        }
+        Command::Bench {
+            days,
+            part,
+            input,
+            warmup,
+            samples,
+            save,
+            baseline,
+            threshold,
+        } => {
+            let options = BenchOptions {
+                config: BenchConfig { warmup, samples },
+                save,
+                baseline,
+                threshold,
+            };
+            bench(&days, part, &InputSource::from_arg(input.as_deref()), &options)?
+        }
+        Command::Animate {
+            day,
+            part,
+            input,
+            fps,
+            viewport,
+            paused,
+        } => {
+            if !(animate::MIN_FPS..=animate::MAX_FPS).contains(&fps) {
+                return Err(anyhow!(
+                    "the frame rate must be between {} and {} steps per second",
+                    animate::MIN_FPS,
+                    animate::MAX_FPS
+                ));
+            }
+            let options = animate::PlayerOptions {
+                fps,
+                viewport,
+                paused,
+            };
+            animate(day, part, &InputSource::from_arg(input.as_deref()), &options)?;
+            true
+        }
+        Command::Debug { day, part, input } => {
+            debug(day, part, &InputSource::from_arg(input.as_deref()))?;
+            true
+        }
+        Command::Export {
+            day,
+            part,
+            input,
+            output,
+            scale,
+            palette,
+            every,
+            max_frames,
+            delay,
+        } => {
+            let options = export::ExportOptions {
+                output,
+                scale: scale as usize,
+                palette,
+                every: every.map(|n| n as usize),
+                max_frames,
+                delay,
+            };
+            export(day, part, &InputSource::from_arg(input.as_deref()), &options)?;
+            true
+        }
+        Command::New { day, answers } => {
+            let answers = answers.unwrap_or_else(verify::default_path);
+            let dir = scaffold::new_day(day, &answers)?;
+            println!("[*] day {} created in {}", day, dir.display());
+            true
+        }
+        Command::Gen {
+            day,
+            seed,
+            size,
+            output,
+        } => {
+            gen(day, seed, size, output.as_deref())?;
+            true
+        }
+        Command::Diff {
+            days,
+            inputs,
+            seed,
+            size,
+        } => {
+            let seeds = seed..seed.checked_add(inputs).context("too many inputs")?;
+            diff(&days, seeds, size)?
+        }
+        Command::Fetch {
+            days,
+            force,
+            config,
+        } => {
+            let config = config.unwrap_or_else(client::default_config_path);
+            fetch(&days, force, &config)?
+        }
+        Command::Submit {
+            day,
+            part,
+            answer,
+            input,
+            answers,
+            config,
+        } => {
+            let options = SubmitOptions {
+                answer,
+                source: InputSource::from_arg(input.as_deref()),
+                answers: answers.unwrap_or_else(verify::default_path),
+                config: config.unwrap_or_else(client::default_config_path),
+            };
+            submit(day, part, &options)?
+        }
+    };
+    Ok(if success {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    })
+}
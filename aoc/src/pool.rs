@@ -0,0 +1,147 @@
+use std::{
+    collections::BTreeMap,
+    num::NonZeroUsize,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc,
+    },
+    thread,
+};
+
+use anyhow::Result;
+
+/// Threads used when no job count is given
+pub fn default_jobs() -> usize {
+    thread::available_parallelism().map_or(1, NonZeroUsize::get)
+}
+
+/// Apply `work` to every item on up to `jobs` threads. `done` receives the
+/// results in the order of the items, each one as soon as it and the ones
+/// before it are ready. The remaining items are dropped once `done` fails
+pub fn ordered<T: Sync, R: Send>(
+    items: &[T],
+    jobs: usize,
+    work: impl Fn(&T) -> R + Sync,
+    mut done: impl FnMut(&T, R) -> Result<()>,
+) -> Result<()> {
+    let next = AtomicUsize::new(0);
+    let (tx, rx) = mpsc::channel();
+    thread::scope(|scope| {
+        for _ in 0..jobs.clamp(1, items.len().max(1)) {
+            let (tx, next, work) = (tx.clone(), &next, &work);
+            scope.spawn(move || loop {
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                let Some(item) = items.get(i) else {
+                    break;
+                };
+                if tx.send((i, work(item))).is_err() {
+                    break;
+                }
+            });
+        }
+        drop(tx);
+
+        let mut pending = BTreeMap::new();
+        let mut expected = 0;
+        for (i, result) in rx {
+            pending.insert(i, result);
+            while let Some(result) = pending.remove(&expected) {
+                if let Err(e) = done(&items[expected], result) {
+                    next.store(items.len(), Ordering::Relaxed);
+                    return Err(e);
+                }
+                expected += 1;
+            }
+        }
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        panic::{self, AssertUnwindSafe},
+        time::Duration,
+    };
+
+    use anyhow::{anyhow, bail};
+
+    use super::*;
+
+    /// Results given to `done`, and whether the pool panicked
+    type Delivered = (Vec<(u64, Result<u64, String>)>, bool);
+
+    /// Run the pool on 12 items, stopping once `done` got `stop_at`. Later items
+    /// finish first so that the threads deliver them out of order
+    fn collect(
+        jobs: usize,
+        work: impl Fn(&u64) -> Result<u64> + Sync,
+        stop_at: Option<u64>,
+    ) -> Delivered {
+        let items: Vec<u64> = (0..12).collect();
+        let mut results = vec![];
+        let outcome = panic::catch_unwind(AssertUnwindSafe(|| {
+            let work = |&i: &u64| {
+                thread::sleep(Duration::from_millis(12 - i));
+                work(&i)
+            };
+            ordered(&items, jobs, work, |&i, r| {
+                results.push((i, r.map_err(|e| e.to_string())));
+                match stop_at {
+                    Some(stop) if stop == i => bail!("stop"),
+                    _ => Ok(()),
+                }
+            })
+        }));
+        (results, outcome.is_err())
+    }
+
+    fn same_as_sequential(
+        work: impl Fn(&u64) -> Result<u64> + Sync + Copy,
+        stop_at: Option<u64>,
+    ) -> Delivered {
+        let sequential = collect(1, work, stop_at);
+        for jobs in [2, 4, 20] {
+            assert_eq!(collect(jobs, work, stop_at), sequential, "{} jobs", jobs);
+        }
+        sequential
+    }
+
+    #[test]
+    fn ordered_results() {
+        let (results, panicked) = same_as_sequential(|&i| Ok(i * i), None);
+        assert!(!panicked);
+        let expected: Vec<_> = (0..12).map(|i| (i, Ok(i * i))).collect();
+        assert_eq!(results, expected);
+    }
+
+    #[test]
+    fn ordered_errors() {
+        let work = |&i: &u64| match i % 5 {
+            3 => Err(anyhow!("failed {}", i)),
+            _ => Ok(i),
+        };
+        let (results, panicked) = same_as_sequential(work, None);
+        assert!(!panicked);
+        assert_eq!(results.len(), 12);
+        assert_eq!(results[3], (3, Err("failed 3".to_string())));
+        assert_eq!(results[8], (8, Err("failed 8".to_string())));
+
+        // A failing `done` drops the remaining items
+        let (results, panicked) = same_as_sequential(work, Some(4));
+        assert!(!panicked);
+        assert_eq!(results.last().unwrap().0, 4);
+    }
+
+    #[test]
+    fn ordered_panic() {
+        let work = |&i: &u64| {
+            assert!(i != 5, "job {} panicked", i);
+            Ok(i)
+        };
+        let (results, panicked) = same_as_sequential(work, None);
+        assert!(panicked);
+        let expected: Vec<_> = (0..5).map(|i| (i, Ok(i))).collect();
+        assert_eq!(results, expected);
+    }
+}
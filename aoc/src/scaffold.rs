@@ -0,0 +1,221 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, Context, Result};
+
+use crate::verify::Answers;
+
+/// Workspace the runner was built from
+pub fn workspace_dir() -> PathBuf {
+    let runner = Path::new(env!("CARGO_MANIFEST_DIR"));
+    runner.parent().unwrap_or(runner).to_path_buf()
+}
+
+/// Files of `template/` copied to the new day, relative to both crates
+const TEMPLATE_FILES: &[&str] = &[
+    "Cargo.toml",
+    "src/lib.rs",
+    "src/main.rs",
+    "example",
+    "input",
+];
+
+/// Template file with the names and number of the day filled in
+fn instantiate(content: &str, day: u8) -> String {
+    content
+        .replace("dayx", &format!("day{}", day))
+        .replace("DayX", &format!("Day{}", day))
+        .replace("const DAY: u8 = 0;", &format!("const DAY: u8 = {};", day))
+}
+
+/// Insert `line` among the lines registering the days, keeping them in order.
+/// `registered` gives the day of such a line
+fn register(
+    content: &str,
+    day: u8,
+    line: &str,
+    registered: impl Fn(&str) -> Option<u8>,
+) -> Result<String> {
+    let mut lines: Vec<&str> = content.lines().collect();
+    let days: Vec<(usize, u8)> = lines
+        .iter()
+        .enumerate()
+        .filter_map(|(i, l)| registered(l.trim()).map(|d| (i, d)))
+        .collect();
+    if days.iter().any(|&(_, d)| d == day) {
+        return Err(anyhow!("day {} is already registered", day));
+    }
+    let at = match days.iter().find(|&&(_, d)| d > day) {
+        Some(&(i, _)) => i,
+        None => days.last().context("no day registered")?.0 + 1,
+    };
+    lines.insert(at, line);
+    Ok(lines.join("\n") + "\n")
+}
+
+/// Day number of `prefix<N>suffix`
+fn day_between(s: &str, prefix: &str, suffix: &str) -> Option<u8> {
+    s.strip_prefix(prefix)?.strip_suffix(suffix)?.parse().ok()
+}
+
+/// Day of a member of the workspace manifest, `"dayN",`
+fn workspace_day(line: &str) -> Option<u8> {
+    day_between(line, "\"day", "\",")
+}
+
+/// Day of a dependency of the runner manifest, `dayN = { path = "../dayN" }`
+fn runner_day(line: &str) -> Option<u8> {
+    day_between(line.split_once(' ')?.0, "day", "")
+}
+
+/// Day of an entry of the runner's table, `Day::new::<dayN::DayN>(),`
+fn table_day(line: &str) -> Option<u8> {
+    day_between(line.split_once("::<")?.1.split_once("::")?.0, "day", "")
+}
+
+/// Create `dayN` from the template and register it in the workspace, the
+/// runner and the answers file. Nothing is written if the day already exists
+pub fn new_day(day: u8, answers_path: &Path) -> Result<PathBuf> {
+    if !(1..=25).contains(&day) {
+        return Err(anyhow!("there is no day {}", day));
+    }
+    let root = workspace_dir();
+    let day_dir = root.join(format!("day{}", day));
+    if day_dir.exists() {
+        return Err(anyhow!("{} already exists", day_dir.display()));
+    }
+    let read = |path: &Path| {
+        fs::read_to_string(path).with_context(|| format!("failed to read {}", path.display()))
+    };
+
+    // Everything is prepared before writing, so a registered day is left untouched
+    let workspace_path = root.join("Cargo.toml");
+    let workspace = register(
+        &read(&workspace_path)?,
+        day,
+        &format!("    \"day{}\",", day),
+        workspace_day,
+    )?;
+    let runner_path = root.join("aoc/Cargo.toml");
+    let runner = register(
+        &read(&runner_path)?,
+        day,
+        &format!("day{} = {{ path = \"../day{}\" }}", day, day),
+        runner_day,
+    )?;
+    let days_path = root.join("aoc/src/days.rs");
+    let days = register(
+        &read(&days_path)?,
+        day,
+        &format!("    Day::new::<day{}::Day{}>(),", day, day),
+        table_day,
+    )?;
+    let mut answers = Answers::load(answers_path)?;
+    if !answers.add_day(day) {
+        return Err(anyhow!("day {} already has expected answers", day));
+    }
+    answers.sort();
+    let files = TEMPLATE_FILES
+        .iter()
+        .map(|f| {
+            Ok((
+                day_dir.join(f),
+                instantiate(&read(&root.join("template").join(f))?, day),
+            ))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    fs::create_dir_all(day_dir.join("src"))?;
+    for (path, content) in files {
+        fs::write(&path, content).with_context(|| format!("failed to write {}", path.display()))?;
+    }
+    fs::write(&workspace_path, workspace)?;
+    fs::write(&runner_path, runner)?;
+    fs::write(&days_path, days)?;
+    answers.save(answers_path)?;
+    Ok(day_dir)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WORKSPACE: &str =
+        "[workspace]\nmembers = [\n    \"aoc\",\n    \"day2\",\n    \"day10\",\n]\n";
+
+    const TABLE: &str = "pub const DAYS: &[Day] = &[\n    Day::new::<day2::Day2>(),\n    Day::animated::<day9::Day9>(),\n];\n";
+
+    #[test]
+    fn placeholders() {
+        let template =
+            "name = \"dayx\"\npub struct DayX;\nimpl Solution for DayX {\n    const DAY: u8 = 0;\n";
+        assert_eq!(
+            instantiate(template, 7),
+            "name = \"day7\"\npub struct Day7;\nimpl Solution for Day7 {\n    const DAY: u8 = 7;\n"
+        );
+    }
+
+    #[test]
+    fn day_numbers() {
+        assert_eq!(day_between("day12", "day", ""), Some(12));
+        assert_eq!(day_between("day", "day", ""), None);
+        assert_eq!(day_between("dayx", "day", ""), None);
+        assert_eq!(workspace_day("\"day3\","), Some(3));
+        assert_eq!(workspace_day("\"aoc\","), None);
+        assert_eq!(runner_day("day4 = { path = \"../day4\" }"), Some(4));
+        assert_eq!(runner_day("common = { path = \"../common\" }"), None);
+        assert_eq!(table_day("Day::differential::<day16::Day16>(),"), Some(16));
+        assert_eq!(table_day("pub const DAYS: &[Day] = &["), None);
+    }
+
+    #[test]
+    fn ordered_insertion() {
+        let line = |d: u8| format!("    \"day{}\",", d);
+        let first = register(WORKSPACE, 1, &line(1), workspace_day).unwrap();
+        assert!(first.contains("\"aoc\",\n    \"day1\",\n    \"day2\","));
+        let middle = register(WORKSPACE, 5, &line(5), workspace_day).unwrap();
+        assert!(middle.contains("\"day2\",\n    \"day5\",\n    \"day10\","));
+        let last = register(WORKSPACE, 11, &line(11), workspace_day).unwrap();
+        assert!(last.ends_with("\"day10\",\n    \"day11\",\n]\n"));
+
+        let table = register(TABLE, 5, "    Day::new::<day5::Day5>(),", table_day).unwrap();
+        assert_eq!(table.lines().nth(2), Some("    Day::new::<day5::Day5>(),"));
+    }
+
+    #[test]
+    fn registered_day() {
+        let e = register(WORKSPACE, 10, "    \"day10\",", workspace_day).unwrap_err();
+        assert_eq!(e.to_string(), "day 10 is already registered");
+        let e = register("[workspace]\n", 1, "    \"day1\",", workspace_day).unwrap_err();
+        assert_eq!(e.to_string(), "no day registered");
+    }
+
+    /// The files of the workspace list every day where the scaffolding looks
+    #[test]
+    fn workspace_files() {
+        let root = workspace_dir();
+        for (file, registered) in [
+            ("Cargo.toml", workspace_day as fn(&str) -> Option<u8>),
+            ("aoc/Cargo.toml", runner_day),
+            ("aoc/src/days.rs", table_day),
+        ] {
+            let content = fs::read_to_string(root.join(file)).unwrap();
+            let days: Vec<u8> = content
+                .lines()
+                .filter_map(|l| registered(l.trim()))
+                .collect();
+            assert_eq!(days, (1..=25).collect::<Vec<_>>(), "{}", file);
+        }
+    }
+
+    #[test]
+    fn existing_day() {
+        let answers = std::env::temp_dir().join("aoc2022-scaffold-answers.toml");
+        let e = new_day(1, &answers).unwrap_err();
+        assert!(e.to_string().ends_with("day1 already exists"), "{}", e);
+        assert!(new_day(26, &answers).is_err());
+        assert!(!answers.exists());
+    }
+}
@@ -0,0 +1,245 @@
+use std::{
+    fmt::Display,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, Context, Result};
+use toml::{Table, Value};
+
+/// Expected answers, stored as one table per day:
+///
+/// ```toml
+/// [day1]
+/// part1 = "71780"
+/// part2 = "212489"
+/// ```
+#[derive(Debug, Default)]
+pub struct Answers {
+    table: Table,
+}
+
+/// Default answers file, next to the default inputs
+pub fn default_path() -> PathBuf {
+    common::input::input_dir().join("answers.toml")
+}
+
+impl Answers {
+    /// A missing file is an empty set of answers
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Answers::default());
+        }
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read answers file {}", path.display()))?;
+        let table = content
+            .parse::<Table>()
+            .with_context(|| format!("invalid answers file {}", path.display()))?;
+        Ok(Answers { table })
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let content = toml::to_string(&self.table)?;
+        std::fs::write(path, content)
+            .with_context(|| format!("failed to write answers file {}", path.display()))
+    }
+
+    pub fn get(&self, day: u8, part: u8) -> Result<Option<&str>> {
+        let Some(day_table) = self.table.get(&format!("day{}", day)) else {
+            return Ok(None);
+        };
+        let day_table = day_table
+            .as_table()
+            .ok_or_else(|| anyhow!("day{} is not a table", day))?;
+        match day_table.get(&format!("part{}", part)) {
+            None => Ok(None),
+            Some(Value::String(answer)) => Ok(Some(answer)),
+            Some(_) => Err(anyhow!("day{}.part{} is not a string", day, part)),
+        }
+    }
+
+    pub fn set(&mut self, day: u8, part: u8, answer: String) {
+        let key = format!("day{}", day);
+        if !self.table.get(&key).is_some_and(Value::is_table) {
+            self.table.insert(key.clone(), Value::Table(Table::new()));
+        }
+        let Some(Value::Table(day_table)) = self.table.get_mut(&key) else {
+            unreachable!()
+        };
+        day_table.insert(format!("part{}", part), Value::String(answer));
+    }
+
+    /// Empty table for a day, `false` if it already has one
+    pub fn add_day(&mut self, day: u8) -> bool {
+        let key = format!("day{}", day);
+        if self.table.contains_key(&key) {
+            return false;
+        }
+        self.table.insert(key, Value::Table(Table::new()));
+        true
+    }
+
+    pub fn sort(&mut self) {
+        sort_days(&mut self.table);
+    }
+}
+
+/// Keep `dayN` tables sorted numerically, so recorded files stay diff friendly
+pub fn sort_days(table: &mut Table) {
+    let mut days: Vec<_> = std::mem::take(table).into_iter().collect();
+    days.sort_by_key(|(k, _)| k.trim_start_matches("day").parse::<u8>().unwrap_or(u8::MAX));
+    *table = days.into_iter().collect();
+}
+
+/// Outcome of checking a single part
+#[derive(Debug, PartialEq, Eq)]
+pub enum Verdict {
+    Pass,
+    Fail { expected: String },
+    Missing,
+}
+
+impl Verdict {
+    pub fn check(answers: &Answers, day: u8, part: u8, answer: &str) -> Result<Self> {
+        Ok(match answers.get(day, part)? {
+            None => Verdict::Missing,
+            Some(expected) if expected == answer => Verdict::Pass,
+            Some(expected) => Verdict::Fail {
+                expected: expected.to_string(),
+            },
+        })
+    }
+}
+
+/// Number of parts by outcome
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Tally {
+    pub passed: usize,
+    pub failed: usize,
+    pub missing: usize,
+}
+
+impl Tally {
+    pub fn count(&mut self, verdict: &Verdict) {
+        match verdict {
+            Verdict::Pass => self.passed += 1,
+            Verdict::Fail { .. } => self.failed += 1,
+            Verdict::Missing => self.missing += 1,
+        }
+    }
+
+    /// Parts without an answer, e.g. every part of a day whose input does not parse
+    pub fn errors(&mut self, parts: usize) {
+        self.failed += parts;
+    }
+}
+
+impl Display for Tally {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} passed, {} failed, {} missing",
+            self.passed, self.failed, self.missing
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn answers(content: &str) -> Answers {
+        Answers {
+            table: content.parse().unwrap(),
+        }
+    }
+
+    #[test]
+    fn get_and_set() {
+        let mut answers = answers("[day1]\npart1 = \"71780\"\n");
+        assert_eq!(answers.get(1, 1).unwrap(), Some("71780"));
+        assert_eq!(answers.get(1, 2).unwrap(), None);
+        assert_eq!(answers.get(2, 1).unwrap(), None);
+        answers.set(1, 2, "212489".to_string());
+        answers.set(3, 1, "8".to_string());
+        assert_eq!(answers.get(1, 2).unwrap(), Some("212489"));
+        assert_eq!(answers.get(3, 1).unwrap(), Some("8"));
+        assert!(!answers.add_day(3));
+        assert!(answers.add_day(4));
+        assert_eq!(answers.get(4, 1).unwrap(), None);
+    }
+
+    #[test]
+    fn invalid_answers() {
+        let answers = answers("day1 = 3\n[day2]\npart1 = 5\n");
+        assert_eq!(
+            answers.get(1, 1).unwrap_err().to_string(),
+            "day1 is not a table"
+        );
+        assert_eq!(
+            answers.get(2, 1).unwrap_err().to_string(),
+            "day2.part1 is not a string"
+        );
+        // A day that is not a table is replaced when recording
+        let mut answers = answers;
+        answers.set(1, 1, "4".to_string());
+        assert_eq!(answers.get(1, 1).unwrap(), Some("4"));
+    }
+
+    #[test]
+    fn days_sorted_numerically() {
+        let mut answers = answers("[day10]\n[day2]\n[notes]\n[day1]\n");
+        answers.sort();
+        let keys: Vec<&str> = answers.table.keys().map(String::as_str).collect();
+        assert_eq!(keys, vec!["day1", "day2", "day10", "notes"]);
+    }
+
+    #[test]
+    fn save_and_load() {
+        let path = std::env::temp_dir().join(format!("aoc-answers-{}.toml", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        assert!(Answers::load(&path).unwrap().table.is_empty());
+
+        let mut answers = Answers::default();
+        answers.set(2, 1, "15".to_string());
+        answers.save(&path).unwrap();
+        let loaded = Answers::load(&path).unwrap();
+        assert_eq!(loaded.get(2, 1).unwrap(), Some("15"));
+
+        std::fs::write(&path, "[day1").unwrap();
+        let error = Answers::load(&path).unwrap_err().to_string();
+        assert!(error.starts_with("invalid answers file"), "{}", error);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn failed_parts() {
+        let answers = answers("[day1]\npart1 = \"1\"\npart2 = \"2\"\n");
+        let verdicts = [
+            Verdict::check(&answers, 1, 1, "1").unwrap(),
+            Verdict::check(&answers, 1, 2, "3").unwrap(),
+            Verdict::check(&answers, 2, 1, "1").unwrap(),
+        ];
+        assert_eq!(verdicts[0], Verdict::Pass);
+        assert_eq!(
+            verdicts[1],
+            Verdict::Fail {
+                expected: "2".to_string()
+            }
+        );
+        assert_eq!(verdicts[2], Verdict::Missing);
+
+        let mut tally = Tally::default();
+        verdicts.iter().for_each(|v| tally.count(v));
+        // A day that does not parse fails all of its parts
+        tally.errors(2);
+        assert_eq!(
+            tally,
+            Tally {
+                passed: 1,
+                failed: 3,
+                missing: 1
+            }
+        );
+        assert_eq!(tally.to_string(), "1 passed, 3 failed, 1 missing");
+    }
+}
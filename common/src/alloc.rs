@@ -0,0 +1,188 @@
+//! Allocation counting, to see what the solutions cost in memory and not only
+//! in time.
+//!
+//! A binary opts in by installing [`Counting`] as its global allocator. It only
+//! counts while [`enable`] is on, otherwise each allocation costs a single
+//! extra check. The counters are global, so the steps measured must not run
+//! concurrently with anything else.
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    fmt::Display,
+    sync::atomic::{AtomicBool, AtomicI64, AtomicU64, Ordering::Relaxed},
+};
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static ALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+static BYTES: AtomicU64 = AtomicU64::new(0);
+/// Live bytes allocated while enabled. Frees of older blocks can make it negative
+static CURRENT: AtomicI64 = AtomicI64::new(0);
+static PEAK: AtomicI64 = AtomicI64::new(0);
+
+/// The system allocator, counting what goes through it
+pub struct Counting;
+
+fn record(allocated: usize, freed: usize) {
+    ALLOCATIONS.fetch_add(1, Relaxed);
+    BYTES.fetch_add(allocated as u64, Relaxed);
+    let delta = allocated as i64 - freed as i64;
+    let current = CURRENT.fetch_add(delta, Relaxed) + delta;
+    PEAK.fetch_max(current, Relaxed);
+}
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let p = System.alloc(layout);
+        if !p.is_null() && ENABLED.load(Relaxed) {
+            record(layout.size(), 0);
+        }
+        p
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let p = System.alloc_zeroed(layout);
+        if !p.is_null() && ENABLED.load(Relaxed) {
+            record(layout.size(), 0);
+        }
+        p
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        if ENABLED.load(Relaxed) {
+            CURRENT.fetch_sub(layout.size() as i64, Relaxed);
+        }
+    }
+
+    /// Counted as an allocation of the new size, like the copy it may be
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let p = System.realloc(ptr, layout, new_size);
+        if !p.is_null() && ENABLED.load(Relaxed) {
+            record(new_size, layout.size());
+        }
+        p
+    }
+}
+
+/// Turn counting on or off, it only works with [`Counting`] installed
+pub fn enable(on: bool) {
+    ENABLED.store(on, Relaxed);
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Relaxed)
+}
+
+/// Allocations made by a single step
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AllocStats {
+    pub allocations: u64,
+    /// Total of all the allocations, freed or not
+    pub bytes: u64,
+    /// Most bytes live at the same time, above what was live before the step
+    pub peak: u64,
+}
+
+/// `1.5 MiB` style size
+fn size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", value, UNITS[unit])
+}
+
+impl Display for AllocStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} allocs, {}, peak {}",
+            self.allocations,
+            size(self.bytes),
+            size(self.peak)
+        )
+    }
+}
+
+/// Run `f`, counting its allocations if counting is on
+pub fn measure<T>(f: impl FnOnce() -> T) -> (T, Option<AllocStats>) {
+    if !is_enabled() {
+        return (f(), None);
+    }
+    let allocations = ALLOCATIONS.load(Relaxed);
+    let bytes = BYTES.load(Relaxed);
+    let current = CURRENT.load(Relaxed);
+    PEAK.store(current, Relaxed);
+
+    let res = f();
+
+    let stats = AllocStats {
+        allocations: ALLOCATIONS.load(Relaxed) - allocations,
+        bytes: BYTES.load(Relaxed) - bytes,
+        peak: (PEAK.load(Relaxed) - current).max(0) as u64,
+    };
+    (res, Some(stats))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sizes() {
+        assert_eq!(size(0), "0 B");
+        assert_eq!(size(1023), "1023 B");
+        assert_eq!(size(1536), "1.5 KiB");
+        assert_eq!(size(1 << 20), "1.0 MiB");
+        assert_eq!(size(3 << 40), "3.0 TiB");
+        assert_eq!(size(u64::MAX), "16777216.0 TiB");
+        let stats = AllocStats {
+            allocations: 3,
+            bytes: 2048,
+            peak: 10,
+        };
+        assert_eq!(stats.to_string(), "3 allocs, 2.0 KiB, peak 10 B");
+    }
+
+    /// The only test touching the global counters, the test binary does not
+    /// install [`Counting`] so they only move through direct calls
+    #[test]
+    fn counting() {
+        let layout = |size| Layout::from_size_align(size, 8).unwrap();
+        let steps = || unsafe {
+            let p = Counting.alloc(layout(100));
+            let p = Counting.realloc(p, layout(100), 300);
+            Counting.dealloc(p, layout(300));
+            let p = Counting.alloc_zeroed(layout(50));
+            Counting.dealloc(p, layout(50));
+        };
+
+        enable(false);
+        assert_eq!(measure(steps), ((), None));
+        let before = ALLOCATIONS.load(Relaxed);
+        steps();
+        assert_eq!(ALLOCATIONS.load(Relaxed), before);
+
+        enable(true);
+        let (_, stats) = measure(steps);
+        // The reallocation counts as a new block of 300 bytes replacing the first one
+        let expected = AllocStats {
+            allocations: 3,
+            bytes: 450,
+            peak: 300,
+        };
+        assert_eq!(stats, Some(expected));
+
+        // Only what is live above the start counts in the peak
+        let held = unsafe { Counting.alloc(layout(1000)) };
+        let (_, stats) = measure(|| unsafe { Counting.dealloc(held, layout(1000)) });
+        assert_eq!(stats.map(|s| (s.allocations, s.peak)), Some((0, 0)));
+        enable(false);
+    }
+}
@@ -0,0 +1,165 @@
+//! Frame by frame views of the simulations, played by `aoc animate`, stepped
+//! through by `aoc debug` and written to images by `aoc export`.
+//!
+//! A day exposes a [`Simulation`] through [`Animate`]. Each step produces a
+//! [`Frame`]: a rectangle of coloured characters placed in the coordinates of
+//! the puzzle, so that the player can crop it to any region of interest.
+
+use anyhow::Result;
+
+use crate::point::Point;
+use crate::Solution;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Colour {
+    Default,
+    Grey,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+    /// Shade between the low (`0`) and high (`255`) ends of a gradient, for
+    /// heights and other values with more levels than there are colours
+    Scale(u8),
+}
+
+impl Colour {
+    /// Shade of `value` in `0..=max`
+    pub fn scale(value: usize, max: usize) -> Self {
+        Colour::Scale((value.min(max) * 255 / max.max(1)) as u8)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pixel {
+    pub ch: char,
+    pub colour: Colour,
+}
+
+impl Pixel {
+    pub const EMPTY: Pixel = Pixel::new('.', Colour::Grey);
+
+    pub const fn new(ch: char, colour: Colour) -> Self {
+        Pixel { ch, colour }
+    }
+}
+
+/// A drawing of the simulation at one step
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    /// Puzzle coordinates of the top left pixel
+    pub origin: Point<isize>,
+    pub width: usize,
+    pub height: usize,
+    /// Point worth keeping in view when the frame is cropped
+    pub focus: Option<Point<isize>>,
+    /// One line summary of the state
+    pub status: String,
+    pixels: Vec<Pixel>,
+}
+
+impl Frame {
+    /// Frame filled with [`Pixel::EMPTY`]
+    pub fn new(origin: Point<isize>, width: usize, height: usize) -> Self {
+        Frame {
+            origin,
+            width,
+            height,
+            focus: None,
+            status: String::new(),
+            pixels: vec![Pixel::EMPTY; width * height],
+        }
+    }
+
+    /// Smallest frame containing all the given points, empty if there are none
+    pub fn bounding(points: impl IntoIterator<Item = Point<isize>>) -> Self {
+        let mut bounds: Option<(Point<isize>, Point<isize>)> = None;
+        for p in points {
+            bounds = Some(match bounds {
+                None => (p, p),
+                Some((min, max)) => (
+                    Point::new(min.x.min(p.x), min.y.min(p.y)),
+                    Point::new(max.x.max(p.x), max.y.max(p.y)),
+                ),
+            });
+        }
+        match bounds {
+            None => Frame::new(Point::default(), 0, 0),
+            Some((min, max)) => Frame::new(
+                min,
+                (max.x - min.x + 1) as usize,
+                (max.y - min.y + 1) as usize,
+            ),
+        }
+    }
+
+    fn index(&self, p: Point<isize>) -> Option<usize> {
+        let (x, y) = (p.x - self.origin.x, p.y - self.origin.y);
+        if x < 0 || y < 0 || x as usize >= self.width || y as usize >= self.height {
+            return None;
+        }
+        Some(y as usize * self.width + x as usize)
+    }
+
+    /// Pixel at puzzle coordinates `p`, if inside the frame
+    pub fn get(&self, p: Point<isize>) -> Option<Pixel> {
+        self.index(p).map(|i| self.pixels[i])
+    }
+
+    /// Points outside the frame are ignored
+    pub fn set(&mut self, p: Point<isize>, pixel: Pixel) {
+        if let Some(i) = self.index(p) {
+            self.pixels[i] = pixel;
+        }
+    }
+
+    pub fn with_focus(mut self, focus: Point<isize>) -> Self {
+        self.focus = Some(focus);
+        self
+    }
+
+    pub fn with_status(mut self, status: impl Into<String>) -> Self {
+        self.status = status.into();
+        self
+    }
+
+    /// Rows of pixels, top to bottom
+    pub fn rows(&self) -> impl Iterator<Item = &[Pixel]> {
+        self.pixels.chunks(self.width.max(1))
+    }
+}
+
+/// A simulation advancing one step at a time
+pub trait Simulation {
+    /// Advance by one step, `false` once the simulation is over
+    fn step(&mut self) -> bool;
+
+    /// Drawing of the current state
+    fn frame(&self) -> Frame;
+
+    /// Named values of the current state, shown next to the frame by the debugger
+    fn inspect(&self) -> Vec<(&'static str, String)> {
+        vec![]
+    }
+
+    /// Copy of the simulation at its current step, so that the debugger can go
+    /// back without replaying from the start. `None` when it cannot be copied
+    fn snapshot(&self) -> Option<Box<dyn Simulation>> {
+        None
+    }
+}
+
+/// A day whose solution can be watched step by step
+pub trait Animate: Solution {
+    /// Simulation of the given part, starting from the parsed input
+    fn simulation(input: &Self::Input, part: u8) -> Result<Box<dyn Simulation>>;
+}
+
+/// Parse the input and start the simulation of a part
+pub fn simulation<S: Animate>(input: &str, part: u8) -> Result<Box<dyn Simulation>> {
+    let parsed = S::parse(input)?;
+    S::simulation(&parsed, part)
+}
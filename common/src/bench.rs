@@ -0,0 +1,107 @@
+//! Repeated timing of each step of a solution
+
+use std::{hint::black_box, time::Duration, time::Instant};
+
+use anyhow::{Context, Result};
+
+use crate::Solution;
+
+#[derive(Debug, Clone, Copy)]
+pub struct BenchConfig {
+    /// Untimed runs before sampling, to warm up caches and the allocator
+    pub warmup: usize,
+    /// Timed runs per step
+    pub samples: usize,
+}
+
+impl Default for BenchConfig {
+    fn default() -> Self {
+        BenchConfig {
+            warmup: 3,
+            samples: 10,
+        }
+    }
+}
+
+/// Summary of the samples of a single step
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub stddev: Duration,
+}
+
+impl Stats {
+    pub fn from_samples(samples: &[Duration]) -> Self {
+        assert!(!samples.is_empty(), "no samples");
+        let mut sorted = samples.to_vec();
+        sorted.sort();
+
+        let n = sorted.len();
+        let median = if n.is_multiple_of(2) {
+            (sorted[n / 2 - 1] + sorted[n / 2]) / 2
+        } else {
+            sorted[n / 2]
+        };
+        let secs: Vec<f64> = sorted.iter().map(Duration::as_secs_f64).collect();
+        let mean = secs.iter().sum::<f64>() / n as f64;
+        let variance = secs.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / n as f64;
+
+        Stats {
+            min: sorted[0],
+            median,
+            mean: Duration::from_secs_f64(mean),
+            stddev: Duration::from_secs_f64(variance.sqrt()),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct BenchReport {
+    pub day: u8,
+    pub parse: Stats,
+    /// Part number and its stats
+    pub parts: Vec<(u8, Stats)>,
+}
+
+fn sample<T>(config: &BenchConfig, mut f: impl FnMut() -> Result<T>) -> Result<(T, Stats)> {
+    for _ in 0..config.warmup {
+        black_box(f()?);
+    }
+    let mut samples = Vec::with_capacity(config.samples.max(1));
+    let mut last = None;
+    for _ in 0..config.samples.max(1) {
+        let instant = Instant::now();
+        let res = black_box(f()?);
+        samples.push(instant.elapsed());
+        last = Some(res);
+    }
+    Ok((last.unwrap(), Stats::from_samples(&samples)))
+}
+
+/// Time the parsing and the requested parts, each step on its own
+pub fn bench<S: Solution>(input: &str, parts: &[u8], config: &BenchConfig) -> Result<BenchReport> {
+    let (parsed, parse) =
+        sample(config, || S::parse(black_box(input))).context("failed to parse input")?;
+
+    let parts = parts
+        .iter()
+        .copied()
+        .filter(|&p| p >= 1 && p <= S::PARTS)
+        .map(|part| {
+            let (_, stats) = sample(config, || match part {
+                1 => S::part1(&parsed),
+                _ => S::part2(&parsed),
+            })
+            .with_context(|| format!("part {} failed", part))?;
+            Ok((part, stats))
+        })
+        .collect::<Result<_>>()?;
+
+    Ok(BenchReport {
+        day: S::DAY,
+        parse,
+        parts,
+    })
+}
@@ -0,0 +1,131 @@
+//! Cycle detection for deterministic simulations.
+//!
+//! A simulation is a state and a `step` function advancing it by one step.
+//! Two states with the same `fingerprint` are assumed to evolve identically,
+//! so once a fingerprint repeats the sequence of states loops forever and
+//! any step, however far, maps back to one already simulated.
+
+use std::{collections::HashMap, hash::Hash};
+
+/// Shape of a looping sequence: the states of steps `mu..mu + lambda` repeat forever
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    /// First step of the cycle
+    pub mu: usize,
+    /// Length of the cycle
+    pub lambda: usize,
+}
+
+impl Cycle {
+    /// Step before `mu + lambda` whose state is the same as the state of step `n`
+    pub fn equivalent_step(&self, n: usize) -> usize {
+        if n < self.mu + self.lambda {
+            n
+        } else {
+            self.mu + (n - self.mu) % self.lambda
+        }
+    }
+
+    /// Value of a metric after `n` steps, for metrics growing by the same amount
+    /// on every cycle (a height, a count...).
+    ///
+    /// `history` holds the metric of steps `0..=mu + lambda`, as returned by
+    /// [`detect_with_history`].
+    pub fn extrapolate(&self, n: usize, history: &[usize]) -> usize {
+        if let Some(&v) = history.get(n) {
+            return v;
+        }
+        let per_cycle = history[self.mu + self.lambda] - history[self.mu];
+        let cycles = (n - self.mu) / self.lambda;
+        history[self.equivalent_step(n)] + cycles * per_cycle
+    }
+}
+
+/// Step the simulation until a fingerprint repeats
+pub fn detect<S, K: Hash + Eq>(
+    state: S,
+    step: impl FnMut(&mut S),
+    fingerprint: impl FnMut(&S) -> K,
+) -> Cycle {
+    detect_with_history(state, step, fingerprint, |_| ()).0
+}
+
+/// Step the simulation until a fingerprint repeats, recording a metric of every
+/// state along the way.
+///
+/// The history holds `mu + lambda + 1` values, the last one being the metric of
+/// the first repeated state.
+pub fn detect_with_history<S, K: Hash + Eq, M>(
+    mut state: S,
+    mut step: impl FnMut(&mut S),
+    mut fingerprint: impl FnMut(&S) -> K,
+    mut metric: impl FnMut(&S) -> M,
+) -> (Cycle, Vec<M>) {
+    let mut seen = HashMap::new();
+    let mut history = vec![];
+    loop {
+        history.push(metric(&state));
+        let i = history.len() - 1;
+        if let Some(mu) = seen.insert(fingerprint(&state), i) {
+            let cycle = Cycle { mu, lambda: i - mu };
+            crate::event!(INFO, cycle.mu, cycle.lambda, "cycle found");
+            return (cycle, history);
+        }
+        step(&mut state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Sequence following `next` from state 0
+    fn follow(next: &'static [usize]) -> (Cycle, Vec<usize>) {
+        detect_with_history(0, |s| *s = next[*s], |&s| s, |&s| s)
+    }
+
+    #[test]
+    fn cycle_shapes() {
+        // 0 1 2 | 3 4 5 | 3
+        let (cycle, history) = follow(&[1, 2, 3, 4, 5, 3]);
+        assert_eq!(cycle, Cycle { mu: 3, lambda: 3 });
+        assert_eq!(history, vec![0, 1, 2, 3, 4, 5, 3]);
+        // Loop through the start
+        assert_eq!(follow(&[1, 2, 0]).0, Cycle { mu: 0, lambda: 3 });
+        // Fixed point after one step
+        assert_eq!(follow(&[1, 1]).0, Cycle { mu: 1, lambda: 1 });
+        // Fixed point from the start
+        assert_eq!(follow(&[0]).0, Cycle { mu: 0, lambda: 1 });
+        assert_eq!(
+            detect(0, |s| *s = [1, 2, 3, 4, 5, 3][*s], |&s| s),
+            Cycle { mu: 3, lambda: 3 }
+        );
+    }
+
+    #[test]
+    fn equivalent_step() {
+        let cycle = Cycle { mu: 3, lambda: 3 };
+        let steps: Vec<_> = (0..12).map(|n| cycle.equivalent_step(n)).collect();
+        assert_eq!(steps, vec![0, 1, 2, 3, 4, 5, 3, 4, 5, 3, 4, 5]);
+        let cycle = Cycle { mu: 0, lambda: 1 };
+        assert_eq!(cycle.equivalent_step(1_000_000), 0);
+    }
+
+    #[test]
+    fn extrapolate_matches_simulation() {
+        // The height grows by the state plus one on every step
+        let next = [1, 2, 3, 4, 5, 3];
+        let step = |(s, h): &mut (usize, usize)| {
+            *s = next[*s];
+            *h += *s + 1;
+        };
+        let (cycle, heights) = detect_with_history((0, 0), step, |&(s, _)| s, |&(_, h)| h);
+        assert_eq!(heights.len(), cycle.mu + cycle.lambda + 1);
+
+        let mut state = (0, 0);
+        for n in 0..50 {
+            assert_eq!(cycle.extrapolate(n, &heights), state.1, "step {}", n);
+            step(&mut state);
+        }
+    }
+}
@@ -0,0 +1,438 @@
+//! Differential testing of the days solving a part in more than one way.
+//!
+//! Every [`Variant`] of a part runs on generated inputs next to the `part1` or
+//! `part2` of the solution, and they must all give the same answer, or all
+//! fail. When they do not, the input is shrunk to a small one on which they
+//! still disagree: first by generating smaller inputs from the same seed, then
+//! by removing lines. Panics count as errors, so that the shrinking can go
+//! through inputs the solutions do not expect. A generated input that does
+//! not parse is reported as is.
+
+use std::{
+    cell::Cell,
+    fmt::Display,
+    panic::{self, AssertUnwindSafe},
+    sync::Once,
+};
+
+use anyhow::Result;
+
+use crate::gen::Generate;
+use crate::Answer;
+
+/// Another implementation of a part
+pub struct Variant<I> {
+    pub name: &'static str,
+    pub part: u8,
+    pub run: fn(&I) -> Result<Answer>,
+}
+
+/// A day with several implementations of some of its parts
+pub trait Differential: Generate {
+    /// Size of the inputs, small enough for the slowest implementation
+    const DIFF_SIZE: usize = <Self as Generate>::SIZE;
+
+    /// Implementations checked against the parts of the solution
+    fn variants() -> Vec<Variant<Self::Input>>;
+}
+
+/// Answer of each implementation, or its error
+pub type Outcomes = Vec<(&'static str, Result<String, String>)>;
+
+/// Name of the parts of the solution among the implementations
+const REFERENCE: &str = "solution";
+
+/// Name of the outcome of an input that does not parse
+const PARSE: &str = "parse";
+
+/// Implementations of a part, the solution first
+fn implementations<S: Differential>(part: u8) -> Vec<Variant<S::Input>> {
+    let reference = Variant {
+        name: REFERENCE,
+        part,
+        run: match part {
+            1 => S::part1,
+            _ => S::part2,
+        },
+    };
+    std::iter::once(reference)
+        .chain(S::variants().into_iter().filter(|v| v.part == part))
+        .collect()
+}
+
+thread_local! {
+    /// Set while [`run`] catches the panics of an implementation
+    static CATCHING: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Panics caught by [`run`] are not printed, the other ones go to the hook
+/// that was installed before
+fn install_quiet_hook() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if !CATCHING.get() {
+                previous(info)
+            }
+        }));
+    });
+}
+
+/// Result of `f`, a panic counting as an error
+fn run<T>(f: impl FnOnce() -> Result<T>) -> Result<T, String> {
+    CATCHING.set(true);
+    let result = panic::catch_unwind(AssertUnwindSafe(f));
+    CATCHING.set(false);
+    match result {
+        Ok(result) => result.map_err(|e| format!("{:#}", e)),
+        Err(payload) => {
+            let message = payload
+                .downcast_ref::<&str>()
+                .map(|s| s.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_default();
+            Err(format!("panicked: {}", message))
+        }
+    }
+}
+
+/// Run every implementation on an input, or give the error parsing it
+fn run_all<S: Differential>(input: &str, part: u8) -> Result<Outcomes, String> {
+    let parsed = run(|| S::parse(input))?;
+    let outcomes = implementations::<S>(part)
+        .iter()
+        .map(|v| (v.name, run(|| (v.run)(&parsed).map(|a| a.to_string()))))
+        .collect();
+    Ok(outcomes)
+}
+
+/// Two answers that differ, or an answer and an error. Errors are equal to
+/// each other whatever their message
+fn disagree(outcomes: &Outcomes) -> bool {
+    outcomes
+        .windows(2)
+        .any(|w| w[0].1.as_ref().ok() != w[1].1.as_ref().ok())
+}
+
+/// Input on which the implementations of a part disagree, or generated input
+/// that does not parse
+#[derive(Debug, Clone)]
+pub struct Mismatch {
+    pub day: u8,
+    pub part: u8,
+    pub seed: u64,
+    /// Shrunk input
+    pub input: String,
+    pub outcomes: Outcomes,
+}
+
+impl Display for Mismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.outcomes.iter().any(|(name, _)| *name == PARSE) {
+            writeln!(
+                f,
+                "day {} part {}: input from seed {} does not parse:",
+                self.day, self.part, self.seed
+            )?;
+        } else {
+            writeln!(
+                f,
+                "day {} part {}: implementations disagree on input from seed {}, shrunk to:",
+                self.day, self.part, self.seed
+            )?;
+        }
+        write!(f, "{}", self.input)?;
+        for (name, outcome) in self.outcomes.iter() {
+            match outcome {
+                Ok(answer) => write!(f, "\n  {}: {}", name, answer)?,
+                Err(e) => write!(f, "\n  {}: error: {}", name, e)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for Mismatch {}
+
+/// Smaller input on which the implementations still disagree
+fn shrink<S: Differential>(seed: u64, size: usize, part: u8) -> String {
+    let fails = |input: &str| run_all::<S>(input, part).is_ok_and(|o| disagree(&o));
+
+    let mut size = size;
+    while size > 1 && fails(&crate::gen::generate::<S>(seed, Some(size / 2))) {
+        size /= 2;
+    }
+    let mut lines: Vec<String> = crate::gen::generate::<S>(seed, Some(size))
+        .lines()
+        .map(str::to_string)
+        .collect();
+
+    // Remove chunks of lines, halving their length when none can go
+    let join = |lines: &[String]| lines.iter().map(|l| format!("{}\n", l)).collect::<String>();
+    let mut chunk = lines.len() / 2;
+    while chunk > 0 {
+        let mut removed = false;
+        let mut i = 0;
+        while i < lines.len() {
+            let mut candidate = lines.clone();
+            candidate.drain(i..(i + chunk).min(lines.len()));
+            if fails(&join(&candidate)) {
+                lines = candidate;
+                removed = true;
+            } else {
+                i += chunk;
+            }
+        }
+        chunk = match removed {
+            true => chunk.min(lines.len() / 2).max(1),
+            false => chunk / 2,
+        };
+    }
+    join(&lines)
+}
+
+/// Run every implementation of the parts with variants on the inputs
+/// generated from `seeds`, of [`Differential::DIFF_SIZE`] by default. Stops at
+/// the first disagreement, shrinking its input
+pub fn check<S: Differential>(
+    seeds: impl IntoIterator<Item = u64>,
+    size: Option<usize>,
+) -> Result<(), Mismatch> {
+    // Shrinking can catch hundreds of panics, only the other ones are printed
+    install_quiet_hook();
+    let size = size.unwrap_or(S::DIFF_SIZE);
+    let mut parts: Vec<u8> = S::variants().iter().map(|v| v.part).collect();
+    parts.sort();
+    parts.dedup();
+    for seed in seeds {
+        let input = crate::gen::generate::<S>(seed, Some(size));
+        for &part in parts.iter() {
+            let outcomes = match run_all::<S>(&input, part) {
+                Ok(outcomes) => outcomes,
+                Err(e) => {
+                    return Err(Mismatch {
+                        day: S::DAY,
+                        part,
+                        seed,
+                        input,
+                        outcomes: vec![(PARSE, Err(e))],
+                    })
+                }
+            };
+            if disagree(&outcomes) {
+                let input = shrink::<S>(seed, size, part);
+                return Err(Mismatch {
+                    day: S::DAY,
+                    part,
+                    seed,
+                    outcomes: run_all::<S>(&input, part).unwrap_or(outcomes),
+                    input,
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gen::Rng;
+    use crate::Solution;
+
+    /// Sum of numbers from 0 to 99, one per line
+    struct Sum;
+
+    impl Solution for Sum {
+        const DAY: u8 = 1;
+        type Input = Vec<usize>;
+
+        fn parse(input: &str) -> Result<Self::Input> {
+            Ok(crate::parse::lines(input, |l| {
+                crate::parse::value(l, "invalid number")
+            })?)
+        }
+
+        fn part1(numbers: &Self::Input) -> Result<Answer> {
+            Ok(numbers.iter().sum::<usize>().into())
+        }
+
+        fn part2(numbers: &Self::Input) -> Result<Answer> {
+            Ok(numbers.len().into())
+        }
+    }
+
+    impl Generate for Sum {
+        const SIZE: usize = 64;
+
+        fn generate(rng: &mut Rng, size: usize) -> String {
+            (0..size)
+                .map(|_| format!("{}\n", rng.usize(0..=99)))
+                .collect()
+        }
+    }
+
+    impl Differential for Sum {
+        fn variants() -> Vec<Variant<Self::Input>> {
+            vec![Variant {
+                name: "reversed",
+                part: 1,
+                run: |numbers| Ok(numbers.iter().rev().sum::<usize>().into()),
+            }]
+        }
+    }
+
+    /// Only the variant of part `PART`: part 1 counts the numbers above 90
+    /// twice, part 2 panics on them
+    struct Buggy<const PART: u8>;
+
+    impl<const PART: u8> Solution for Buggy<PART> {
+        const DAY: u8 = 1;
+        type Input = Vec<usize>;
+
+        fn parse(input: &str) -> Result<Self::Input> {
+            Sum::parse(input)
+        }
+
+        fn part1(numbers: &Self::Input) -> Result<Answer> {
+            Sum::part1(numbers)
+        }
+
+        fn part2(numbers: &Self::Input) -> Result<Answer> {
+            Sum::part2(numbers)
+        }
+    }
+
+    impl<const PART: u8> Generate for Buggy<PART> {
+        const SIZE: usize = Sum::SIZE;
+
+        fn generate(rng: &mut Rng, size: usize) -> String {
+            Sum::generate(rng, size)
+        }
+    }
+
+    impl<const PART: u8> Differential for Buggy<PART> {
+        fn variants() -> Vec<Variant<Self::Input>> {
+            let variants: Vec<Variant<Self::Input>> = vec![
+                Variant {
+                    name: "doubled",
+                    part: 1,
+                    run: |numbers| {
+                        let doubled = numbers.iter().filter(|&&n| n > 90).sum::<usize>();
+                        Ok((numbers.iter().sum::<usize>() + doubled).into())
+                    },
+                },
+                Variant {
+                    name: "panicking",
+                    part: 2,
+                    run: |numbers| {
+                        assert!(numbers.iter().all(|&n| n <= 90), "too large");
+                        Ok(numbers.len().into())
+                    },
+                },
+            ];
+            variants.into_iter().filter(|v| v.part == PART).collect()
+        }
+    }
+
+    /// Generates a line that is not a number after the ones of [`Sum`]
+    struct Garbled;
+
+    impl Solution for Garbled {
+        const DAY: u8 = 1;
+        type Input = Vec<usize>;
+
+        fn parse(input: &str) -> Result<Self::Input> {
+            Sum::parse(input)
+        }
+
+        fn part1(numbers: &Self::Input) -> Result<Answer> {
+            Sum::part1(numbers)
+        }
+
+        fn part2(numbers: &Self::Input) -> Result<Answer> {
+            Sum::part2(numbers)
+        }
+    }
+
+    impl Generate for Garbled {
+        const SIZE: usize = Sum::SIZE;
+
+        fn generate(rng: &mut Rng, size: usize) -> String {
+            Sum::generate(rng, size) + "?\n"
+        }
+    }
+
+    impl Differential for Garbled {
+        fn variants() -> Vec<Variant<Self::Input>> {
+            Sum::variants()
+        }
+    }
+
+    fn outcomes(results: &[Result<&str, &str>]) -> Outcomes {
+        results
+            .iter()
+            .map(|r| ("", r.map(str::to_string).map_err(str::to_string)))
+            .collect()
+    }
+
+    #[test]
+    fn disagreement() {
+        assert!(!disagree(&outcomes(&[Ok("1"), Ok("1"), Ok("1")])));
+        assert!(disagree(&outcomes(&[Ok("1"), Ok("1"), Ok("2")])));
+        assert!(disagree(&outcomes(&[Ok("1"), Err("invalid")])));
+        assert!(!disagree(&outcomes(&[Err("invalid"), Err("panicked: ")])));
+        assert!(!disagree(&outcomes(&[Ok("1")])));
+    }
+
+    #[test]
+    fn agreeing_variants() {
+        check::<Sum>(0..10, None).unwrap();
+    }
+
+    #[test]
+    fn shrunk_mismatch() {
+        let mismatch = check::<Buggy<1>>(0..10, None).unwrap_err();
+        assert_eq!((mismatch.day, mismatch.part), (1, 1));
+        // A single number above 90 is enough
+        let numbers = Sum::parse(&mismatch.input).unwrap();
+        assert_eq!(numbers.len(), 1, "{}", mismatch);
+        assert!(numbers[0] > 90);
+        let n = numbers[0];
+        let expected = vec![
+            ("solution", Ok(n.to_string())),
+            ("doubled", Ok((2 * n).to_string())),
+        ];
+        assert_eq!(mismatch.outcomes, expected);
+    }
+
+    #[test]
+    fn shrunk_panic() {
+        let mismatch = check::<Buggy<2>>(0..10, None).unwrap_err();
+        assert_eq!(mismatch.part, 2);
+        let numbers = Sum::parse(&mismatch.input).unwrap();
+        assert_eq!(numbers.len(), 1, "{}", mismatch);
+        assert!(numbers[0] > 90);
+        let expected = vec![
+            ("solution", Ok("1".to_string())),
+            ("panicking", Err("panicked: too large".to_string())),
+        ];
+        assert_eq!(mismatch.outcomes, expected);
+    }
+
+    #[test]
+    fn unparsable_input() {
+        let mismatch = check::<Garbled>(0..10, None).unwrap_err();
+        assert_eq!((mismatch.part, mismatch.seed), (1, 0));
+        assert_eq!(mismatch.input, crate::gen::generate::<Garbled>(0, None));
+        let expected = "line 65, column 1: invalid number: invalid digit found in string in \"?\"";
+        assert_eq!(
+            mismatch.outcomes,
+            vec![("parse", Err(expected.to_string()))]
+        );
+        assert!(mismatch
+            .to_string()
+            .starts_with("day 1 part 1: input from seed 0 does not parse:\n"));
+    }
+}
@@ -0,0 +1,86 @@
+//! Random puzzle inputs for stress testing, written by `aoc gen`.
+//!
+//! The generator is seeded and self contained so that a seed gives the same
+//! input on every platform and with every version of the dependencies.
+
+use std::ops::RangeInclusive;
+
+use crate::Solution;
+
+/// SplitMix64 pseudo random generator
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform value in `0..n`, `n` must not be zero
+    pub fn below(&mut self, n: u64) -> u64 {
+        assert!(n > 0, "empty range");
+        // Widening multiplication, the bias is negligible for the ranges used here
+        ((self.next_u64() as u128 * n as u128) >> 64) as u64
+    }
+
+    pub fn usize(&mut self, range: RangeInclusive<usize>) -> usize {
+        let (start, end) = range.into_inner();
+        assert!(start <= end, "empty range");
+        start + self.below((end - start) as u64 + 1) as usize
+    }
+
+    pub fn isize(&mut self, range: RangeInclusive<isize>) -> isize {
+        let (start, end) = range.into_inner();
+        assert!(start <= end, "empty range");
+        start + self.below(end.abs_diff(start) as u64 + 1) as isize
+    }
+
+    /// `true` with probability `p`
+    pub fn chance(&mut self, p: f64) -> bool {
+        ((self.next_u64() >> 11) as f64) < p * (1u64 << 53) as f64
+    }
+
+    pub fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len() as u64) as usize]
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = self.below(i as u64 + 1) as usize;
+            items.swap(i, j);
+        }
+    }
+
+    /// Random lowercase word with a length in `len`
+    pub fn word(&mut self, len: RangeInclusive<usize>) -> String {
+        let len = self.usize(len);
+        (0..len)
+            .map(|_| (b'a' + self.below(26) as u8) as char)
+            .collect()
+    }
+}
+
+/// A day able to write random inputs its parser accepts
+pub trait Generate: Solution {
+    /// Size giving inputs about as large as the real ones
+    const SIZE: usize;
+
+    /// Random input in the exact format of the puzzle. What the size counts
+    /// (lines, side of a grid...) depends on the day
+    fn generate(rng: &mut Rng, size: usize) -> String;
+}
+
+/// Input of the given size, or of the size of the real inputs
+pub fn generate<S: Generate>(seed: u64, size: Option<usize>) -> String {
+    S::generate(&mut Rng::new(seed), size.unwrap_or(S::SIZE))
+}
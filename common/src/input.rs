@@ -0,0 +1,141 @@
+use std::{
+    env,
+    io::Read,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, Context, Result};
+
+/// Environment variable pointing to a directory laid out like this repository,
+/// i.e. containing a `dayN/input` file for each day
+pub const INPUT_DIR_VAR: &str = "AOC_INPUT";
+
+/// Where to read a day's puzzle input from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputSource {
+    /// `dayN/input` inside `$AOC_INPUT`, or inside the workspace if unset
+    Default,
+    Stdin,
+    Path(PathBuf),
+}
+
+impl InputSource {
+    /// `-` means stdin, anything else is a file path
+    pub fn from_arg(arg: Option<&str>) -> Self {
+        match arg {
+            None => InputSource::Default,
+            Some("-") => InputSource::Stdin,
+            Some(p) => InputSource::Path(PathBuf::from(p)),
+        }
+    }
+
+    pub fn load(&self, day: u8) -> Result<String> {
+        let input = match self {
+            InputSource::Default => {
+                let path = default_path(day);
+                read_file(&path).with_context(|| {
+                    format!(
+                        "no input for day {}, pass a path or set {} to a directory containing day{}/input",
+                        day, INPUT_DIR_VAR, day
+                    )
+                })?
+            }
+            InputSource::Stdin => {
+                let mut input = String::new();
+                std::io::stdin()
+                    .read_to_string(&mut input)
+                    .context("failed to read input from stdin")?;
+                input
+            }
+            InputSource::Path(path) => read_file(path)?,
+        };
+        if input.trim().is_empty() {
+            return Err(anyhow!("input for day {} is empty", day));
+        }
+        Ok(input)
+    }
+}
+
+/// Input directory: `$AOC_INPUT` if set, the workspace root otherwise
+pub fn input_dir() -> PathBuf {
+    match env::var_os(INPUT_DIR_VAR) {
+        Some(dir) => PathBuf::from(dir),
+        None => Path::new(env!("CARGO_MANIFEST_DIR")).join(".."),
+    }
+}
+
+pub fn default_path(day: u8) -> PathBuf {
+    input_dir().join(format!("day{}", day)).join("input")
+}
+
+fn read_file(path: &Path) -> Result<String> {
+    std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read input file {}", path.display()))
+}
+
+/// Load the input of a day binary, from its first command line argument if any
+pub fn from_args(day: u8) -> Result<String> {
+    let arg = env::args().nth(1);
+    InputSource::from_arg(arg.as_deref()).load(day)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    #[test]
+    fn sources() {
+        assert_eq!(InputSource::from_arg(None), InputSource::Default);
+        assert_eq!(InputSource::from_arg(Some("-")), InputSource::Stdin);
+        assert_eq!(
+            InputSource::from_arg(Some("day1/example")),
+            InputSource::Path(PathBuf::from("day1/example"))
+        );
+    }
+
+    /// The only test reading `AOC_INPUT`, as the environment is shared by the threads
+    #[test]
+    fn input_dir_variable() {
+        let dir = env::temp_dir().join(format!("aoc-input-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        for day in [1, 2, 3] {
+            fs::create_dir_all(dir.join(format!("day{}", day))).unwrap();
+        }
+        fs::write(dir.join("day1/input"), "1000\n2000\n").unwrap();
+        fs::write(dir.join("day2/input"), "").unwrap();
+        fs::write(dir.join("day3/input"), " \n\n").unwrap();
+
+        env::set_var(INPUT_DIR_VAR, &dir);
+        assert_eq!(default_path(1), dir.join("day1").join("input"));
+        assert_eq!(InputSource::Default.load(1).unwrap(), "1000\n2000\n");
+        for day in [2, 3] {
+            let error = InputSource::Default.load(day).unwrap_err();
+            assert_eq!(error.to_string(), format!("input for day {} is empty", day));
+        }
+        let error = format!("{:#}", InputSource::Default.load(4).unwrap_err());
+        assert!(
+            error.starts_with(&format!(
+                "no input for day 4, pass a path or set {} to a directory containing day4/input: failed to read input file",
+                INPUT_DIR_VAR
+            )),
+            "{}",
+            error
+        );
+
+        // An explicit path does not depend on the variable
+        let path = InputSource::Path(dir.join("day1/input"));
+        env::remove_var(INPUT_DIR_VAR);
+        assert_eq!(path.load(7).unwrap(), "1000\n2000\n");
+        let error = InputSource::Path(dir.join("day2/input"))
+            .load(2)
+            .unwrap_err();
+        assert_eq!(error.to_string(), "input for day 2 is empty");
+        assert_eq!(
+            input_dir(),
+            Path::new(env!("CARGO_MANIFEST_DIR")).join("..")
+        );
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,15 @@
+pub mod alloc;
+pub mod animate;
+pub mod bench;
+pub mod cycle;
+pub mod diff;
+pub mod gen;
+pub mod input;
+pub mod optimize;
+pub mod parse;
+pub mod point;
+pub mod search;
+pub mod solution;
+pub mod trace;
+
+pub use solution::{Answer, Solution};
@@ -0,0 +1,375 @@
+//! Searches for the highest scoring node of a tree of choices, for the puzzles
+//! maximising a value rather than looking for a goal.
+//!
+//! A [`Problem`] gives the successors of a node and its score.
+//! [`branch_and_bound`] explores the whole tree, skipping the subtrees whose
+//! [`Problem::bound`] cannot beat the best score found so far, while [`beam`]
+//! only keeps the most promising nodes of each depth. Both skip the nodes whose
+//! [`Problem::key`] was already reached with a score at least as good.
+
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    fmt::Debug,
+    hash::Hash,
+};
+
+pub trait Problem {
+    type Node;
+    type Score: Copy + Ord + Debug;
+    /// Part of a node that decides everything its descendants can gain, `()`
+    /// when nodes are not memoised
+    type Key: Eq + Hash;
+
+    /// Nodes reachable with one choice, tried in order by the depth-first search
+    fn successors(&self, node: &Self::Node) -> Vec<Self::Node>;
+
+    /// Value of a node, the search looks for the highest one
+    fn score(&self, node: &Self::Node) -> Self::Score;
+
+    /// Highest score any descendant of the node could reach, `None` when unknown
+    fn bound(&self, _node: &Self::Node) -> Option<Self::Score> {
+        None
+    }
+
+    /// Nodes with the same key have the same descendants, up to their score
+    fn key(&self, _node: &Self::Node) -> Option<Self::Key> {
+        None
+    }
+}
+
+/// Work done by a search
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Stats {
+    /// Nodes whose successors were generated
+    pub explored: usize,
+    /// Nodes skipped because of their bound, or dropped from the beam
+    pub pruned: usize,
+    /// Nodes skipped because their key was reached with a better score
+    pub memoised: usize,
+}
+
+/// Highest scoring node found
+#[derive(Debug, Clone)]
+pub struct Outcome<N, S> {
+    pub node: N,
+    pub score: S,
+    pub stats: Stats,
+}
+
+/// Best score reached by each key
+struct Memo<K, S> {
+    scores: HashMap<K, S>,
+}
+
+impl<K: Eq + Hash, S: Copy + Ord> Memo<K, S> {
+    /// `false` when the key was already reached with a score at least as good
+    fn improves(&mut self, key: Option<K>, score: S) -> bool {
+        let Some(key) = key else {
+            return true;
+        };
+        match self.scores.entry(key) {
+            Entry::Vacant(e) => {
+                e.insert(score);
+                true
+            }
+            Entry::Occupied(mut e) if *e.get() < score => {
+                e.insert(score);
+                true
+            }
+            Entry::Occupied(_) => false,
+        }
+    }
+}
+
+/// Depth-first search calling `visit` on every node that was not skipped.
+/// `prune` tells whether the subtree of a node is worth exploring
+fn dfs<P: Problem>(
+    problem: &P,
+    start: P::Node,
+    mut prune: impl FnMut(&P::Node) -> bool,
+    mut visit: impl FnMut(&P::Node),
+) -> Stats {
+    let mut stats = Stats::default();
+    let mut memo = Memo {
+        scores: HashMap::new(),
+    };
+    let mut stack = vec![start];
+    while let Some(node) = stack.pop() {
+        if !memo.improves(problem.key(&node), problem.score(&node)) {
+            stats.memoised += 1;
+            continue;
+        }
+        visit(&node);
+        if prune(&node) {
+            stats.pruned += 1;
+            continue;
+        }
+        stats.explored += 1;
+        let mut successors = problem.successors(&node);
+        successors.reverse();
+        stack.extend(successors);
+    }
+    stats
+}
+
+/// Visit every node of the tree, except the memoised ones
+pub fn explore<P: Problem>(problem: &P, start: P::Node, visit: impl FnMut(&P::Node)) -> Stats {
+    dfs(problem, start, |_| false, visit)
+}
+
+/// Exhaustive depth-first search for the highest score. Good successors first
+/// make the bound prune more
+pub fn branch_and_bound<P: Problem>(problem: &P, start: P::Node) -> Outcome<P::Node, P::Score>
+where
+    P::Node: Clone,
+{
+    let mut best = (problem.score(&start), start.clone());
+    // The visit and the pruning both need the best score, the visit runs first
+    let best_score = std::cell::Cell::new(best.0);
+    let stats = dfs(
+        problem,
+        start,
+        |node| {
+            problem
+                .bound(node)
+                .is_some_and(|bound| bound <= best_score.get())
+        },
+        |node| {
+            let score = problem.score(node);
+            if score > best.0 {
+                best = (score, node.clone());
+                best_score.set(score);
+            }
+        },
+    );
+    crate::event!(INFO, score = ?best.0, ?stats, "branch and bound done");
+    Outcome {
+        node: best.1,
+        score: best.0,
+        stats,
+    }
+}
+
+/// Breadth-first search keeping only the `width` nodes of each depth ranked
+/// highest by `rank`. Fast, but it can miss the best node
+pub fn beam<P: Problem, R: Ord>(
+    problem: &P,
+    start: P::Node,
+    width: usize,
+    mut rank: impl FnMut(&P::Node) -> R,
+) -> Outcome<P::Node, P::Score>
+where
+    P::Node: Clone,
+{
+    let mut stats = Stats::default();
+    let mut memo = Memo {
+        scores: HashMap::new(),
+    };
+    let mut best = (problem.score(&start), start.clone());
+    let mut layer = vec![start];
+    let mut depth = 0;
+    while !layer.is_empty() {
+        depth += 1;
+        let mut next = vec![];
+        for node in layer.iter() {
+            stats.explored += 1;
+            for successor in problem.successors(node) {
+                let score = problem.score(&successor);
+                if !memo.improves(problem.key(&successor), score) {
+                    stats.memoised += 1;
+                    continue;
+                }
+                if score > best.0 {
+                    best = (score, successor.clone());
+                }
+                next.push(successor);
+            }
+        }
+        if next.len() > width {
+            next.sort_by_cached_key(|n| std::cmp::Reverse(rank(n)));
+            stats.pruned += next.len() - width;
+            next.truncate(width);
+        }
+        crate::event!(DEBUG, depth, nodes = next.len(), best = ?best.0, "beam level");
+        layer = next;
+    }
+    crate::event!(INFO, score = ?best.0, ?stats, "beam done");
+    Outcome {
+        node: best.1,
+        score: best.0,
+        stats,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cmp::Reverse;
+
+    /// Takes or skips each item in turn, scoring the value taken. A node is the
+    /// index of the next item and the value taken so far
+    struct Items {
+        values: Vec<usize>,
+        /// Try skipping an item before taking it
+        skip_first: bool,
+        bounded: bool,
+        /// Key the nodes by their index, the items left being the same
+        memoised: bool,
+    }
+
+    fn items(values: &[usize]) -> Items {
+        Items {
+            values: values.to_vec(),
+            skip_first: false,
+            bounded: false,
+            memoised: false,
+        }
+    }
+
+    impl Problem for Items {
+        type Node = (usize, usize);
+        type Score = usize;
+        type Key = usize;
+
+        fn successors(&self, &(i, taken): &(usize, usize)) -> Vec<(usize, usize)> {
+            let Some(value) = self.values.get(i) else {
+                return vec![];
+            };
+            let mut successors = vec![(i + 1, taken + value), (i + 1, taken)];
+            if self.skip_first {
+                successors.reverse();
+            }
+            successors
+        }
+
+        fn score(&self, &(_, taken): &(usize, usize)) -> usize {
+            taken
+        }
+
+        fn bound(&self, &(i, taken): &(usize, usize)) -> Option<usize> {
+            let left: usize = self.values[i..].iter().sum();
+            self.bounded.then_some(taken + left)
+        }
+
+        fn key(&self, &(i, _): &(usize, usize)) -> Option<usize> {
+            self.memoised.then_some(i)
+        }
+    }
+
+    fn stats(explored: usize, pruned: usize, memoised: usize) -> Stats {
+        Stats {
+            explored,
+            pruned,
+            memoised,
+        }
+    }
+
+    #[test]
+    fn explore_every_node() {
+        let mut visited = vec![];
+        let stats = explore(&items(&[5, 3, 2]), (0, 0), |&node| visited.push(node));
+        assert_eq!(stats, self::stats(15, 0, 0));
+        assert_eq!(visited.len(), 15);
+        assert_eq!(&visited[..4], &[(0, 0), (1, 5), (2, 8), (3, 10)]);
+        assert_eq!(visited.last(), Some(&(3, 0)));
+    }
+
+    #[test]
+    fn bound_pruning() {
+        let exhaustive = branch_and_bound(&items(&[5, 3, 2]), (0, 0));
+        assert_eq!((exhaustive.node, exhaustive.score), ((3, 10), 10));
+        assert_eq!(exhaustive.stats, stats(15, 0, 0));
+
+        // Taking everything first, no other node can do better
+        let bounded = Items {
+            bounded: true,
+            ..items(&[5, 3, 2])
+        };
+        let best = branch_and_bound(&bounded, (0, 0));
+        assert_eq!((best.node, best.score), ((3, 10), 10));
+        assert_eq!(best.stats, stats(3, 4, 0));
+
+        // Skipping first, the best node is found last and only the leaves are
+        // pruned
+        let skipping = Items {
+            skip_first: true,
+            ..bounded
+        };
+        let best = branch_and_bound(&skipping, (0, 0));
+        assert_eq!((best.node, best.score), ((3, 10), 10));
+        assert_eq!(best.stats, stats(7, 8, 0));
+    }
+
+    #[test]
+    fn memo_skipping() {
+        // Every node after the first one of its depth does worse
+        let memoised = Items {
+            memoised: true,
+            ..items(&[5, 3, 2])
+        };
+        let best = branch_and_bound(&memoised, (0, 0));
+        assert_eq!(best.score, 10);
+        assert_eq!(best.stats, stats(4, 0, 3));
+
+        // Equal scores are skipped too
+        let mut visited = vec![];
+        let memoised = Items {
+            memoised: true,
+            ..items(&[5, 0])
+        };
+        explore(&memoised, (0, 0), |&node| visited.push(node));
+        assert_eq!(visited, vec![(0, 0), (1, 5), (2, 5)]);
+
+        // Better nodes reached later are explored again
+        let skipping = Items {
+            memoised: true,
+            skip_first: true,
+            ..items(&[5, 3, 2])
+        };
+        let best = branch_and_bound(&skipping, (0, 0));
+        assert_eq!(best.score, 10);
+        assert_eq!(best.stats, stats(14, 0, 1));
+    }
+
+    #[test]
+    fn beam_width() {
+        let problem = items(&[5, 3, 2]);
+        let wide = beam(&problem, (0, 0), 8, |&(_, taken)| taken);
+        assert_eq!((wide.node, wide.score), ((3, 10), 10));
+        assert_eq!(wide.stats, stats(15, 0, 0));
+
+        // One node per depth, the two successors of each kept node compete
+        let narrow = beam(&problem, (0, 0), 1, |&(_, taken)| taken);
+        assert_eq!((narrow.node, narrow.score), ((3, 10), 10));
+        assert_eq!(narrow.stats, stats(4, 3, 0));
+
+        let pair = beam(&problem, (0, 0), 2, |&(_, taken)| taken);
+        assert_eq!(pair.score, 10);
+        assert_eq!(pair.stats, stats(1 + 2 + 2 + 2, 2 + 2, 0));
+    }
+
+    #[test]
+    fn beam_ranking() {
+        // Keeping the worst nodes, the best one is whichever was seen before
+        // being dropped
+        let problem = items(&[5, 3, 2]);
+        let worst = beam(&problem, (0, 0), 1, |&(_, taken)| Reverse(taken));
+        assert_eq!((worst.node, worst.score), ((1, 5), 5));
+        assert_eq!(worst.stats, stats(4, 3, 0));
+
+        // Ties keep the successors order
+        let first = beam(&problem, (0, 0), 1, |_| ());
+        assert_eq!((first.node, first.score), ((3, 10), 10));
+    }
+
+    #[test]
+    fn beam_memo() {
+        let memoised = Items {
+            memoised: true,
+            ..items(&[5, 3, 2])
+        };
+        let best = beam(&memoised, (0, 0), 8, |&(_, taken)| taken);
+        assert_eq!(best.score, 10);
+        assert_eq!(best.stats, stats(4, 0, 3));
+    }
+}
@@ -0,0 +1,270 @@
+//! Parse errors pointing at the offending part of the puzzle input
+
+use std::{fmt::Display, str::FromStr};
+
+/// Error raised while parsing the input, with its location when known.
+/// Lines and columns start at 1.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    /// The offending text
+    pub text: String,
+    pub message: String,
+}
+
+impl ParseError {
+    pub fn new(message: impl Display, text: &str) -> Self {
+        ParseError {
+            line: None,
+            column: None,
+            text: text.to_string(),
+            message: message.to_string(),
+        }
+    }
+
+    pub fn at_column(mut self, column: usize) -> Self {
+        self.column = Some(column);
+        self
+    }
+
+    pub fn at(mut self, line: usize, column: usize) -> Self {
+        self.line = Some(line);
+        self.column = Some(column);
+        self
+    }
+
+    /// Locate the error inside `chunk`, which starts at line `first_line`.
+    ///
+    /// An error already carrying a line is relative to `chunk`, otherwise
+    /// the offending text is looked up in it.
+    pub fn locate(mut self, first_line: usize, chunk: &str) -> Self {
+        if let Some(line) = self.line {
+            self.line = Some(first_line + line - 1);
+            return self;
+        }
+        let pos = (!self.text.is_empty())
+            .then(|| chunk.find(&self.text))
+            .flatten();
+        match pos {
+            Some(pos) => {
+                let before = &chunk[..pos];
+                self.line = Some(first_line + before.matches('\n').count());
+                if self.column.is_none() {
+                    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+                    self.column = Some(chunk[line_start..pos].chars().count() + 1);
+                }
+            }
+            None => self.line = Some(first_line),
+        }
+        self
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.line, self.column) {
+            (Some(line), Some(column)) => write!(f, "line {}, column {}: ", line, column)?,
+            (Some(line), None) => write!(f, "line {}: ", line)?,
+            (None, Some(column)) => write!(f, "column {}: ", column)?,
+            (None, None) => {}
+        }
+        write!(f, "{} in {:?}", self.message, self.text)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Turn a missing value or a foreign error into a [`ParseError`] on `text`
+pub trait ParseContext<T> {
+    fn parse_context(self, message: &str, text: &str) -> Result<T, ParseError>;
+}
+
+impl<T> ParseContext<T> for Option<T> {
+    fn parse_context(self, message: &str, text: &str) -> Result<T, ParseError> {
+        self.ok_or_else(|| ParseError::new(message, text))
+    }
+}
+
+impl<T, E: Display> ParseContext<T> for Result<T, E> {
+    fn parse_context(self, message: &str, text: &str) -> Result<T, ParseError> {
+        self.map_err(|e| ParseError::new(format!("{}: {}", message, e), text))
+    }
+}
+
+/// Parse a single value, e.g. a number, reporting `text` on failure
+pub fn value<T>(text: &str, message: &str) -> Result<T, ParseError>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    text.parse().parse_context(message, text)
+}
+
+/// Parse each line of `input`, errors are located in the input
+pub fn lines<'a, T>(
+    input: &'a str,
+    mut f: impl FnMut(&'a str) -> Result<T, ParseError>,
+) -> Result<Vec<T>, ParseError> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| f(line).map_err(|e| e.locate(i + 1, line)))
+        .collect()
+}
+
+/// Byte range of the first blank line separating two parts of `input`,
+/// with either LF or CRLF line endings
+fn blank_line(input: &str) -> Option<(usize, usize)> {
+    let lf = input.find("\n\n").map(|i| (i, i + 2));
+    let crlf = input.find("\r\n\r\n").map(|i| (i, i + 4));
+    match (lf, crlf) {
+        (Some(lf), Some(crlf)) => Some(lf.min(crlf)),
+        (lf, crlf) => lf.or(crlf),
+    }
+}
+
+/// Parse each blank line separated block of `input`, errors are located in the input
+pub fn blocks<'a, T>(
+    input: &'a str,
+    mut f: impl FnMut(&'a str) -> Result<T, ParseError>,
+) -> Result<Vec<T>, ParseError> {
+    let mut res = vec![];
+    let mut first_line = 1;
+    let mut rest = Some(input);
+    while let Some(chunk) = rest {
+        let block = match blank_line(chunk) {
+            Some((end, next)) => {
+                rest = Some(&chunk[next..]);
+                &chunk[..end]
+            }
+            None => {
+                rest = None;
+                chunk
+            }
+        };
+        res.push(f(block).map_err(|e| e.locate(first_line, block))?);
+        first_line += block.matches('\n').count() + 2;
+    }
+    Ok(res)
+}
+
+/// Split `input` in two at the first blank line, giving the line the second part starts at
+pub fn sections(input: &str) -> Result<(&str, &str, usize), ParseError> {
+    let (end, next) = blank_line(input).parse_context("missing blank line between sections", "")?;
+    let first = &input[..end];
+    Ok((first, &input[next..], first.matches('\n').count() + 3))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn number(text: &str) -> Result<u32, ParseError> {
+        value(text, "invalid number")
+    }
+
+    #[test]
+    fn display() {
+        let error = ParseError::new("invalid number", "x");
+        assert_eq!(error.to_string(), "invalid number in \"x\"");
+        assert_eq!(
+            error.clone().at_column(3).to_string(),
+            "column 3: invalid number in \"x\""
+        );
+        assert_eq!(
+            error.at(2, 3).to_string(),
+            "line 2, column 3: invalid number in \"x\""
+        );
+    }
+
+    #[test]
+    fn locate() {
+        let chunk = "1 2\n3 x 4\n";
+        let error = ParseError::new("invalid number", "x").locate(5, chunk);
+        assert_eq!((error.line, error.column), (Some(6), Some(3)));
+
+        // A known column is kept, a known line is made relative to the chunk
+        let error = ParseError::new("invalid number", "x")
+            .at_column(9)
+            .locate(5, chunk);
+        assert_eq!((error.line, error.column), (Some(6), Some(9)));
+        let error = ParseError::new("invalid number", "x")
+            .at(2, 1)
+            .locate(5, chunk);
+        assert_eq!((error.line, error.column), (Some(6), Some(1)));
+
+        // Text missing from the chunk, or empty, only gives the first line
+        for text in ["y", ""] {
+            let error = ParseError::new("invalid number", text).locate(5, chunk);
+            assert_eq!((error.line, error.column), (Some(5), None));
+        }
+
+        // Columns count characters, not bytes
+        let error = ParseError::new("invalid number", "x").locate(1, "é x");
+        assert_eq!(error.column, Some(3));
+    }
+
+    #[test]
+    fn parse_context() {
+        let error = None::<u32>
+            .parse_context("missing number", "a")
+            .unwrap_err();
+        assert_eq!(error, ParseError::new("missing number", "a"));
+        let error = "a".parse::<u32>().parse_context("bad", "a").unwrap_err();
+        assert_eq!(error.message, "bad: invalid digit found in string");
+        assert_eq!(Some(1).parse_context("missing number", "a"), Ok(1));
+    }
+
+    #[test]
+    fn values() {
+        assert_eq!(number("12"), Ok(12));
+        let error = number("-1").unwrap_err();
+        assert_eq!(error.text, "-1");
+        assert!(error.message.starts_with("invalid number: "));
+        assert_eq!((error.line, error.column), (None, None));
+    }
+
+    #[test]
+    fn lines_are_located() {
+        assert_eq!(lines("1\n2\n", number), Ok(vec![1, 2]));
+        assert_eq!(lines("1\r\n2\r\n", number), Ok(vec![1, 2]));
+        let error = lines("1\n2\nx\n", number).unwrap_err();
+        assert_eq!((error.line, error.column), (Some(3), Some(1)));
+        let error = lines("1\r\nx\r\n", number).unwrap_err();
+        assert_eq!((error.line, error.column), (Some(2), Some(1)));
+
+        // Trailing blank lines are given to the parser like any other line
+        assert_eq!(lines("1\n\n", |l| Ok(l.len())), Ok(vec![1, 0]));
+        assert_eq!(lines("", number), Ok(vec![]));
+    }
+
+    #[test]
+    fn blocks_are_located() {
+        let sum = |block: &str| lines(block, number).map(|n| n.iter().sum::<u32>());
+        assert_eq!(blocks("1\n2\n\n3", sum), Ok(vec![3, 3]));
+        assert_eq!(blocks("1\r\n2\r\n\r\n3\r\n", sum), Ok(vec![3, 3]));
+
+        let error = blocks("1\n2\n\n3\nx", sum).unwrap_err();
+        assert_eq!((error.line, error.column), (Some(5), Some(1)));
+        let error = blocks("1\r\n\r\n2\r\n\r\n3\r\nx\r\n", sum).unwrap_err();
+        assert_eq!((error.line, error.column), (Some(6), Some(1)));
+
+        // Empty blocks, e.g. from trailing blank lines, still count their line
+        assert_eq!(blocks("1\n\n\n\n2\n\n", sum), Ok(vec![1, 0, 2, 0]));
+        let error = blocks("1\n\n\n\nx", sum).unwrap_err();
+        assert_eq!((error.line, error.column), (Some(5), Some(1)));
+    }
+
+    #[test]
+    fn sections_give_second_line() {
+        assert_eq!(sections("a\nb\n\nc\n"), Ok(("a\nb", "c\n", 4)));
+        assert_eq!(sections("a\r\nb\r\n\r\nc\r\n"), Ok(("a\r\nb", "c\r\n", 4)));
+        assert_eq!(sections("\n\nc"), Ok(("", "c", 3)));
+        let error = sections("a\nb\n").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "missing blank line between sections in \"\""
+        );
+    }
+}
@@ -0,0 +1,339 @@
+//! Generic 2D/3D points and the 4 grid directions
+
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+/// 2D point, `y` grows downwards like in the puzzle maps
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Point<T> {
+    pub x: T,
+    pub y: T,
+}
+
+/// 3D point
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Point3<T> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+impl<T> Point<T> {
+    pub const fn new(x: T, y: T) -> Self {
+        Point { x, y }
+    }
+
+    /// Checked conversion of both coordinates, `None` if one does not fit
+    pub fn try_cast<U: TryFrom<T>>(self) -> Option<Point<U>> {
+        Some(Point::new(
+            U::try_from(self.x).ok()?,
+            U::try_from(self.y).ok()?,
+        ))
+    }
+}
+
+impl<T> Point3<T> {
+    pub const fn new(x: T, y: T, z: T) -> Self {
+        Point3 { x, y, z }
+    }
+
+    pub fn try_cast<U: TryFrom<T>>(self) -> Option<Point3<U>> {
+        Some(Point3::new(
+            U::try_from(self.x).ok()?,
+            U::try_from(self.y).ok()?,
+            U::try_from(self.z).ok()?,
+        ))
+    }
+}
+
+impl Point<usize> {
+    /// Move by the given offset, `None` if we would go below 0
+    pub fn offset(&self, dx: isize, dy: isize) -> Option<Self> {
+        Some(Point {
+            x: self.x.checked_add_signed(dx)?,
+            y: self.y.checked_add_signed(dy)?,
+        })
+    }
+
+    /// Same point with signed coordinates, see [`Point::try_cast`] for a
+    /// checked conversion.
+    ///
+    /// # Panics
+    ///
+    /// When a coordinate is above `isize::MAX`, which the coordinates of a
+    /// grid held in memory cannot be
+    pub fn signed(&self) -> Point<isize> {
+        self.try_cast()
+            .unwrap_or_else(|| panic!("{:?} does not fit in isize coordinates", self))
+    }
+}
+
+impl Point<isize> {
+    pub fn offset(&self, dx: isize, dy: isize) -> Self {
+        Point::new(self.x + dx, self.y + dy)
+    }
+
+    pub fn step(&self, dir: Direction) -> Self {
+        *self + dir.delta()
+    }
+
+    pub fn unsigned(&self) -> Option<Point<usize>> {
+        self.try_cast()
+    }
+}
+
+impl Point3<isize> {
+    /// The 6 points sharing a face with this one
+    pub fn neighbours6(&self) -> [Self; 6] {
+        let Point3 { x, y, z } = *self;
+        [
+            Point3::new(x - 1, y, z),
+            Point3::new(x + 1, y, z),
+            Point3::new(x, y - 1, z),
+            Point3::new(x, y + 1, z),
+            Point3::new(x, y, z - 1),
+            Point3::new(x, y, z + 1),
+        ]
+    }
+}
+
+macro_rules! impl_distances {
+    ($($t:ty => $u:ty),*) => {$(
+        impl Point<$t> {
+            pub fn manhattan(&self, other: &Self) -> $u {
+                self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+            }
+
+            pub fn chebyshev(&self, other: &Self) -> $u {
+                self.x.abs_diff(other.x).max(self.y.abs_diff(other.y))
+            }
+
+            pub fn euclidean(&self, other: &Self) -> f64 {
+                let dx = self.x.abs_diff(other.x) as f64;
+                let dy = self.y.abs_diff(other.y) as f64;
+                (dx * dx + dy * dy).sqrt()
+            }
+        }
+
+        impl Point3<$t> {
+            pub fn manhattan(&self, other: &Self) -> $u {
+                self.x.abs_diff(other.x) + self.y.abs_diff(other.y) + self.z.abs_diff(other.z)
+            }
+
+            pub fn chebyshev(&self, other: &Self) -> $u {
+                self.x
+                    .abs_diff(other.x)
+                    .max(self.y.abs_diff(other.y))
+                    .max(self.z.abs_diff(other.z))
+            }
+
+            pub fn euclidean(&self, other: &Self) -> f64 {
+                let dx = self.x.abs_diff(other.x) as f64;
+                let dy = self.y.abs_diff(other.y) as f64;
+                let dz = self.z.abs_diff(other.z) as f64;
+                (dx * dx + dy * dy + dz * dz).sqrt()
+            }
+        }
+    )*};
+}
+
+impl_distances!(
+    i32 => u32, i64 => u64, isize => usize,
+    u32 => u32, u64 => u64, usize => usize
+);
+
+macro_rules! impl_ops {
+    ($p:ident { $($f:ident),* }) => {
+        impl<T: Add<Output = T>> Add for $p<T> {
+            type Output = Self;
+
+            fn add(self, rhs: Self) -> Self {
+                $p { $($f: self.$f + rhs.$f),* }
+            }
+        }
+
+        impl<T: Sub<Output = T>> Sub for $p<T> {
+            type Output = Self;
+
+            fn sub(self, rhs: Self) -> Self {
+                $p { $($f: self.$f - rhs.$f),* }
+            }
+        }
+
+        impl<T: AddAssign> AddAssign for $p<T> {
+            fn add_assign(&mut self, rhs: Self) {
+                $(self.$f += rhs.$f;)*
+            }
+        }
+
+        impl<T: SubAssign> SubAssign for $p<T> {
+            fn sub_assign(&mut self, rhs: Self) {
+                $(self.$f -= rhs.$f;)*
+            }
+        }
+
+        impl<T: Neg<Output = T>> Neg for $p<T> {
+            type Output = Self;
+
+            fn neg(self) -> Self {
+                $p { $($f: -self.$f),* }
+            }
+        }
+
+        impl<T: Mul<Output = T> + Copy> Mul<T> for $p<T> {
+            type Output = Self;
+
+            fn mul(self, rhs: T) -> Self {
+                $p { $($f: self.$f * rhs),* }
+            }
+        }
+    };
+}
+
+impl_ops!(Point { x, y });
+impl_ops!(Point3 { x, y, z });
+
+impl<T> From<(T, T)> for Point<T> {
+    fn from((x, y): (T, T)) -> Self {
+        Point { x, y }
+    }
+}
+
+impl<T> From<(T, T, T)> for Point3<T> {
+    fn from((x, y, z): (T, T, T)) -> Self {
+        Point3 { x, y, z }
+    }
+}
+
+/// The 4 grid directions, `Up` means decreasing `y`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Direction {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Direction {
+    /// Clockwise, starting from `Up`
+    pub const ALL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+        Direction::Left,
+    ];
+
+    pub fn rotate_left(&self) -> Self {
+        match self {
+            Direction::Up => Direction::Left,
+            Direction::Left => Direction::Down,
+            Direction::Down => Direction::Right,
+            Direction::Right => Direction::Up,
+        }
+    }
+
+    pub fn rotate_right(&self) -> Self {
+        match self {
+            Direction::Up => Direction::Right,
+            Direction::Right => Direction::Down,
+            Direction::Down => Direction::Left,
+            Direction::Left => Direction::Up,
+        }
+    }
+
+    pub fn opposite(&self) -> Self {
+        self.rotate_left().rotate_left()
+    }
+
+    /// Offset of a single step in this direction
+    pub fn delta(&self) -> Point<isize> {
+        match self {
+            Direction::Up => Point::new(0, -1),
+            Direction::Right => Point::new(1, 0),
+            Direction::Down => Point::new(0, 1),
+            Direction::Left => Point::new(-1, 0),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arithmetic() {
+        let (a, b) = (Point::new(3, -2), Point::new(-1, 5));
+        assert_eq!(a + b, Point::new(2, 3));
+        assert_eq!(a - b, Point::new(4, -7));
+        assert_eq!(-a, Point::new(-3, 2));
+        assert_eq!(a * 3, Point::new(9, -6));
+        let mut c = a;
+        c += b;
+        c -= Point::new(1, 1);
+        assert_eq!(c, Point::new(1, 2));
+        assert_eq!(
+            Point3::new(1, 2, 3) + Point3::from((1, 1, 1)) * 2,
+            Point3::new(3, 4, 5)
+        );
+    }
+
+    #[test]
+    fn distances() {
+        let (a, b) = (Point::new(1isize, -2), Point::new(-3, 1));
+        assert_eq!(a.manhattan(&b), 7);
+        assert_eq!(a.chebyshev(&b), 4);
+        assert_eq!(a.euclidean(&b), 5.0);
+        let (a, b) = (Point::new(7usize, 2), Point::new(3, 2));
+        assert_eq!((a.manhattan(&b), b.manhattan(&a)), (4, 4));
+        let (a, b) = (Point3::new(0i64, 0, 0), Point3::new(1, -2, 2));
+        assert_eq!(a.manhattan(&b), 5);
+        assert_eq!(a.chebyshev(&b), 2);
+        assert_eq!(a.euclidean(&b), 3.0);
+        let extremes = (Point::new(i32::MIN, 0), Point::new(i32::MAX, 0));
+        assert_eq!(extremes.0.manhattan(&extremes.1), u32::MAX);
+    }
+
+    #[test]
+    fn conversions() {
+        assert_eq!(
+            Point::new(2isize, 3).unsigned(),
+            Some(Point::new(2usize, 3))
+        );
+        assert_eq!(Point::new(2isize, -1).unsigned(), None);
+        assert_eq!(Point::new(300u32, 1).try_cast::<u8>(), None);
+        assert_eq!(
+            Point3::new(1i64, 2, 3).try_cast::<u8>(),
+            Some(Point3::new(1, 2, 3))
+        );
+        assert_eq!(Point::new(4usize, 5).signed(), Point::new(4isize, 5));
+        assert_eq!(Point::new(0usize, 1).offset(-1, 0), None);
+        assert_eq!(Point::new(1usize, 1).offset(-1, 1), Some(Point::new(0, 2)));
+    }
+
+    #[test]
+    #[should_panic(expected = "does not fit")]
+    fn signed_overflow() {
+        Point::new(usize::MAX, 0).signed();
+    }
+
+    #[test]
+    fn directions() {
+        for d in Direction::ALL {
+            assert_eq!(d.rotate_left().rotate_right(), d);
+            assert_eq!(d.opposite().delta(), -d.delta());
+            assert_eq!(d.rotate_right().rotate_right(), d.opposite());
+        }
+        let clockwise: Vec<_> = Direction::ALL.iter().map(|d| d.rotate_right()).collect();
+        assert_eq!(
+            clockwise,
+            Direction::ALL
+                .iter()
+                .cycle()
+                .skip(1)
+                .take(4)
+                .copied()
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(Point::new(0isize, 0).step(Direction::Up), Point::new(0, -1));
+        assert_eq!(Point3::new(0isize, 0, 0).neighbours6().len(), 6);
+    }
+}
@@ -0,0 +1,292 @@
+//! Graph searches over a neighbour function.
+//!
+//! Nodes are any hashable values, `neighbours` returns the nodes reachable
+//! in one step (with the step cost for the weighted searches). Every search
+//! accepts several starting nodes and stops at the first node matching
+//! `is_goal`.
+
+use std::{
+    cmp::Reverse,
+    collections::{hash_map::Entry, BinaryHeap, HashMap, VecDeque},
+    hash::Hash,
+    ops::Add,
+};
+
+/// Path found by a search, from the start to the goal (both included)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Path<N, C> {
+    pub nodes: Vec<N>,
+    pub cost: C,
+}
+
+impl<N, C> Path<N, C> {
+    pub fn start(&self) -> &N {
+        self.nodes.first().unwrap()
+    }
+
+    pub fn goal(&self) -> &N {
+        self.nodes.last().unwrap()
+    }
+
+    /// Number of steps taken, not counting the start
+    pub fn steps(&self) -> usize {
+        self.nodes.len() - 1
+    }
+}
+
+/// Follow the parents back from `goal` to one of the starts
+fn reconstruct<N: Eq + Hash + Clone>(parents: &HashMap<N, Option<N>>, goal: N) -> Vec<N> {
+    let mut nodes = vec![goal];
+    while let Some(Some(parent)) = parents.get(nodes.last().unwrap()) {
+        nodes.push(parent.clone());
+    }
+    nodes.reverse();
+    nodes
+}
+
+/// Breadth-first search, every step costs 1
+pub fn bfs<N, I>(
+    starts: impl IntoIterator<Item = N>,
+    mut neighbours: impl FnMut(&N) -> I,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> Option<Path<N, usize>>
+where
+    N: Eq + Hash + Clone,
+    I: IntoIterator<Item = N>,
+{
+    let mut parents = HashMap::new();
+    let mut queue = VecDeque::new();
+    for start in starts {
+        if let Entry::Vacant(e) = parents.entry(start.clone()) {
+            e.insert(None);
+            queue.push_back((start, 0));
+        }
+    }
+
+    while let Some((node, cost)) = queue.pop_front() {
+        if is_goal(&node) {
+            let nodes = reconstruct(&parents, node);
+            return Some(Path { nodes, cost });
+        }
+        for next in neighbours(&node) {
+            if let Entry::Vacant(e) = parents.entry(next.clone()) {
+                e.insert(Some(node.clone()));
+                queue.push_back((next, cost + 1));
+            }
+        }
+    }
+    None
+}
+
+/// Distance from the closest start to every reachable node
+pub fn bfs_distances<N, I>(
+    starts: impl IntoIterator<Item = N>,
+    mut neighbours: impl FnMut(&N) -> I,
+) -> HashMap<N, usize>
+where
+    N: Eq + Hash + Clone,
+    I: IntoIterator<Item = N>,
+{
+    let mut distances = HashMap::new();
+    let mut queue = VecDeque::new();
+    for start in starts {
+        if let Entry::Vacant(e) = distances.entry(start.clone()) {
+            e.insert(0);
+            queue.push_back((start, 0));
+        }
+    }
+
+    while let Some((node, cost)) = queue.pop_front() {
+        for next in neighbours(&node) {
+            if let Entry::Vacant(e) = distances.entry(next.clone()) {
+                e.insert(cost + 1);
+                queue.push_back((next, cost + 1));
+            }
+        }
+    }
+    distances
+}
+
+/// Dijkstra search, step costs must not be negative
+pub fn dijkstra<N, C, I>(
+    starts: impl IntoIterator<Item = N>,
+    neighbours: impl FnMut(&N) -> I,
+    is_goal: impl FnMut(&N) -> bool,
+) -> Option<Path<N, C>>
+where
+    N: Eq + Hash + Clone,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (N, C)>,
+{
+    astar(starts, neighbours, |_| C::default(), is_goal)
+}
+
+/// A* search, `heuristic` must never overestimate the remaining cost
+pub fn astar<N, C, I>(
+    starts: impl IntoIterator<Item = N>,
+    mut neighbours: impl FnMut(&N) -> I,
+    mut heuristic: impl FnMut(&N) -> C,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> Option<Path<N, C>>
+where
+    N: Eq + Hash + Clone,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (N, C)>,
+{
+    // Nodes are stored once and referenced by index in the heap, so they
+    // don't need to be `Ord`
+    let mut nodes: Vec<(N, C, Option<usize>)> = vec![];
+    let mut index = HashMap::new();
+    let mut heap = BinaryHeap::new();
+    for start in starts {
+        if let Entry::Vacant(e) = index.entry(start.clone()) {
+            e.insert(nodes.len());
+            heap.push(Reverse((heuristic(&start), C::default(), nodes.len())));
+            nodes.push((start, C::default(), None));
+        }
+    }
+
+    while let Some(Reverse((_, cost, i))) = heap.pop() {
+        // Stale entry, a cheaper way to this node was already expanded
+        if cost > nodes[i].1 {
+            continue;
+        }
+        let node = nodes[i].0.clone();
+        if is_goal(&node) {
+            let mut path = vec![];
+            let mut cur = Some(i);
+            while let Some(j) = cur {
+                path.push(nodes[j].0.clone());
+                cur = nodes[j].2;
+            }
+            path.reverse();
+            return Some(Path { nodes: path, cost });
+        }
+        for (next, step) in neighbours(&node) {
+            let new_cost = cost + step;
+            let j = match index.entry(next) {
+                Entry::Vacant(e) => {
+                    let j = nodes.len();
+                    nodes.push((e.key().clone(), new_cost, Some(i)));
+                    e.insert(j);
+                    j
+                }
+                Entry::Occupied(e) => {
+                    let j = *e.get();
+                    if new_cost >= nodes[j].1 {
+                        continue;
+                    }
+                    nodes[j].1 = new_cost;
+                    nodes[j].2 = Some(i);
+                    j
+                }
+            };
+            let estimate = new_cost + heuristic(&nodes[j].0);
+            heap.push(Reverse((estimate, new_cost, j)));
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::point::Point;
+
+    /// Weighted directed graph: a shortcut `a -> d` costing more than the long way
+    fn edges(node: &char) -> Vec<(char, u32)> {
+        match node {
+            'a' => vec![('d', 5), ('b', 1)],
+            'b' => vec![('c', 1), ('e', 1)],
+            'c' => vec![('d', 1)],
+            'e' => vec![('d', 10)],
+            'x' => vec![('d', 1)],
+            _ => vec![],
+        }
+    }
+
+    /// 4-neighbours of a 10x10 map whose `#` cells are walls
+    const MAZE: [&str; 10] = [
+        "..........",
+        ".########.",
+        ".#......#.",
+        ".#.####.#.",
+        ".#.#..#.#.",
+        ".#.#.##.#.",
+        ".#.#....#.",
+        ".#.######.",
+        ".#........",
+        ".#########",
+    ];
+
+    fn open(p: Point<isize>) -> bool {
+        p.unsigned()
+            .and_then(|p| MAZE.get(p.y)?.as_bytes().get(p.x))
+            .is_some_and(|&c| c == b'.')
+    }
+
+    fn maze_neighbours(p: &Point<isize>) -> Vec<Point<isize>> {
+        crate::point::Direction::ALL
+            .iter()
+            .map(|d| p.step(*d))
+            .filter(|&n| open(n))
+            .collect()
+    }
+
+    #[test]
+    fn weighted_shortest_path() {
+        let path = dijkstra(['a'], edges, |&n| n == 'd').unwrap();
+        assert_eq!(path.nodes, vec!['a', 'b', 'c', 'd']);
+        assert_eq!((path.cost, path.steps()), (3, 3));
+        assert_eq!((*path.start(), *path.goal()), ('a', 'd'));
+
+        // Fewest steps is the shortcut
+        let path = bfs(
+            ['a'],
+            |n| edges(n).into_iter().map(|(n, _)| n),
+            |&n| n == 'd',
+        )
+        .unwrap();
+        assert_eq!((path.nodes, path.cost), (vec!['a', 'd'], 1));
+    }
+
+    #[test]
+    fn multiple_starts() {
+        let path = dijkstra(['a', 'x'], edges, |&n| n == 'd').unwrap();
+        assert_eq!((path.nodes, path.cost), (vec!['x', 'd'], 1));
+
+        let path = dijkstra(['a', 'b'], edges, |&n| n == 'b').unwrap();
+        assert_eq!((path.nodes, path.cost), (vec!['b'], 0));
+
+        let distances = bfs_distances(['e', 'x'], |n| edges(n).into_iter().map(|(n, _)| n));
+        assert_eq!(distances.len(), 3);
+        assert_eq!(distances[&'d'], 1);
+    }
+
+    #[test]
+    fn unreachable_goal() {
+        assert_eq!(dijkstra(['c'], edges, |&n| n == 'a'), None);
+        assert_eq!(astar(['c'], edges, |_| 0, |&n| n == 'a'), None);
+        assert_eq!(bfs(['d'], |_| [], |&n| n == 'a'), None);
+        let distances = bfs_distances(['c'], |n| edges(n).into_iter().map(|(n, _)| n));
+        assert!(!distances.contains_key(&'a'));
+    }
+
+    #[test]
+    fn astar_matches_dijkstra() {
+        let (start, goal) = (Point::new(0, 0), Point::new(4, 4));
+        let weighted = |p: &Point<isize>| maze_neighbours(p).into_iter().map(|n| (n, 1));
+        let plain = dijkstra([start], weighted, |&p| p == goal).unwrap();
+        let guided = astar([start], weighted, |p| p.manhattan(&goal), |&p| p == goal).unwrap();
+        let unweighted = bfs([start], maze_neighbours, |&p| p == goal).unwrap();
+        assert_eq!(plain.cost, 44);
+        assert_eq!(guided.cost, plain.cost);
+        assert_eq!(unweighted.cost, plain.cost);
+
+        // Every step of the path moves to a neighbour
+        assert_eq!(guided.nodes.len(), 45);
+        for w in guided.nodes.windows(2) {
+            assert!(maze_neighbours(&w[0]).contains(&w[1]));
+        }
+    }
+}
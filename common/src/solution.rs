@@ -0,0 +1,153 @@
+use std::{
+    fmt::Display,
+    time::{Duration, Instant},
+};
+
+use anyhow::{anyhow, Result};
+
+use crate::alloc::{self, AllocStats};
+
+/// Answer of a puzzle part, whatever its underlying type
+#[derive(Debug, Clone, PartialEq)]
+pub enum Answer {
+    Int(i64),
+    UInt(u64),
+    Float(f64),
+    Text(String),
+}
+
+impl Answer {
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Answer::Int(_) => "int",
+            Answer::UInt(_) => "uint",
+            Answer::Float(_) => "float",
+            Answer::Text(_) => "text",
+        }
+    }
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Answer::Int(v) => write!(f, "{}", v),
+            Answer::UInt(v) => write!(f, "{}", v),
+            Answer::Float(v) => write!(f, "{}", v),
+            Answer::Text(v) => write!(f, "{}", v),
+        }
+    }
+}
+
+macro_rules! impl_from {
+    ($variant:ident, $target:ty, $($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(v: $t) -> Self {
+                    Answer::$variant(v as $target)
+                }
+            }
+        )*
+    };
+}
+
+impl_from!(Int, i64, i32, i64, isize);
+impl_from!(UInt, u64, u32, u64, usize);
+impl_from!(Float, f64, f32, f64);
+
+impl From<String> for Answer {
+    fn from(v: String) -> Self {
+        Answer::Text(v)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(v: &str) -> Self {
+        Answer::Text(v.to_string())
+    }
+}
+
+/// A day of the calendar: the input is parsed once, then shared by both parts
+pub trait Solution {
+    const DAY: u8;
+    /// Number of parts, the last day only has one
+    const PARTS: u8 = 2;
+
+    type Input;
+
+    fn parse(input: &str) -> Result<Self::Input>;
+
+    fn part1(input: &Self::Input) -> Result<Answer>;
+
+    fn part2(_input: &Self::Input) -> Result<Answer> {
+        Err(anyhow!("day {} has no part 2", Self::DAY))
+    }
+}
+
+#[derive(Debug)]
+pub struct PartReport {
+    pub part: u8,
+    pub answer: Result<Answer>,
+    pub time: Duration,
+    /// Only when allocation counting is on
+    pub alloc: Option<AllocStats>,
+}
+
+#[derive(Debug)]
+pub struct Report {
+    pub day: u8,
+    pub parse_time: Duration,
+    pub parse_alloc: Option<AllocStats>,
+    pub parts: Vec<PartReport>,
+}
+
+/// Time `f`, and count its allocations if counting is on
+fn profile<T>(f: impl FnOnce() -> T) -> (T, Duration, Option<AllocStats>) {
+    let ((res, time), alloc) = alloc::measure(|| {
+        let instant = Instant::now();
+        let res = f();
+        (res, instant.elapsed())
+    });
+    (res, time, alloc)
+}
+
+/// Parse the input then run the requested parts, timing each step separately
+pub fn execute<S: Solution>(input: &str, parts: &[u8]) -> Result<Report> {
+    let (parsed, parse_time, parse_alloc) = profile(|| {
+        crate::span!(INFO, "parse", day = S::DAY);
+        S::parse(input)
+    });
+    let parsed = parsed?;
+
+    let parts = parts
+        .iter()
+        .copied()
+        .filter(|&p| p >= 1 && p <= S::PARTS)
+        .map(|part| {
+            let (answer, time, alloc) = profile(|| {
+                crate::span!(INFO, "part", day = S::DAY, part);
+                let answer = match part {
+                    1 => S::part1(&parsed),
+                    _ => S::part2(&parsed),
+                };
+                match &answer {
+                    Ok(answer) => crate::event!(INFO, %answer),
+                    Err(e) => crate::event!(INFO, error = %e),
+                }
+                answer
+            });
+            PartReport {
+                part,
+                answer,
+                time,
+                alloc,
+            }
+        })
+        .collect();
+
+    Ok(Report {
+        day: S::DAY,
+        parse_time,
+        parse_alloc,
+        parts,
+    })
+}
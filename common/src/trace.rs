@@ -0,0 +1,57 @@
+//! Tracing of the parsing, the parts and the main loops of the solutions.
+//!
+//! [`span!`](crate::span) and [`event!`](crate::event) take the arguments of
+//! their `tracing` counterparts, with the level as a bare `INFO`, `DEBUG`...
+//! Without the `trace` feature they are type checked but compile to nothing,
+//! so the solutions pay nothing for their instrumentation. The levels follow
+//! the verbosity of the runner:
+//!
+//! - `INFO`: parsing, parts and their outcome, one event per run at most
+//! - `DEBUG`: every iteration of the main loops
+//! - `TRACE`: dumps of whole states
+
+pub use tracing;
+
+/// Enter a span until the end of the enclosing block
+#[cfg(feature = "trace")]
+#[macro_export]
+macro_rules! span {
+    ($level:ident, $($args:tt)+) => {
+        let _span = $crate::trace::tracing::span!(
+            $crate::trace::tracing::Level::$level,
+            $($args)+
+        )
+        .entered();
+    };
+}
+
+#[cfg(not(feature = "trace"))]
+#[macro_export]
+macro_rules! span {
+    ($level:ident, $($args:tt)+) => {
+        if false {
+            let _ = $crate::trace::tracing::span!(
+                $crate::trace::tracing::Level::$level,
+                $($args)+
+            );
+        }
+    };
+}
+
+#[cfg(feature = "trace")]
+#[macro_export]
+macro_rules! event {
+    ($level:ident, $($args:tt)+) => {
+        $crate::trace::tracing::event!($crate::trace::tracing::Level::$level, $($args)+)
+    };
+}
+
+#[cfg(not(feature = "trace"))]
+#[macro_export]
+macro_rules! event {
+    ($level:ident, $($args:tt)+) => {
+        if false {
+            $crate::trace::tracing::event!($crate::trace::tracing::Level::$level, $($args)+)
+        }
+    };
+}
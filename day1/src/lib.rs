@@ -0,0 +1,285 @@
+//! Day 1: Calorie Counting.
+//!
+//! Besides the puzzle input, inventories of any size can be read as a stream,
+//! holding a single line and the `k` largest elves in memory. The `day1`
+//! binary does so when given `k` after the input path, `-` reading stdin:
+//!
+//! ```
+//! let inventory = "1000\n2000\n\n4000\n\n5000\n6000\n\n7000\n";
+//! let top = day1::top_calories(inventory.as_bytes(), 2).unwrap();
+//! assert_eq!(top, vec![11000, 7000]);
+//! ```
+
+use std::{cmp::Reverse, collections::BinaryHeap, io::BufRead};
+
+use anyhow::{Context, Result};
+use common::gen::{Generate, Rng};
+use common::parse::{self, ParseContext, ParseError};
+use common::{Answer, Solution};
+
+fn get_calories_sum(input: &str) -> Result<Vec<u32>, ParseError> {
+    parse::blocks(input, |c| {
+        let mut total: u32 = 0;
+        parse::lines(c, |v| {
+            let calories = parse::value::<u32>(v, "invalid calories")?;
+            total = total
+                .checked_add(calories)
+                .parse_context("too many calories", v)?;
+            Ok(())
+        })?;
+        Ok(total)
+    })
+}
+
+/// The `k` largest values pushed, kept in a min-heap of at most `k` values
+#[derive(Debug, Clone)]
+pub struct TopK {
+    k: usize,
+    heap: BinaryHeap<Reverse<u32>>,
+}
+
+impl TopK {
+    /// Nothing is allocated up front, `k` can be larger than the values pushed
+    pub fn new(k: usize) -> Self {
+        TopK {
+            k,
+            heap: BinaryHeap::new(),
+        }
+    }
+
+    pub fn push(&mut self, value: u32) {
+        if self.heap.len() < self.k {
+            self.heap.push(Reverse(value));
+        } else if let Some(mut smallest) = self.heap.peek_mut() {
+            if smallest.0 < value {
+                *smallest = Reverse(value);
+            }
+        }
+    }
+
+    /// Largest first
+    pub fn into_sorted_vec(self) -> Vec<u32> {
+        self.heap
+            .into_sorted_vec()
+            .into_iter()
+            .map(|Reverse(v)| v)
+            .collect()
+    }
+}
+
+impl Extend<u32> for TopK {
+    fn extend<T: IntoIterator<Item = u32>>(&mut self, iter: T) {
+        iter.into_iter().for_each(|v| self.push(v));
+    }
+}
+
+/// Calories carried by each elf of an inventory, read one line at a time.
+/// Elves are separated by blank lines, repeated blank lines are ignored.
+/// Stops after the first error
+pub struct Elves<R> {
+    reader: R,
+    line: String,
+    line_number: usize,
+    done: bool,
+}
+
+impl<R: BufRead> Elves<R> {
+    pub fn new(reader: R) -> Self {
+        Elves {
+            reader,
+            line: String::new(),
+            line_number: 0,
+            done: false,
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for Elves<R> {
+    type Item = Result<u32>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let mut sum = None;
+        loop {
+            self.line.clear();
+            let read = self
+                .reader
+                .read_line(&mut self.line)
+                .with_context(|| format!("failed to read line {}", self.line_number + 1));
+            match read {
+                Ok(0) => {
+                    self.done = true;
+                    return sum.map(Ok);
+                }
+                Ok(_) => self.line_number += 1,
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err(e));
+                }
+            }
+            let line = self.line.trim_end_matches(['\n', '\r']);
+            if line.is_empty() {
+                match sum {
+                    Some(sum) => return Some(Ok(sum)),
+                    None => continue,
+                }
+            }
+            let total = parse::value::<u32>(line, "invalid calories")
+                .and_then(|v| {
+                    v.checked_add(sum.unwrap_or(0))
+                        .parse_context("too many calories", line)
+                })
+                .map_err(|e| e.locate(self.line_number, line));
+            match total {
+                Ok(total) => sum = Some(total),
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err(e.into()));
+                }
+            }
+        }
+    }
+}
+
+/// The `k` largest calories carried by an elf of the inventory, largest first
+pub fn top_calories(reader: impl BufRead, k: usize) -> Result<Vec<u32>> {
+    let mut top = TopK::new(k);
+    for calories in Elves::new(reader) {
+        top.push(calories?);
+    }
+    Ok(top.into_sorted_vec())
+}
+
+pub struct Day1;
+
+impl Solution for Day1 {
+    const DAY: u8 = 1;
+
+    type Input = Vec<u32>;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(get_calories_sum(input)?)
+    }
+
+    fn part1(sum_calories: &Self::Input) -> Result<Answer> {
+        let v = sum_calories.iter().max().context("max err")?;
+        Ok((*v).into())
+    }
+
+    fn part2(sum_calories: &Self::Input) -> Result<Answer> {
+        let mut top = TopK::new(3);
+        top.extend(sum_calories.iter().copied());
+        let v = top
+            .into_sorted_vec()
+            .into_iter()
+            .try_fold(0u32, u32::checked_add)
+            .context("too many calories")?;
+        Ok(v.into())
+    }
+}
+
+impl Generate for Day1 {
+    /// Elves
+    const SIZE: usize = 250;
+
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        let elves: Vec<String> = (0..size)
+            .map(|_| {
+                let items = rng.usize(1..=15);
+                (0..items)
+                    .map(|_| format!("{}\n", rng.usize(1000..=70000)))
+                    .collect()
+            })
+            .collect();
+        elves.join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../example");
+
+    #[test]
+    fn calories_sum() {
+        let sums = Day1::parse(EXAMPLE).unwrap();
+        assert_eq!(sums, vec![6000, 4000, 11000, 24000, 10000]);
+    }
+
+    #[test]
+    fn part1() {
+        let sums = Day1::parse(EXAMPLE).unwrap();
+        assert_eq!(Day1::part1(&sums).unwrap().to_string(), "24000");
+    }
+
+    #[test]
+    fn part2() {
+        let sums = Day1::parse(EXAMPLE).unwrap();
+        assert_eq!(Day1::part2(&sums).unwrap().to_string(), "45000");
+    }
+
+    #[test]
+    fn top_k() {
+        let mut top = TopK::new(3);
+        top.extend([5, 1, 9, 3, 9, 7, 2]);
+        assert_eq!(top.into_sorted_vec(), vec![9, 9, 7]);
+        let mut none = TopK::new(0);
+        none.push(1);
+        assert_eq!(none.into_sorted_vec(), vec![]);
+        let mut all = TopK::new(usize::MAX);
+        all.extend([2, 3, 1]);
+        assert_eq!(all.into_sorted_vec(), vec![3, 2, 1]);
+        assert_eq!(
+            top_calories(EXAMPLE.as_bytes(), usize::MAX).unwrap().len(),
+            5
+        );
+    }
+
+    #[test]
+    fn streaming() {
+        let top = top_calories(EXAMPLE.as_bytes(), 3).unwrap();
+        assert_eq!(top, vec![24000, 11000, 10000]);
+
+        let input = common::gen::generate::<Day1>(3, None).replace('\n', "\r\n");
+        let elves: Vec<u32> = Elves::new(input.as_bytes()).collect::<Result<_>>().unwrap();
+        assert_eq!(elves, Day1::parse(&input.replace('\r', "")).unwrap());
+
+        let elves: Vec<u32> = Elves::new("\n1\n2\n\n\n\n3".as_bytes())
+            .collect::<Result<_>>()
+            .unwrap();
+        assert_eq!(elves, vec![3, 3]);
+    }
+
+    #[test]
+    fn streaming_errors() {
+        let mut elves = Elves::new("1000\n\n2000\nlots\n3000\n".as_bytes());
+        assert_eq!(elves.next().unwrap().unwrap(), 1000);
+        let e = elves.next().unwrap().unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "line 4, column 1: invalid calories: invalid digit found in string in \"lots\""
+        );
+        assert!(elves.next().is_none());
+
+        let e = top_calories("4000000000\n1000000000\n".as_bytes(), 1).unwrap_err();
+        assert!(e.to_string().starts_with("line 2"), "{}", e);
+    }
+
+    #[test]
+    fn overflows() {
+        let e = Day1::parse("1\n\n4000000000\n1000000000\n").unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "line 4, column 1: too many calories in \"1000000000\""
+        );
+        let sums = Day1::parse("4000000000\n\n1000000000\n").unwrap();
+        assert_eq!(Day1::part1(&sums).unwrap().to_string(), "4000000000");
+        assert_eq!(
+            Day1::part2(&sums).unwrap_err().to_string(),
+            "too many calories"
+        );
+    }
+}
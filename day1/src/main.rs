@@ -1,29 +1,46 @@
-use anyhow::{Context, Result};
-
-fn get_calories_sum(input: &str) -> Vec<u32> {
-    input
-        .split("\n\n")
-        .map(|c| c.lines().map(|v| v.parse::<u32>().unwrap()).sum())
-        .collect()
-}
+use std::{
+    fs::File,
+    io::{self, BufRead, BufReader},
+};
 
-fn part1(input: &str) -> Result<u32> {
-    let sum_calories = get_calories_sum(input);
-    let v = sum_calories.iter().max().context("max err")?;
-    Ok(*v)
-}
+use anyhow::{Context, Result};
+use common::input::{self, InputSource};
+use common::Solution;
+use day1::Day1;
 
-fn part2(input: &str) -> Result<u32> {
-    let mut sum_calories = get_calories_sum(input);
-    sum_calories.sort_by(|x, y| y.cmp(x));
-    let v = sum_calories.iter().take(3).sum();
-    Ok(v)
+/// Reader of the inventory, which is not loaded in memory
+fn open(source: &InputSource) -> Result<Box<dyn BufRead>> {
+    let path = match source {
+        InputSource::Stdin => return Ok(Box::new(io::stdin().lock())),
+        InputSource::Default => input::default_path(Day1::DAY),
+        InputSource::Path(path) => path.clone(),
+    };
+    let file = File::open(&path)
+        .with_context(|| format!("failed to read input file {}", path.display()))?;
+    Ok(Box::new(BufReader::new(file)))
 }
 
 fn main() -> Result<()> {
-    let input = include_str!("../input");
-    let max_cal_sum = part1(input)?;
-    let max_3cal_sum = part2(input)?;
+    // `day1 <input> <k>` streams the input, keeping the k largest elves
+    if let Some(k) = std::env::args().nth(2) {
+        let k: usize = k
+            .parse()
+            .with_context(|| format!("invalid number of elves {:?}", k))?;
+        let source = InputSource::from_arg(std::env::args().nth(1).as_deref());
+        let top = day1::top_calories(open(&source)?, k)?;
+        let total = top
+            .iter()
+            .try_fold(0u64, |total, &c| total.checked_add(c.into()))
+            .context("too many calories")?;
+        println!("[*] Biggest {} calories sums: {:?}", top.len(), top);
+        println!("[*] Total: {}", total);
+        return Ok(());
+    }
+
+    let input = common::input::from_args(Day1::DAY)?;
+    let sum_calories = Day1::parse(&input)?;
+    let max_cal_sum = Day1::part1(&sum_calories)?;
+    let max_3cal_sum = Day1::part2(&sum_calories)?;
     println!("[*] Biggest calories sum: {}", max_cal_sum);
     println!("[*] Biggest top 3 calories sum: {}", max_3cal_sum);
 
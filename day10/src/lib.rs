@@ -0,0 +1,440 @@
+//! Day 10: Cathode-Ray Tube.
+//!
+//! Also usable as an emulator of the handheld device, its [`Cpu`] drawing on a
+//! [`Crt`] while it runs:
+//!
+//! ```
+//! use day10::{Cpu, Instruction};
+//!
+//! let program: Vec<Instruction> = ["noop", "addx 3", "addx -5"]
+//!     .iter()
+//!     .map(|l| l.parse().unwrap())
+//!     .collect();
+//! let mut cpu = Cpu::new();
+//! let strengths = cpu.execute_instructions(&program, &[2, 4]);
+//! assert_eq!(strengths, vec![2, 16]);
+//! assert_eq!((cpu.register(), cpu.cycles()), (-1, 5));
+//! ```
+
+use std::{fmt::Display, str::FromStr};
+
+use anyhow::Result;
+use common::animate::{Animate, Colour, Frame, Pixel, Simulation};
+use common::gen::{Generate, Rng};
+use common::parse::{self, ParseContext, ParseError};
+use common::point::Point;
+use common::{Answer, Solution};
+
+/// The CPU of the device, with its single `X` register
+pub struct Cpu {
+    register: isize,
+    total_cycles: isize,
+    crt: Crt,
+}
+
+/// An instruction and the number of cycles it takes
+#[derive(Debug, Clone)]
+pub struct Instruction {
+    value: InstructionType,
+    cycles: isize,
+}
+
+#[derive(Debug, Clone)]
+pub enum InstructionType {
+    AddX(isize),
+    Nop,
+}
+
+/// The 40x6 screen, a pixel is lit when the 3 pixels wide sprite centred on
+/// `X` covers it while it is drawn
+#[derive(Clone)]
+pub struct Crt {
+    pixels: [[char; 40]; 6],
+    cur_i: usize,
+    cur_row: usize,
+}
+
+impl FromStr for Instruction {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut splits = s.split(" ");
+        let n = splits.next().parse_context("no name", s)?;
+        let i = match n {
+            "addx" => {
+                let v = splits.next().parse_context("no add value", s)?;
+                let v = parse::value(v, "invalid add value")?;
+                let t = InstructionType::AddX(v);
+                Instruction::new(t, 2)
+            }
+            "noop" => {
+                let t = InstructionType::Nop;
+                Instruction::new(t, 1)
+            }
+            _ => return Err(ParseError::new("invalid instruction name", n)),
+        };
+        Ok(i)
+    }
+}
+
+impl Instruction {
+    pub fn new(value: InstructionType, cycles: isize) -> Self {
+        Instruction { value, cycles }
+    }
+
+    pub fn kind(&self) -> &InstructionType {
+        &self.value
+    }
+
+    pub fn cycles(&self) -> isize {
+        self.cycles
+    }
+}
+
+impl Default for Crt {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Crt {
+    pub fn new() -> Self {
+        Crt {
+            pixels: [['.'; 40]; 6],
+            cur_i: 0,
+            cur_row: 0,
+        }
+    }
+
+    /// Draw the pixels of the next `cycles` cycles. Pixels past the last row
+    /// are not drawn
+    pub fn draw_pixel(&mut self, sprite_pos: isize, cycles: isize) {
+        for _ in 0..cycles {
+            if self.cur_row == self.pixels.len() {
+                return;
+            }
+            let range = sprite_pos - self.cur_i as isize;
+            if (-1..=1).contains(&range) {
+                self.pixels[self.cur_row][self.cur_i] = '#';
+            }
+            self.cur_i += 1;
+            if self.cur_i.is_multiple_of(40) {
+                self.cur_row += 1;
+                self.cur_i = 0;
+            }
+        }
+    }
+}
+
+impl Display for Crt {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in self.pixels.iter() {
+            writeln!(f, "{}", String::from_iter(row.iter()))?;
+        }
+        Ok(())
+    }
+}
+
+impl Default for Cpu {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Cpu {
+    pub fn new() -> Self {
+        Cpu {
+            register: 1,
+            total_cycles: 0,
+            crt: Crt::new(),
+        }
+    }
+
+    /// Value of `X`
+    pub fn register(&self) -> isize {
+        self.register
+    }
+
+    /// Cycles elapsed since the start
+    pub fn cycles(&self) -> isize {
+        self.total_cycles
+    }
+
+    pub fn crt(&self) -> &Crt {
+        &self.crt
+    }
+
+    /// Run the instructions, returning the signal strength (cycle times `X`)
+    /// during each of the given cycles, sorted, that the program reaches
+    pub fn execute_instructions(
+        &mut self,
+        instructions: &[Instruction],
+        sig_idxs: &[isize],
+    ) -> Vec<isize> {
+        let mut res = vec![];
+        let mut sig_idxs = sig_idxs.iter();
+        let mut sig_idx = sig_idxs.next();
+
+        for instruction in instructions.iter() {
+            self.crt.draw_pixel(self.register, instruction.cycles);
+            let new_cycle = self.total_cycles + instruction.cycles;
+            if let Some(si) = sig_idx {
+                if *si <= new_cycle {
+                    let sig_strength = self.register * si;
+                    res.push(sig_strength);
+                    sig_idx = sig_idxs.next();
+                }
+            }
+            self.total_cycles = new_cycle;
+            if let InstructionType::AddX(n) = instruction.value {
+                self.register += n;
+            }
+        }
+
+        res
+    }
+}
+
+/// Cycles whose signal strength is summed by part 1
+const SIGNAL_CYCLES: [isize; 6] = [20, 60, 100, 140, 180, 220];
+
+/// The device running its program one cycle at a time
+#[derive(Clone)]
+pub struct CpuSimulation {
+    instructions: Vec<Instruction>,
+    next: usize,
+    /// Cycles already spent on the next instruction
+    progress: isize,
+    register: isize,
+    cycle: isize,
+    crt: Crt,
+    /// Sum of the signal strengths of the cycles run so far
+    signal: isize,
+}
+
+impl Simulation for CpuSimulation {
+    fn step(&mut self) -> bool {
+        let Some(instruction) = self.instructions.get(self.next) else {
+            return false;
+        };
+        self.cycle += 1;
+        if SIGNAL_CYCLES.contains(&self.cycle) {
+            self.signal += self.cycle * self.register;
+        }
+        self.crt.draw_pixel(self.register, 1);
+        self.progress += 1;
+        if self.progress == instruction.cycles {
+            if let InstructionType::AddX(n) = instruction.value {
+                self.register += n;
+            }
+            self.next += 1;
+            self.progress = 0;
+        }
+        true
+    }
+
+    /// The screen with the next pixel drawn in red, and the sprite below it
+    fn frame(&self) -> Frame {
+        let (width, rows) = (40, self.crt.pixels.len());
+        let mut frame = Frame::new(Point::new(0, 0), width, rows + 2);
+        for (y, row) in self.crt.pixels.iter().enumerate() {
+            for (x, &c) in row.iter().enumerate() {
+                if c == '#' {
+                    frame.set(
+                        Point::new(x as isize, y as isize),
+                        Pixel::new(c, Colour::White),
+                    );
+                }
+            }
+        }
+        for x in 0..width as isize {
+            frame.set(
+                Point::new(x, rows as isize),
+                Pixel::new(' ', Colour::Default),
+            );
+        }
+        for x in self.register - 1..=self.register + 1 {
+            frame.set(
+                Point::new(x, rows as isize + 1),
+                Pixel::new('#', Colour::Cyan),
+            );
+        }
+        let beam = Point::new(self.crt.cur_i as isize, self.crt.cur_row as isize);
+        if let Some(pixel) = frame.get(beam) {
+            frame.set(beam, Pixel::new(pixel.ch, Colour::Red));
+        }
+        frame
+            .with_focus(beam)
+            .with_status(format!("cycle {}, X = {}", self.cycle, self.register))
+    }
+
+    fn inspect(&self) -> Vec<(&'static str, String)> {
+        let next = match self.instructions.get(self.next) {
+            Some(i) => {
+                let name = match i.value {
+                    InstructionType::AddX(n) => format!("addx {}", n),
+                    InstructionType::Nop => "noop".to_string(),
+                };
+                format!("{}, cycle {}/{}", name, self.progress + 1, i.cycles)
+            }
+            None => "-".to_string(),
+        };
+        vec![
+            ("cycle", self.cycle.to_string()),
+            ("X", self.register.to_string()),
+            ("next", next),
+            (
+                "sprite",
+                format!("{}..={}", self.register - 1, self.register + 1),
+            ),
+            (
+                "drawing",
+                format!("row {}, column {}", self.crt.cur_row, self.crt.cur_i),
+            ),
+            ("signal strength", self.signal.to_string()),
+        ]
+    }
+
+    fn snapshot(&self) -> Option<Box<dyn Simulation>> {
+        Some(Box::new(self.clone()))
+    }
+}
+
+pub struct Day10;
+
+impl Solution for Day10 {
+    const DAY: u8 = 10;
+
+    type Input = Vec<Instruction>;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(parse::lines(input, str::parse)?)
+    }
+
+    fn part1(instructions: &Self::Input) -> Result<Answer> {
+        let mut cpu = Cpu::new();
+        let sig = cpu.execute_instructions(instructions, &SIGNAL_CYCLES);
+        Ok(sig.iter().sum::<isize>().into())
+    }
+
+    fn part2(instructions: &Self::Input) -> Result<Answer> {
+        let mut cpu = Cpu::new();
+        let _ = cpu.execute_instructions(instructions, &SIGNAL_CYCLES);
+        Ok(cpu.crt.to_string().into())
+    }
+}
+
+impl Animate for Day10 {
+    /// Both parts run the same program, part 1 reads the signal strength and
+    /// part 2 the screen
+    fn simulation(instructions: &Self::Input, _part: u8) -> Result<Box<dyn Simulation>> {
+        Ok(Box::new(CpuSimulation {
+            instructions: instructions.clone(),
+            next: 0,
+            progress: 0,
+            register: 1,
+            cycle: 0,
+            crt: Crt::new(),
+            signal: 0,
+        }))
+    }
+}
+
+impl Generate for Day10 {
+    /// Cycles, at most the 240 pixels of the screen
+    const SIZE: usize = 240;
+
+    /// The register stays around the width of the screen so that sprites are drawn
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        let mut input = String::new();
+        let mut cycles = 0;
+        let mut register: isize = 1;
+        while cycles < size.min(240) {
+            if cycles + 1 == size.min(240) || rng.chance(0.3) {
+                input += "noop\n";
+                cycles += 1;
+                continue;
+            }
+            let v = loop {
+                let v = rng.isize(-20..=20);
+                if v != 0 && (-5..=45).contains(&(register + v)) {
+                    break v;
+                }
+            };
+            register += v;
+            input += &format!("addx {}\n", v);
+            cycles += 2;
+        }
+        input
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../example");
+
+    const IMAGE: &str = "\
+##..##..##..##..##..##..##..##..##..##..
+###...###...###...###...###...###...###.
+####....####....####....####....####....
+#####.....#####.....#####.....#####.....
+######......######......######......####
+#######.......#######.......#######.....
+";
+
+    #[test]
+    fn signal_strengths() {
+        let instructions = Day10::parse(EXAMPLE).unwrap();
+        let mut cpu = Cpu::new();
+        let sig = cpu.execute_instructions(&instructions, &[20, 60, 100, 140, 180, 220]);
+        assert_eq!(sig, vec![420, 1140, 1800, 2940, 2880, 3960]);
+    }
+
+    #[test]
+    fn crt_image() {
+        let instructions = Day10::parse(EXAMPLE).unwrap();
+        let mut cpu = Cpu::new();
+        cpu.execute_instructions(&instructions, &[]);
+        assert_eq!(cpu.crt.to_string(), IMAGE);
+    }
+
+    #[test]
+    fn cycle_by_cycle() {
+        let instructions = Day10::parse(EXAMPLE).unwrap();
+        let mut sim = Day10::simulation(&instructions, 1).unwrap();
+        let mut cycles = 0;
+        while sim.step() {
+            cycles += 1;
+            if cycles == 20 {
+                assert!(sim
+                    .inspect()
+                    .contains(&("signal strength", "420".to_string())));
+            }
+        }
+        assert_eq!(cycles, 240);
+        assert!(sim
+            .inspect()
+            .contains(&("signal strength", "13140".to_string())));
+        let frame = sim.frame();
+        let screen: String = frame
+            .rows()
+            .take(6)
+            .map(|row| row.iter().map(|p| p.ch).collect::<String>() + "\n")
+            .collect();
+        assert_eq!(screen, IMAGE);
+    }
+
+    #[test]
+    fn part1() {
+        let instructions = Day10::parse(EXAMPLE).unwrap();
+        assert_eq!(Day10::part1(&instructions).unwrap().to_string(), "13140");
+    }
+
+    #[test]
+    fn part2() {
+        let instructions = Day10::parse(EXAMPLE).unwrap();
+        assert_eq!(Day10::part2(&instructions).unwrap().to_string(), IMAGE);
+    }
+}
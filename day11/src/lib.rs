@@ -0,0 +1,358 @@
+//! Day 11: Monkey in the Middle.
+//!
+//! Also usable to play keep away with any monkeys:
+//!
+//! ```
+//! use day11::{play_keep_away, Monkey};
+//!
+//! let mut monkeys: Vec<Monkey> = [
+//!     "Monkey 0:\n  Starting items: 4\n  Operation: new = old * 2\n  Test: divisible by 13\n    If true: throw to monkey 1\n    If false: throw to monkey 1",
+//!     "Monkey 1:\n  Starting items: 1\n  Operation: new = old + 1\n  Test: divisible by 17\n    If true: throw to monkey 0\n    If false: throw to monkey 0",
+//! ]
+//! .iter()
+//! .map(|m| m.parse().unwrap())
+//! .collect();
+//! play_keep_away(&mut monkeys, 1, true).unwrap();
+//! let inspections: Vec<usize> = monkeys.iter().map(Monkey::inspections).collect();
+//! assert_eq!(inspections, vec![1, 2]);
+//! ```
+
+use std::{collections::VecDeque, str::FromStr};
+
+use anyhow::{bail, Context, Result};
+use common::gen::{Generate, Rng};
+use common::parse::{self, ParseContext, ParseError};
+use common::{Answer, Solution};
+use ibig::{modular::ModuloRing, UBig};
+
+/// A monkey, its items given by their worry level
+#[derive(Debug, Clone)]
+pub struct Monkey {
+    items: VecDeque<UBig>,
+    visited: usize,
+    operation: Operation,
+    division: usize,
+    throw1: usize,
+    throw2: usize,
+}
+
+impl Monkey {
+    /// Monkey throwing to `throw1` the items whose worry level is divisible by
+    /// `test_div`, and to `throw2` the others
+    pub fn new(
+        items: VecDeque<UBig>,
+        operation: Operation,
+        test_div: usize,
+        throw1: usize,
+        throw2: usize,
+    ) -> Self {
+        Monkey {
+            items,
+            visited: 0,
+            operation,
+            division: test_div,
+            throw1,
+            throw2,
+        }
+    }
+}
+
+/// Change of the worry level of an item when a monkey inspects it
+#[derive(Debug, Clone)]
+pub enum Operation {
+    Add(UBig),
+    Mult(UBig),
+    Square,
+}
+
+impl FromStr for Operation {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut operation = s.trim_start().split(" ").skip(1);
+        let operand = operation.next().parse_context("invalid operand", s)?;
+        let value = operation.next().parse_context("invalid value", s)?;
+        let operation = match (operand, value) {
+            ("*", "old") => Operation::Square,
+            ("+", v) => Operation::Add(parse::value(v, "invalid value")?),
+            ("*", v) => Operation::Mult(parse::value(v, "invalid value")?),
+            _ => return Err(ParseError::new("invalid operand", operand)),
+        };
+        Ok(operation)
+    }
+}
+
+impl FromStr for Monkey {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut lines = s.lines().skip(1);
+        let line = lines.next().parse_context("no starting items", s)?;
+        let (_, items) = line
+            .split_once(":")
+            .parse_context("invalid starting items", line)?;
+        let items = items
+            .trim_start()
+            .split(",")
+            .map(|i| parse::value::<UBig>(i.trim_start(), "invalid item"))
+            .collect::<Result<VecDeque<UBig>, ParseError>>()?;
+
+        let line = lines.next().parse_context("no operation", s)?;
+        let (_, operation) = line
+            .split_once("=")
+            .parse_context("invalid operation", line)?;
+        let operation = operation.parse()?;
+
+        let mut last_number = |name: &str| -> Result<usize, ParseError> {
+            let line = lines.next().parse_context(&format!("no {}", name), s)?;
+            let value = line
+                .split(" ")
+                .last()
+                .parse_context(&format!("invalid {}", name), line)?;
+            parse::value(value, &format!("invalid {}", name))
+        };
+        let test_div = last_number("division test")?;
+        let throw1 = last_number("throw 1")?;
+        let throw2 = last_number("throw 2")?;
+        if test_div == 0 {
+            return Err(last_number_error(s, 3, "division test by zero"));
+        }
+
+        Ok(Monkey::new(items, operation, test_div, throw1, throw2))
+    }
+}
+
+/// Error on the number ending line `i` of the block of a monkey
+fn last_number_error(block: &str, i: usize, message: &str) -> ParseError {
+    let line = block.lines().nth(i).unwrap_or_default();
+    let column = line.rfind(' ').map_or(1, |c| c + 2);
+    ParseError::new(message, line).at(i + 1, column)
+}
+
+impl Monkey {
+    /// Items held, the next one to be inspected first
+    pub fn items(&self) -> &VecDeque<UBig> {
+        &self.items
+    }
+
+    /// Items inspected so far
+    pub fn inspections(&self) -> usize {
+        self.visited
+    }
+
+    /// New worry level of an item, modulo the ring
+    pub fn execute_operation(&self, ring: &ModuloRing, value: &UBig) -> UBig {
+        let a = ring.from(value);
+        let r = match &self.operation {
+            Operation::Add(v) => a + ring.from(v),
+            Operation::Mult(v) => a * ring.from(v),
+            Operation::Square => a.clone() * a,
+        };
+        r.residue()
+    }
+}
+
+/// Play the rounds, the worry levels being divided by 3 after each inspection
+/// when `divide` is set. Worry levels are kept modulo the product of the
+/// divisibility tests. Fails when a monkey throws to itself or to a missing
+/// monkey
+pub fn play_keep_away(monkeys: &mut [Monkey], rounds: usize, divide: bool) -> Result<()> {
+    let len = monkeys.len();
+    let common_modulo: usize = monkeys.iter().map(|m| m.division).product();
+    let ring = ModuloRing::new(&UBig::from(common_modulo));
+
+    for round in 1..=rounds {
+        for i in 0..len {
+            while let Some(item) = monkeys[i].items.pop_front() {
+                let mut new_item = monkeys[i].execute_operation(&ring, &item);
+                if divide {
+                    new_item /= 3;
+                }
+
+                let new_mi = if &new_item % monkeys[i].division == 0 {
+                    monkeys[i].throw1
+                } else {
+                    monkeys[i].throw2
+                };
+                if new_mi == i {
+                    bail!("monkey {} throws to itself", i);
+                }
+                let target = monkeys
+                    .get_mut(new_mi)
+                    .with_context(|| format!("monkey {} throws to missing monkey {}", i, new_mi))?;
+                target.items.push_back(new_item);
+                monkeys[i].visited += 1;
+            }
+        }
+        common::event!(
+            DEBUG,
+            round,
+            inspections = ?monkeys.iter().map(|m| m.visited).collect::<Vec<_>>(),
+            "round"
+        );
+    }
+    Ok(())
+}
+
+/// Product of the two highest inspection counts
+fn monkey_business(monkeys: &[Monkey]) -> Result<usize> {
+    let mut inspections: Vec<usize> = monkeys.iter().map(|m| m.visited).collect();
+    inspections.sort_by_key(|&v| std::cmp::Reverse(v));
+    match inspections[..] {
+        [first, second, ..] => first
+            .checked_mul(second)
+            .context("monkey business overflow"),
+        _ => bail!("at least two monkeys are needed"),
+    }
+}
+
+pub struct Day11;
+
+impl Solution for Day11 {
+    const DAY: u8 = 11;
+
+    type Input = Vec<Monkey>;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        // Every monkey throws to two others
+        let count = input.split("\n\n").count();
+        if count < 2 {
+            let first = input.lines().next().unwrap_or_default();
+            return Err(ParseError::new("at least two monkeys are needed", first).into());
+        }
+        let mut index = 0;
+        let monkeys = parse::blocks(input, |block| {
+            let monkey: Monkey = block.parse()?;
+            for (i, target) in [(4, monkey.throw1), (5, monkey.throw2)] {
+                if target >= count || target == index {
+                    return Err(last_number_error(
+                        block,
+                        i,
+                        "no other monkey with this number",
+                    ));
+                }
+            }
+            index += 1;
+            Ok(monkey)
+        })?;
+        Ok(monkeys)
+    }
+
+    fn part1(monkeys: &Self::Input) -> Result<Answer> {
+        let mut monkeys = monkeys.clone();
+        play_keep_away(monkeys.as_mut_slice(), 20, true)?;
+        Ok(monkey_business(&monkeys)?.into())
+    }
+
+    fn part2(monkeys: &Self::Input) -> Result<Answer> {
+        let mut monkeys = monkeys.clone();
+        play_keep_away(monkeys.as_mut_slice(), 10_000, false)?;
+        Ok(monkey_business(&monkeys)?.into())
+    }
+}
+
+impl Generate for Day11 {
+    /// Monkeys, between 2 and 10 as their tests divide by distinct primes
+    const SIZE: usize = 8;
+
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        let mut primes = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29];
+        rng.shuffle(&mut primes);
+        let n = size.clamp(2, primes.len());
+        let monkeys: Vec<String> = (0..n)
+            .map(|i| {
+                let items: Vec<String> = (0..rng.usize(1..=8))
+                    .map(|_| rng.usize(50..=99).to_string())
+                    .collect();
+                let operation = match rng.below(5) {
+                    0 => "old * old".to_string(),
+                    1 | 2 => format!("old + {}", rng.usize(1..=8)),
+                    _ => format!("old * {}", rng.usize(2..=19)),
+                };
+                let mut target = || (i + rng.usize(1..=n - 1)) % n;
+                let (throw1, throw2) = (target(), target());
+                let mut monkey = format!("Monkey {}:\n", i);
+                monkey += &format!("  Starting items: {}\n", items.join(", "));
+                monkey += &format!("  Operation: new = {}\n", operation);
+                monkey += &format!("  Test: divisible by {}\n", primes[i]);
+                monkey += &format!("    If true: throw to monkey {}\n", throw1);
+                monkey += &format!("    If false: throw to monkey {}\n", throw2);
+                monkey
+            })
+            .collect();
+        monkeys.join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../example");
+
+    fn inspections(rounds: usize, divide: bool) -> Vec<usize> {
+        let mut monkeys = Day11::parse(EXAMPLE).unwrap();
+        play_keep_away(&mut monkeys, rounds, divide).unwrap();
+        monkeys.iter().map(|m| m.visited).collect()
+    }
+
+    #[test]
+    fn inspection_counts() {
+        assert_eq!(inspections(20, true), vec![101, 95, 7, 105]);
+        assert_eq!(inspections(20, false), vec![99, 97, 8, 103]);
+        assert_eq!(inspections(1000, false), vec![5204, 4792, 199, 5192]);
+    }
+
+    #[test]
+    fn items_after_first_round() {
+        let mut monkeys = Day11::parse(EXAMPLE).unwrap();
+        play_keep_away(&mut monkeys, 1, true).unwrap();
+        let items: Vec<Vec<UBig>> = monkeys
+            .iter()
+            .map(|m| m.items.iter().cloned().collect())
+            .collect();
+        let expected: Vec<Vec<UBig>> = [
+            vec![20, 23, 27, 26],
+            vec![2080, 25, 167, 207, 401, 1046],
+            vec![],
+            vec![],
+        ]
+        .into_iter()
+        .map(|items: Vec<u32>| items.into_iter().map(UBig::from).collect())
+        .collect();
+        assert_eq!(items, expected);
+    }
+
+    #[test]
+    fn malformed_monkeys() {
+        let error = |input: &str| Day11::parse(input).unwrap_err().to_string();
+        let missing = EXAMPLE.replace("If false: throw to monkey 3", "If false: throw to monkey 7");
+        assert_eq!(
+            error(&missing),
+            "line 6, column 31: no other monkey with this number in \"    If false: throw to monkey 7\""
+        );
+        let itself = EXAMPLE.replace("If true: throw to monkey 2", "If true: throw to monkey 0");
+        assert!(error(&itself).starts_with("line 5, column 30: no other monkey"));
+        let zero = EXAMPLE.replace("divisible by 13", "divisible by 0");
+        assert!(error(&zero).starts_with("line 18, column 22: division test by zero"));
+        let alone = EXAMPLE.split("\n\n").next().unwrap();
+        assert!(error(alone).starts_with("at least two monkeys"));
+
+        let mut monkeys = Day11::parse(EXAMPLE).unwrap();
+        monkeys[3].throw2 = 9;
+        let e = play_keep_away(&mut monkeys, 1, true).unwrap_err();
+        assert_eq!(e.to_string(), "monkey 3 throws to missing monkey 9");
+    }
+
+    #[test]
+    fn part1() {
+        let monkeys = Day11::parse(EXAMPLE).unwrap();
+        assert_eq!(Day11::part1(&monkeys).unwrap().to_string(), "10605");
+    }
+
+    #[test]
+    fn part2() {
+        let monkeys = Day11::parse(EXAMPLE).unwrap();
+        assert_eq!(Day11::part2(&monkeys).unwrap().to_string(), "2713310158");
+    }
+}
@@ -0,0 +1,230 @@
+//! Day 12: Hill Climbing Algorithm.
+//!
+//! Also usable to find the shortest hikes of any heightmap:
+//!
+//! ```
+//! let grid: day12::Grid = "SbcdefghijklmnopqrstuvwxyE\n".parse().unwrap();
+//! let path = day12::shortest_path(&grid, true).unwrap();
+//! assert_eq!(path.cost, 25);
+//! assert_eq!(grid.get(path.start()).unwrap(), b'S');
+//! ```
+
+use std::str::FromStr;
+
+use anyhow::{anyhow, Context, Result};
+use common::animate::{Animate, Colour, Frame, Pixel, Simulation};
+use common::gen::{Generate, Rng};
+use common::parse::ParseError;
+use common::search::{self, Path};
+use common::{Answer, Solution};
+use grid::{Coord, DenseGrid};
+
+/// Heightmap from `a` to `z`, with the starting `S` and the best signal `E`
+#[derive(Clone)]
+pub struct Grid {
+    data: DenseGrid<u8>,
+    starting: u8,
+    ending: u8,
+}
+
+impl FromStr for Grid {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let data = DenseGrid::parse_with(s, |c| match c {
+            'a'..='z' | 'S' | 'E' => Ok(c as u8),
+            _ => Err(ParseError::new("invalid elevation", &c.to_string())),
+        })?;
+        for marker in ["S", "E"] {
+            if !data.cells().contains(&marker.as_bytes()[0]) {
+                return Err(ParseError::new("missing square", marker));
+            }
+        }
+        Ok(Grid {
+            data,
+            starting: b'S',
+            ending: b'E',
+        })
+    }
+}
+
+impl Grid {
+    pub fn get(&self, pos: &Coord) -> Result<u8> {
+        self.data.get(*pos).copied().ok_or(anyhow!("invalid pos"))
+    }
+
+    /// `S`, and every `a` square unless `part1`
+    pub fn starting_pos(&self, part1: bool) -> Vec<Coord> {
+        self.data
+            .iter()
+            .filter(|(_, &v)| v == self.starting || (!part1 && v == b'a'))
+            .map(|(pos, _)| pos)
+            .collect()
+    }
+
+    /// Neighbours at most one higher than `pos`
+    pub fn reachable_squares(&self, pos: &Coord) -> Vec<Coord> {
+        let mut cur_v = self.data[*pos];
+        if cur_v == self.starting {
+            cur_v = b'a';
+        }
+
+        self.data
+            .neighbours4(*pos)
+            .filter(|&new_pos| {
+                let mut new_v = self.data[new_pos];
+                if new_v == self.ending {
+                    new_v = b'z';
+                }
+                new_v as isize - cur_v as isize <= 1
+            })
+            .collect()
+    }
+}
+
+/// BFS from every starting square at once
+pub fn shortest_path(grid: &Grid, part1: bool) -> Option<Path<Coord, usize>> {
+    search::bfs(
+        grid.starting_pos(part1),
+        |pos| grid.reachable_squares(pos),
+        |pos| grid.data[*pos] == grid.ending,
+    )
+}
+
+/// Elevation map with the shortest path walked one square at a time
+pub struct HikeSimulation {
+    grid: Grid,
+    path: Vec<Coord>,
+    walked: usize,
+}
+
+impl Simulation for HikeSimulation {
+    fn step(&mut self) -> bool {
+        if self.walked + 1 >= self.path.len() {
+            return false;
+        }
+        self.walked += 1;
+        true
+    }
+
+    fn frame(&self) -> Frame {
+        let data = &self.grid.data;
+        let mut frame = Frame::new(Default::default(), data.width(), data.height());
+        for (c, &v) in data.iter() {
+            let pixel = match v {
+                b'S' => Pixel::new('S', Colour::Green),
+                b'E' => Pixel::new('E', Colour::Red),
+                _ => Pixel::new(v as char, Colour::scale((v - b'a') as usize, 25)),
+            };
+            frame.set(c.signed(), pixel);
+        }
+        for &c in self.path.iter().take(self.walked + 1) {
+            frame.set(c.signed(), Pixel::new(data[c] as char, Colour::Yellow));
+        }
+        let focus = self.path[self.walked].signed();
+        frame.with_focus(focus).with_status(format!(
+            "{} of {} steps",
+            self.walked,
+            self.path.len() - 1
+        ))
+    }
+}
+
+pub struct Day12;
+
+impl Solution for Day12 {
+    const DAY: u8 = 12;
+
+    type Input = Grid;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(input.parse()?)
+    }
+
+    fn part1(grid: &Self::Input) -> Result<Answer> {
+        let sp = shortest_path(grid, true).context("no shortest path")?;
+        let steps = sp.cost;
+        Ok(steps.into())
+    }
+
+    fn part2(grid: &Self::Input) -> Result<Answer> {
+        let sp = shortest_path(grid, false).context("no shortest path")?;
+        let steps = sp.cost;
+        Ok(steps.into())
+    }
+}
+
+impl Animate for Day12 {
+    fn simulation(grid: &Self::Input, part: u8) -> Result<Box<dyn Simulation>> {
+        let path = shortest_path(grid, part == 1).context("no shortest path")?;
+        Ok(Box::new(HikeSimulation {
+            grid: grid.clone(),
+            path: path.nodes,
+            walked: 0,
+        }))
+    }
+}
+
+impl Generate for Day12 {
+    /// Width of the heightmap, at least 26 for the climb from `a` to `z`
+    const SIZE: usize = 159;
+
+    /// Elevation rises from west to east with random pits, maps without a path
+    /// from `S` to `E` are drawn again
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        let width = size.max(26);
+        let height = (width / 4).max(3);
+        loop {
+            let mut rows: Vec<Vec<u8>> = (0..height)
+                .map(|_| {
+                    (0..width)
+                        .map(|x| {
+                            let ramp = 25 * x / (width - 1);
+                            let pit = if rng.chance(0.2) { rng.usize(1..=3) } else { 0 };
+                            b'a' + ramp.saturating_sub(pit) as u8
+                        })
+                        .collect()
+                })
+                .collect();
+            rows[rng.usize(0..=height - 1)][0] = b'S';
+            rows[rng.usize(0..=height - 1)][width - 1] = b'E';
+            let input: String = rows
+                .into_iter()
+                .map(|r| String::from_utf8(r).unwrap() + "\n")
+                .collect();
+            if let Ok(grid) = input.parse::<Grid>() {
+                if shortest_path(&grid, true).is_some() {
+                    return input;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../example");
+
+    #[test]
+    fn path() {
+        let grid = Day12::parse(EXAMPLE).unwrap();
+        let path = shortest_path(&grid, true).unwrap();
+        assert_eq!(*path.start(), Coord::new(0, 0));
+        assert_eq!(*path.goal(), Coord::new(5, 2));
+        assert_eq!(path.steps(), 31);
+    }
+
+    #[test]
+    fn part1() {
+        let grid = Day12::parse(EXAMPLE).unwrap();
+        assert_eq!(Day12::part1(&grid).unwrap().to_string(), "31");
+    }
+
+    #[test]
+    fn part2() {
+        let grid = Day12::parse(EXAMPLE).unwrap();
+        assert_eq!(Day12::part2(&grid).unwrap().to_string(), "29");
+    }
+}
@@ -0,0 +1,368 @@
+//! Day 13: Distress Signal.
+//!
+//! Also usable as a parser and comparator of distress signal packets:
+//!
+//! ```
+//! use day13::{Comp, Packets};
+//!
+//! let left: Packets = "[[1],[2,3,4]]".parse().unwrap();
+//! let right: Packets = "[[1],4]".parse().unwrap();
+//! assert!(matches!(Packets::compare(&left, &right), Comp::True));
+//! assert_eq!(left.to_string(), "[[1],[2,3,4]]");
+//! ```
+
+use std::{cmp::Ordering, fmt::Display, str::FromStr};
+
+use anyhow::{bail, Result};
+use common::gen::{Generate, Rng};
+use common::parse::{self, ParseContext, ParseError};
+use common::{Answer, Solution};
+
+/// A packet: a list of integers and nested lists
+#[derive(Debug, Clone)]
+pub struct Packets {
+    values: Vec<Value>,
+    /// Length of the packet in the text it was parsed from
+    p_size: usize,
+}
+
+#[derive(Debug, Clone)]
+pub enum Value {
+    List(Vec<Value>),
+    Integer(usize),
+}
+
+/// Order of two packets
+#[derive(Debug)]
+pub enum Comp {
+    /// The left packet comes first
+    True,
+    /// Neither packet comes first
+    Neutral,
+    /// The right packet comes first
+    False,
+}
+
+impl FromStr for Packets {
+    type Err = ParseError;
+
+    /// Parse a single packet such as `[1,[2,3]]`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let packets = Packets::parse_prefix(s)?;
+        if packets.p_size != s.len() {
+            let rest = &s[packets.p_size..];
+            let err = ParseError::new("trailing characters", rest);
+            return Err(err.at_column(packets.p_size + 1));
+        }
+        Ok(packets)
+    }
+}
+
+impl Packets {
+    /// Parse a list, stopping after its closing bracket. Error columns are relative to `s`
+    fn parse_prefix(s: &str) -> Result<Self, ParseError> {
+        let mut chars = s.as_bytes().iter().peekable();
+
+        let first = chars.next().parse_context("no opening bracket", s)?;
+        if *first != b'[' {
+            let c = s.chars().next().unwrap_or_default().to_string();
+            return Err(ParseError::new("expected '['", &c).at_column(1));
+        }
+
+        let mut p_size = 1;
+        let mut n_size = 0;
+        let mut values = vec![];
+        let mut closed = false;
+
+        while let Some(c) = chars.peek().copied() {
+            if *c == b',' {
+                p_size += 1;
+                chars.next().unwrap();
+                continue;
+            }
+            if *c == b']' {
+                p_size += 1;
+                closed = true;
+                break;
+            }
+
+            match c {
+                b'[' => {
+                    let sub = Packets::parse_prefix(&s[p_size..]).map_err(|e| {
+                        let column = e.column.map(|c| c + p_size);
+                        ParseError { column, ..e }
+                    })?;
+                    p_size += sub.p_size;
+                    chars.nth(sub.p_size - 1);
+                    values.push(Value::List(sub.values))
+                }
+                b'0'..=b'9' => {
+                    p_size += 1;
+                    chars.next().unwrap();
+                    match chars.peek() {
+                        Some(b'0'..=b'9') => {
+                            n_size += 1;
+                        }
+                        Some(_) => {
+                            let i = &s[p_size - n_size - 1..p_size];
+                            n_size = 0;
+                            let v = parse::value(i, "invalid integer")?;
+                            values.push(Value::Integer(v));
+                        }
+                        _ => (),
+                    }
+                }
+                _ => {
+                    let c = (*c as char).to_string();
+                    return Err(ParseError::new("invalid value", &c).at_column(p_size + 1));
+                }
+            }
+        }
+        if !closed {
+            return Err(ParseError::new("unclosed list", s));
+        }
+        Ok(Packets { values, p_size })
+    }
+}
+
+impl Display for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Value::Integer(i) => write!(f, "{}", i),
+            Value::List(l) => {
+                write!(f, "[")?;
+                for (i, v) in l.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}", v)?;
+                }
+                write!(f, "]")
+            }
+        }
+    }
+}
+
+impl Display for Packets {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", Value::List(self.values.clone()))
+    }
+}
+
+impl Comp {
+    /// The order as used by `sort_by`
+    pub fn ord(&self) -> Ordering {
+        match self {
+            Comp::True => Ordering::Less,
+            Comp::Neutral => Ordering::Equal,
+            Comp::False => Ordering::Greater,
+        }
+    }
+}
+
+impl Value {
+    /// Order of two values, integers being compared as lists of one element
+    /// when the other value is a list
+    pub fn compare(v1: &Value, v2: &Value) -> Comp {
+        match (v1, v2) {
+            (Value::Integer(i1), Value::Integer(i2)) => {
+                if i1 < i2 {
+                    return Comp::True;
+                } else if i1 > i2 {
+                    return Comp::False;
+                }
+            }
+            (Value::List(l1), Value::List(l2)) => {
+                for (e1, e2) in l1.iter().zip(l2.iter()) {
+                    let c = Value::compare(e1, e2);
+                    match c {
+                        Comp::False | Comp::True => return c,
+                        _ => (),
+                    }
+                }
+                if l2.len() < l1.len() {
+                    return Comp::False;
+                } else if l1.len() < l2.len() {
+                    return Comp::True;
+                }
+            }
+            (Value::List(_), Value::Integer(_)) => {
+                return Value::compare(v1, &Value::List(vec![v2.clone()]));
+            }
+            (Value::Integer(_), Value::List(_)) => {
+                return Value::compare(&Value::List(vec![v1.clone()]), v2);
+            }
+        }
+        Comp::Neutral
+    }
+}
+
+impl Packets {
+    /// Elements of the outermost list
+    pub fn values(&self) -> &[Value] {
+        &self.values
+    }
+
+    /// Order of two packets, element by element, the shorter packet coming
+    /// first when all the elements of one are equal to those of the other
+    pub fn compare(p1: &Packets, p2: &Packets) -> Comp {
+        for (v1, v2) in p1.values.iter().zip(p2.values.iter()) {
+            let c = Value::compare(v1, v2);
+            match c {
+                Comp::False | Comp::True => return c,
+                _ => (),
+            }
+        }
+        if p2.values.len() < p1.values.len() {
+            return Comp::False;
+        } else if p1.values.len() < p2.values.len() {
+            return Comp::True;
+        }
+        Comp::Neutral
+    }
+}
+
+pub struct Day13;
+
+impl Solution for Day13 {
+    const DAY: u8 = 13;
+
+    type Input = Vec<Packets>;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        let pairs = parse::blocks(input.trim_end(), |block| {
+            let pair = parse::lines(block, str::parse::<Packets>)?;
+            let last = block.lines().last().unwrap_or_default();
+            match pair.len() {
+                0 | 1 => {
+                    let err = ParseError::new("missing second packet of the pair", last);
+                    Err(err.at(pair.len().max(1), 1))
+                }
+                2 => Ok(pair),
+                _ => {
+                    let third = block.lines().nth(2).unwrap_or_default();
+                    Err(ParseError::new("more than two packets in a pair", third).at(3, 1))
+                }
+            }
+        })?;
+        Ok(pairs.into_iter().flatten().collect())
+    }
+
+    fn part1(packets: &Self::Input) -> Result<Answer> {
+        let mut sum = 0;
+        for (i, pair) in packets.chunks(2).enumerate() {
+            let [left, right] = pair else {
+                bail!("packet {} has no pair", 2 * i + 1);
+            };
+            if let Comp::True = Packets::compare(left, right) {
+                sum += i + 1;
+            }
+        }
+        Ok(sum.into())
+    }
+
+    fn part2(packets: &Self::Input) -> Result<Answer> {
+        let mut packets = packets.clone();
+        let div1: Packets = "[[2]]".parse()?;
+        let div2: Packets = "[[6]]".parse()?;
+        packets.push(div1.clone());
+        packets.push(div2.clone());
+
+        packets.sort_by(|p1, p2| Packets::compare(p1, p2).ord());
+        let mut i1 = 0;
+        let mut i2 = 0;
+        for (i, packet) in packets.iter().enumerate() {
+            if let Comp::Neutral = Packets::compare(&div1, packet) {
+                i1 = i + 1;
+            }
+            if let Comp::Neutral = Packets::compare(&div2, packet) {
+                i2 = i + 1;
+            }
+        }
+        Ok((i1 * i2).into())
+    }
+}
+
+fn random_packet(rng: &mut Rng, depth: usize) -> String {
+    let items: Vec<String> = (0..rng.usize(0..=5))
+        .map(|_| {
+            if depth < 4 && rng.chance(0.3) {
+                random_packet(rng, depth + 1)
+            } else {
+                rng.usize(0..=10).to_string()
+            }
+        })
+        .collect();
+    format!("[{}]", items.join(","))
+}
+
+impl Generate for Day13 {
+    /// Pairs of packets
+    const SIZE: usize = 150;
+
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        let pairs: Vec<String> = (0..size)
+            .map(|_| format!("{}\n{}\n", random_packet(rng, 0), random_packet(rng, 0)))
+            .collect();
+        pairs.join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../example");
+
+    #[test]
+    fn pair_order() {
+        let packets = Day13::parse(EXAMPLE).unwrap();
+        let right_order: Vec<bool> = packets
+            .chunks(2)
+            .map(|c| matches!(Packets::compare(&c[0], &c[1]), Comp::True))
+            .collect();
+        assert_eq!(
+            right_order,
+            vec![true, true, false, true, false, true, false, false]
+        );
+    }
+
+    #[test]
+    fn display() {
+        for l in EXAMPLE.lines().filter(|l| !l.is_empty()) {
+            assert_eq!(l.parse::<Packets>().unwrap().to_string(), l);
+        }
+        assert!("[1]]".parse::<Packets>().is_err());
+    }
+
+    #[test]
+    fn unpaired_packets() {
+        let error = |input: &str| Day13::parse(input).unwrap_err().to_string();
+        assert_eq!(
+            error("[1]\n[2]\n\n[3]\n"),
+            "line 4, column 1: missing second packet of the pair in \"[3]\""
+        );
+        assert_eq!(
+            error("[1]\n[2]\n[3]\n"),
+            "line 3, column 1: more than two packets in a pair in \"[3]\""
+        );
+        // Trailing blank lines are fine
+        assert_eq!(Day13::parse("[1]\n[2]\n\n\n").unwrap().len(), 2);
+
+        let odd: Vec<Packets> = vec!["[1]".parse().unwrap()];
+        let e = Day13::part1(&odd).unwrap_err();
+        assert_eq!(e.to_string(), "packet 1 has no pair");
+    }
+
+    #[test]
+    fn part1() {
+        let packets = Day13::parse(EXAMPLE).unwrap();
+        assert_eq!(Day13::part1(&packets).unwrap().to_string(), "13");
+    }
+
+    #[test]
+    fn part2() {
+        let packets = Day13::parse(EXAMPLE).unwrap();
+        assert_eq!(Day13::part2(&packets).unwrap().to_string(), "140");
+    }
+}
@@ -0,0 +1,362 @@
+//! Day 14: Regolith Reservoir.
+//!
+//! Also usable to pour sand into any cave scan:
+//!
+//! ```
+//! let mut grid: day14::Grid = "498,4 -> 498,6 -> 496,6\n503,4 -> 502,4 -> 502,9 -> 494,9\n"
+//!     .parse()
+//!     .unwrap();
+//! let mut units = 0;
+//! while grid.step() {
+//!     units += 1;
+//! }
+//! assert_eq!(units, 24);
+//! let sand = grid.tiles().cells().iter().filter(|&t| *t == day14::Tile::Sand);
+//! assert_eq!(sand.count(), 24);
+//! ```
+
+use std::str::FromStr;
+
+use anyhow::Result;
+use common::animate::{Animate, Colour, Frame, Pixel, Simulation};
+use common::gen::{Generate, Rng};
+use common::parse::{self, ParseContext, ParseError};
+use common::{Answer, Solution};
+use grid::{Cell, Coord, DenseGrid};
+
+/// Slice of the cave holding the rocks of a scan and the sand source
+#[derive(Clone)]
+pub struct Grid {
+    tiles: DenseGrid<Tile>,
+    source: Coord,
+}
+
+#[derive(Clone, PartialEq, Eq)]
+pub enum Tile {
+    Air,
+    Rock,
+    SandSource,
+    Sand,
+}
+
+impl Cell for Tile {
+    fn from_char(c: char) -> Option<Self> {
+        match c {
+            '.' => Some(Tile::Air),
+            '#' => Some(Tile::Rock),
+            '+' => Some(Tile::SandSource),
+            'o' => Some(Tile::Sand),
+            _ => None,
+        }
+    }
+
+    fn to_char(&self) -> char {
+        match self {
+            Tile::SandSource => '+',
+            Tile::Air => '.',
+            Tile::Rock => '#',
+            Tile::Sand => 'o',
+        }
+    }
+}
+
+fn parse_coord(s: &str) -> Result<Coord, ParseError> {
+    let (x, y) = s.split_once(',').parse_context("invalid coord", s)?;
+    Ok(Coord::new(
+        parse::value(x, "invalid x")?,
+        parse::value(y, "invalid y")?,
+    ))
+}
+
+impl FromStr for Grid {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut max_width = 500;
+        let mut min_width = 500;
+        let mut height = 0;
+        let mut paths = vec![];
+
+        let lines = parse::lines(s, |line| {
+            line.split(" -> ")
+                .map(parse_coord)
+                .collect::<Result<Vec<_>, _>>()
+        })?;
+        for splits in lines {
+            for coords in splits.windows(2) {
+                let (coord1, coord2) = (coords[0], coords[1]);
+                max_width = max_width.max(coord1.x).max(coord2.x);
+                min_width = min_width.min(coord1.x).min(coord2.x);
+                height = height.max(coord1.y).max(coord2.y);
+
+                paths.push((coord1, coord2));
+            }
+        }
+        height += 1;
+        max_width += 1;
+
+        let width = max_width - min_width;
+        let mut tiles = DenseGrid::new(width, height, Tile::Air);
+
+        let source = Coord::new(500 - min_width, 0);
+        tiles[source] = Tile::SandSource;
+
+        for path in paths.iter() {
+            let x1 = path.0.x - min_width;
+            let x2 = path.1.x - min_width;
+            let y1 = path.0.y;
+            let y2 = path.1.y;
+
+            for x in x1.min(x2)..=x1.max(x2) {
+                tiles[Coord::new(x, y1)] = Tile::Rock;
+            }
+            for y in y1.min(y2)..=y1.max(y2) {
+                tiles[Coord::new(x1, y)] = Tile::Rock;
+            }
+        }
+
+        Ok(Grid { tiles, source })
+    }
+}
+
+impl Grid {
+    pub fn tiles(&self) -> &DenseGrid<Tile> {
+        &self.tiles
+    }
+
+    /// Drop one unit of sand from the source, `false` when it falls out of the
+    /// cave or the source is blocked
+    pub fn step(&mut self) -> bool {
+        let mut c = self.source;
+        let new_sand = &mut self.tiles[c];
+        if let Tile::Sand = new_sand {
+            return false;
+        }
+        *new_sand = Tile::Sand;
+
+        let mut rest = false;
+        while !rest {
+            let offsets = [(0, 1), (-1, 1), (1, 1)];
+            for (dx, dy) in offsets {
+                let new_c = c.offset(dx, dy);
+                let tile = new_c.and_then(|new_c| self.tiles.get(new_c));
+                match (new_c, tile) {
+                    (Some(new_c), Some(Tile::Air)) => {
+                        self.tiles[c] = Tile::Air;
+                        self.tiles[new_c] = Tile::Sand;
+
+                        c = new_c;
+                        rest = false;
+                        break;
+                    }
+                    (_, Some(_)) => rest = true,
+                    (_, None) => {
+                        self.tiles[c] = Tile::Air;
+                        return false;
+                    }
+                }
+            }
+        }
+
+        true
+    }
+
+    /// Add the floor two units below the lowest rock, wide enough for the
+    /// sand to pile up to the source
+    pub fn toggle_floor(&mut self) {
+        let width = self.tiles.width();
+        let sup = width * 2;
+        let new_height = self.tiles.height() + 2;
+        let new_width = width + sup * 2;
+
+        let mut tiles = DenseGrid::new(new_width, new_height, Tile::Air);
+
+        for (c, tile) in self.tiles.iter() {
+            tiles[Coord::new(c.x + sup, c.y)] = tile.clone();
+        }
+        for x in 0..new_width {
+            tiles[Coord::new(x, new_height - 1)] = Tile::Rock;
+        }
+
+        self.source.x += sup;
+        self.tiles = tiles;
+    }
+}
+
+impl std::fmt::Debug for Grid {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{}x{} grid:", self.tiles.width(), self.tiles.height())?;
+        write!(f, "{}", self.tiles)
+    }
+}
+
+/// Sand falling one unit at a time
+#[derive(Clone)]
+pub struct SandSimulation {
+    grid: Grid,
+    units: usize,
+}
+
+impl Simulation for SandSimulation {
+    fn step(&mut self) -> bool {
+        let rested = self.grid.step();
+        if rested {
+            self.units += 1;
+        }
+        rested
+    }
+
+    fn frame(&self) -> Frame {
+        let tiles = &self.grid.tiles;
+        let mut frame = Frame::new(Default::default(), tiles.width(), tiles.height());
+        for (c, tile) in tiles.iter() {
+            let colour = match tile {
+                Tile::Air => continue,
+                Tile::Rock => Colour::White,
+                Tile::SandSource => Colour::Red,
+                Tile::Sand => Colour::Yellow,
+            };
+            frame.set(c.signed(), Pixel::new(tile.to_char(), colour));
+        }
+        let source = self.grid.source.signed();
+        if frame.get(source) == Some(Pixel::EMPTY) {
+            frame.set(source, Pixel::new('+', Colour::Red));
+        }
+        frame
+            .with_focus(source)
+            .with_status(format!("{} units of sand at rest", self.units))
+    }
+
+    fn inspect(&self) -> Vec<(&'static str, String)> {
+        let source = self.grid.source;
+        // The source is buried once the last unit rests on it
+        let blocked = matches!(self.grid.tiles[source], Tile::Sand);
+        vec![
+            ("units at rest", self.units.to_string()),
+            ("source", format!("{},{}", source.x, source.y)),
+            ("source blocked", blocked.to_string()),
+            (
+                "grid",
+                format!("{}x{}", self.grid.tiles.width(), self.grid.tiles.height()),
+            ),
+        ]
+    }
+
+    fn snapshot(&self) -> Option<Box<dyn Simulation>> {
+        Some(Box::new(self.clone()))
+    }
+}
+
+pub struct Day14;
+
+impl Solution for Day14 {
+    const DAY: u8 = 14;
+
+    type Input = Grid;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(input.parse()?)
+    }
+
+    fn part1(grid: &Self::Input) -> Result<Answer> {
+        let mut grid = grid.clone();
+        let mut i: usize = 0;
+        while grid.step() {
+            i += 1;
+        }
+        common::event!(TRACE, "resting sand:\n{:?}", grid);
+        Ok(i.into())
+    }
+
+    fn part2(grid: &Self::Input) -> Result<Answer> {
+        let mut grid = grid.clone();
+        grid.toggle_floor();
+        let mut i: usize = 0;
+        while grid.step() {
+            i += 1;
+        }
+        common::event!(TRACE, "resting sand:\n{:?}", grid);
+        Ok(i.into())
+    }
+}
+
+impl Animate for Day14 {
+    fn simulation(grid: &Self::Input, part: u8) -> Result<Box<dyn Simulation>> {
+        let mut grid = grid.clone();
+        if part == 2 {
+            grid.toggle_floor();
+        }
+        Ok(Box::new(SandSimulation { grid, units: 0 }))
+    }
+}
+
+impl Generate for Day14 {
+    /// Rock paths
+    const SIZE: usize = 150;
+
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        (0..size)
+            .map(|_| {
+                let mut p = (rng.usize(440..=560), rng.usize(13..=170));
+                let mut points = vec![p];
+                let horizontal = rng.chance(0.5);
+                for i in 0..rng.usize(1..=5) {
+                    let len = rng.usize(1..=8);
+                    let forward = rng.chance(0.5);
+                    let v = if (i % 2 == 0) == horizontal {
+                        &mut p.0
+                    } else {
+                        &mut p.1
+                    };
+                    *v = if forward {
+                        *v + len
+                    } else {
+                        v.saturating_sub(len).max(2)
+                    };
+                    points.push(p);
+                }
+                let points: Vec<String> =
+                    points.iter().map(|(x, y)| format!("{},{}", x, y)).collect();
+                points.join(" -> ") + "\n"
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../example");
+
+    #[test]
+    fn sand_at_rest() {
+        let mut grid = Day14::parse(EXAMPLE).unwrap();
+        while grid.step() {}
+        let expected = "\
+..........
+..........
+......o...
+.....ooo..
+....#ooo##
+...o#ooo#.
+..###ooo#.
+....oooo#.
+.o.ooooo#.
+#########.
+";
+        assert_eq!(grid.tiles.to_string(), expected);
+    }
+
+    #[test]
+    fn part1() {
+        let grid = Day14::parse(EXAMPLE).unwrap();
+        assert_eq!(Day14::part1(&grid).unwrap().to_string(), "24");
+    }
+
+    #[test]
+    fn part2() {
+        let grid = Day14::parse(EXAMPLE).unwrap();
+        assert_eq!(Day14::part2(&grid).unwrap().to_string(), "93");
+    }
+}
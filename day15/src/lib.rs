@@ -0,0 +1,409 @@
+//! Day 15: Beacon Exclusion Zone.
+//!
+//! Also usable to query the coverage of any set of sensors:
+//!
+//! ```
+//! let grid: day15::Grid = "Sensor at x=0, y=0: closest beacon is at x=2, y=0\n"
+//!     .parse()
+//!     .unwrap();
+//! assert_eq!(grid.sensors[0].closest_beacon_dist, 2);
+//! assert_eq!(grid.covered_in_row(0), 4);
+//! assert_eq!(grid.covered_in_row(1), 3);
+//! ```
+
+use std::{collections::HashSet, str::FromStr};
+
+use anyhow::{Context, Result};
+use common::animate::{Animate, Colour, Frame, Pixel, Simulation};
+use common::gen::{Generate, Rng};
+use common::parse::{self, ParseContext, ParseError};
+use common::point::Point;
+use common::{Answer, Solution};
+use itertools::Itertools;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// Row checked by part 1
+pub const ROW: isize = 2000000;
+/// Largest coordinate of the distress beacon searched by part 2
+pub const SEARCH_MAX: isize = 4000000;
+
+/// Largest side of the animation frames, in pixels
+const FRAME_SIZE: isize = 200;
+
+/// Sensors of the scan and the beacons they detected
+#[derive(Debug, Clone)]
+pub struct Grid {
+    pub sensors: Vec<Sensor>,
+    pub beacons: Vec<Point<isize>>,
+}
+
+/// Sensor with the closest beacon it detected, nothing else lying within
+/// `closest_beacon_dist` of it
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sensor {
+    pub coord: Point<isize>,
+    pub closest_beacon: Point<isize>,
+    pub closest_beacon_dist: usize,
+}
+
+impl FromStr for Grid {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let sensors = parse::lines(s, |line| {
+            let (sensor_str, beacon_str) =
+                line.split_once(":").parse_context("invalid line", line)?;
+            let beacon = parse_coord(beacon_str)?;
+            let sensor_coord = parse_coord(sensor_str)?;
+            Ok(Sensor {
+                coord: sensor_coord,
+                closest_beacon: beacon,
+                closest_beacon_dist: sensor_coord.manhattan(&beacon),
+            })
+        })?;
+        let beacons = sensors.iter().map(|s| s.closest_beacon).collect();
+
+        Ok(Grid { sensors, beacons })
+    }
+}
+
+fn parse_coord(s: &str) -> Result<Point<isize>, ParseError> {
+    let (_, x_str) = s.split_once("x=").parse_context("invalid x coord", s)?;
+    let (x_str, _) = x_str.split_once(",").parse_context("invalid x coord", s)?;
+    let x = parse::value(x_str, "invalid x coord")?;
+    let (_, y_str) = s.split_once("y=").parse_context("invalid y coord", s)?;
+    let y = parse::value(y_str, "invalid y coord")?;
+
+    Ok(Point { x, y })
+}
+
+impl Grid {
+    /// Number of positions of row `count_y` where a beacon cannot be
+    pub fn covered_in_row(&self, count_y: isize) -> usize {
+        let mut count: usize = 0;
+
+        let mut visited_x: HashSet<isize> = HashSet::new();
+        for sensor in self.sensors.iter() {
+            let mut queue = vec![(sensor.coord.x, true), (sensor.coord.x + 1, false)];
+            while let Some((coord_x, left)) = queue.pop() {
+                let coord = Point {
+                    x: coord_x,
+                    y: count_y,
+                };
+                // Free square
+                if coord.manhattan(&sensor.coord) > sensor.closest_beacon_dist {
+                    continue;
+                }
+
+                if left {
+                    queue.push((coord_x - 1, left));
+                } else {
+                    queue.push((coord_x + 1, left));
+                }
+
+                // Special case: beacons are not considered taken squares
+                if coord == sensor.closest_beacon {
+                    continue;
+                }
+
+                if !visited_x.contains(&coord_x) {
+                    visited_x.insert(coord_x);
+                    count += 1;
+                }
+            }
+        }
+
+        count
+    }
+
+    /// The only uncovered position with both coordinates in `0..=max_val`
+    pub fn distress_beacon(&self, max_val: isize) -> Option<Point<isize>> {
+        let min_val = 0;
+
+        // We know that only one square is possible for the distress beacon on the whole map
+        // At least 2 diamond areas are necessary to isolate a single point
+        // Thus our beacon is a point on the line of a diamond + 1
+        // Each line of a diamond has a simple ax+b equation
+        // We can compute the intersections of all the lines of the diamond areas + 1 to find the
+        // beacon
+
+        let candidates = |sensor: &Sensor| {
+            self.sensors
+                .iter()
+                .filter(move |&s| !(s == sensor))
+                .flat_map(move |sensor2| sensor.compute_intersections(sensor2))
+                .filter(|p| p.x >= min_val && p.y >= min_val && p.x <= max_val && p.y <= max_val)
+                .collect::<Vec<_>>()
+        };
+        let free = |p: &Point<isize>| {
+            self.sensors
+                .iter()
+                .all(|sensor| p.manhattan(&sensor.coord) > sensor.closest_beacon_dist)
+        };
+
+        // There is a single free point, so whichever thread finds it gives the
+        // same answer as the sequential search
+        #[cfg(feature = "parallel")]
+        let beacon = {
+            let points: HashSet<Point<isize>> =
+                self.sensors.par_iter().flat_map_iter(candidates).collect();
+            points.into_par_iter().find_any(free)
+        };
+        #[cfg(not(feature = "parallel"))]
+        let beacon = {
+            let points: HashSet<Point<isize>> = self.sensors.iter().flat_map(candidates).collect();
+            points.into_iter().find(free)
+        };
+        beacon
+    }
+}
+
+impl Sensor {
+    /// Crossings of the lines bordering both coverage diamonds from outside
+    pub fn compute_intersections(&self, other: &Self) -> Vec<Point<isize>> {
+        let mut intersections = vec![];
+        let self_dist = self.closest_beacon_dist as isize;
+        let other_dist = other.closest_beacon_dist as isize;
+
+        let self_points = [
+            (1, self.coord.y - (self.coord.x - (self_dist + 1))),
+            (1, self.coord.y - (self.coord.x + (self_dist + 1))),
+            (-1, self.coord.y + (self.coord.x - (self_dist + 1))),
+            (-1, self.coord.y + (self.coord.x + (self_dist + 1))),
+        ];
+
+        let other_points = [
+            (1, other.coord.y - (other.coord.x - (other_dist + 1))),
+            (1, other.coord.y - (other.coord.x + (other_dist + 1))),
+            (-1, other.coord.y + (other.coord.x - (other_dist + 1))),
+            (-1, other.coord.y + (other.coord.x + (other_dist + 1))),
+        ];
+
+        for (p1, p2) in self_points.iter().cartesian_product(other_points.iter()) {
+            let (a1, b1) = p1;
+            let (a2, b2) = p2;
+
+            // Parallel lines
+            if a1 == a2 {
+                continue;
+            }
+
+            let x = (b2 - b1) / (a1 - a2);
+            let y = a1 * x + b1;
+            intersections.push(Point { x, y });
+        }
+
+        intersections
+    }
+}
+
+/// Sensor coverage drawn one sensor at a time, downscaled so that a pixel
+/// covers a square of `cell` positions. Part 1 highlights the checked row,
+/// part 2 ends on the distress beacon
+pub struct CoverageSimulation {
+    grid: Grid,
+    part: u8,
+    added: usize,
+    cell: isize,
+    /// Corners of the drawn area, in pixels
+    min: Point<isize>,
+    max: Point<isize>,
+    distress: Option<Point<isize>>,
+}
+
+impl CoverageSimulation {
+    fn new(grid: Grid, part: u8) -> Self {
+        let mut corners = vec![];
+        for s in grid.sensors.iter() {
+            let d = s.closest_beacon_dist as isize;
+            corners.push(Point::new(s.coord.x - d, s.coord.y - d));
+            corners.push(Point::new(s.coord.x + d, s.coord.y + d));
+        }
+        if part == 2 {
+            corners.push(Point::new(0, 0));
+            corners.push(Point::new(SEARCH_MAX, SEARCH_MAX));
+        }
+        let min = Point::new(
+            corners.iter().map(|p| p.x).min().unwrap_or(0),
+            corners.iter().map(|p| p.y).min().unwrap_or(0),
+        );
+        let max = Point::new(
+            corners.iter().map(|p| p.x).max().unwrap_or(0),
+            corners.iter().map(|p| p.y).max().unwrap_or(0),
+        );
+        let span = (max.x - min.x).max(max.y - min.y) + 1;
+        let cell = (span + FRAME_SIZE - 1) / FRAME_SIZE;
+        CoverageSimulation {
+            grid,
+            part,
+            added: 0,
+            cell,
+            min: Point::new(min.x.div_euclid(cell), min.y.div_euclid(cell)),
+            max: Point::new(max.x.div_euclid(cell), max.y.div_euclid(cell)),
+            distress: None,
+        }
+    }
+
+    /// Pixel holding the position `p`
+    fn pixel(&self, p: Point<isize>) -> Point<isize> {
+        Point::new(p.x.div_euclid(self.cell), p.y.div_euclid(self.cell))
+    }
+}
+
+impl Simulation for CoverageSimulation {
+    fn step(&mut self) -> bool {
+        if self.added < self.grid.sensors.len() {
+            self.added += 1;
+            return true;
+        }
+        if self.part == 2 && self.distress.is_none() {
+            self.distress = self.grid.distress_beacon(SEARCH_MAX);
+            return self.distress.is_some();
+        }
+        false
+    }
+
+    fn frame(&self) -> Frame {
+        let width = (self.max.x - self.min.x + 1) as usize;
+        let height = (self.max.y - self.min.y + 1) as usize;
+        let mut frame = Frame::new(self.min, width, height);
+        let sensors = &self.grid.sensors[..self.added];
+        for y in self.min.y..=self.max.y {
+            for x in self.min.x..=self.max.x {
+                let centre =
+                    Point::new(x * self.cell + self.cell / 2, y * self.cell + self.cell / 2);
+                let covered = sensors
+                    .iter()
+                    .any(|s| centre.manhattan(&s.coord) <= s.closest_beacon_dist);
+                if covered {
+                    let on_row = self.part == 1 && self.pixel(Point::new(0, ROW)).y == y;
+                    let colour = if on_row {
+                        Colour::Magenta
+                    } else {
+                        Colour::Blue
+                    };
+                    frame.set(Point::new(x, y), Pixel::new('#', colour));
+                }
+            }
+        }
+        for s in sensors {
+            frame.set(self.pixel(s.closest_beacon), Pixel::new('B', Colour::Cyan));
+            frame.set(self.pixel(s.coord), Pixel::new('S', Colour::Yellow));
+        }
+        let mut status = format!(
+            "{} of {} sensors, 1 pixel = {} positions",
+            self.added,
+            self.grid.sensors.len(),
+            self.cell
+        );
+        let focus = match (sensors.last(), self.distress) {
+            (_, Some(p)) => {
+                frame.set(self.pixel(p), Pixel::new('X', Colour::Red));
+                status += &format!(", distress beacon at {},{}", p.x, p.y);
+                Some(self.pixel(p))
+            }
+            (Some(s), None) => Some(self.pixel(s.coord)),
+            (None, None) => None,
+        };
+        frame.focus = focus;
+        frame.with_status(status)
+    }
+}
+
+pub struct Day15;
+
+impl Solution for Day15 {
+    const DAY: u8 = 15;
+
+    type Input = Grid;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(input.parse()?)
+    }
+
+    fn part1(grid: &Self::Input) -> Result<Answer> {
+        Ok(grid.covered_in_row(ROW).into())
+    }
+
+    fn part2(grid: &Self::Input) -> Result<Answer> {
+        let p = grid.distress_beacon(SEARCH_MAX).context("not found")?;
+        Ok((p.x * 4000000 + p.y).into())
+    }
+}
+
+impl Animate for Day15 {
+    fn simulation(grid: &Self::Input, part: u8) -> Result<Box<dyn Simulation>> {
+        Ok(Box::new(CoverageSimulation::new(grid.clone(), part)))
+    }
+}
+
+impl Generate for Day15 {
+    /// Sensors, at least the four placed on the corners of the search area
+    const SIZE: usize = 30;
+
+    /// Every sensor stops just short of a random distress beacon. The sensors
+    /// on the corners of the search area then leave it as the only free position
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        let distress = Point::new(rng.isize(1..=SEARCH_MAX - 1), rng.isize(1..=SEARCH_MAX - 1));
+        let mut sensors = vec![
+            Point::new(0, 0),
+            Point::new(SEARCH_MAX, 0),
+            Point::new(0, SEARCH_MAX),
+            Point::new(SEARCH_MAX, SEARCH_MAX),
+        ];
+        while sensors.len() < size {
+            let p = Point::new(rng.isize(0..=SEARCH_MAX), rng.isize(0..=SEARCH_MAX));
+            if p != distress {
+                sensors.push(p);
+            }
+        }
+        rng.shuffle(&mut sensors);
+        sensors
+            .into_iter()
+            .map(|s| {
+                // Closest beacon on the edge of the range, one short of the distress beacon
+                let range = s.manhattan(&distress) as isize - 1;
+                let dx = rng.isize(-range..=range);
+                let dy = (range - dx.abs()) * if rng.chance(0.5) { 1 } else { -1 };
+                format!(
+                    "Sensor at x={}, y={}: closest beacon is at x={}, y={}\n",
+                    s.x,
+                    s.y,
+                    s.x + dx,
+                    s.y + dy
+                )
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../example");
+
+    #[test]
+    fn parse_sensors() {
+        let grid = Day15::parse(EXAMPLE).unwrap();
+        assert_eq!(grid.sensors.len(), 14);
+        assert_eq!(grid.sensors[0].coord, Point::new(2, 18));
+        assert_eq!(grid.sensors[0].closest_beacon, Point::new(-2, 15));
+        assert_eq!(grid.sensors[6].closest_beacon_dist, 9);
+    }
+
+    #[test]
+    fn part1() {
+        let grid = Day15::parse(EXAMPLE).unwrap();
+        assert_eq!(grid.covered_in_row(10), 26);
+    }
+
+    #[test]
+    fn part2() {
+        let grid = Day15::parse(EXAMPLE).unwrap();
+        let beacon = grid.distress_beacon(20).unwrap();
+        assert_eq!(beacon, Point::new(14, 11));
+        assert_eq!(beacon.x * 4000000 + beacon.y, 56000011);
+    }
+}
@@ -0,0 +1,556 @@
+//! Day 16: Proboscidea Volcanium.
+//!
+//! Also usable to explore any network of valves and tunnels:
+//!
+//! ```
+//! use day16::Tunnels;
+//!
+//! let tunnels: Tunnels = "Valve AA has flow rate=0; tunnels lead to valves BB
+//! Valve BB has flow rate=10; tunnel leads to valve AA"
+//!     .parse()
+//!     .unwrap();
+//! assert_eq!(tunnels.shortest_path_cost("AA", "BB"), Some(1));
+//! assert_eq!(tunnels.max_pressure(30), Some(280));
+//! ```
+
+use std::{
+    cell::RefCell,
+    cmp::Reverse,
+    collections::{BTreeSet, HashMap},
+    rc::Rc,
+    str::FromStr,
+};
+
+use anyhow::{Context, Result};
+use common::diff::{Differential, Variant};
+use common::gen::{Generate, Rng};
+use common::optimize::{self, Problem};
+use common::parse::{self, ParseContext, ParseError};
+use common::search;
+use common::{Answer, Solution};
+
+use itertools::Itertools;
+
+/// Name of the valve where the exploration starts
+pub const START: &str = "AA";
+
+/// The valves, by name
+#[derive(Debug)]
+pub struct Tunnels {
+    pub valves: HashMap<String, Rc<RefCell<Valve>>>,
+}
+
+pub struct Valve {
+    pub name: String,
+    /// Pressure released per minute once open
+    pub flow: usize,
+    /// Valves one tunnel away
+    pub neighbours: Vec<Rc<RefCell<Valve>>>,
+    /// Minutes needed to walk to each reachable valve and open it
+    pub path_costs: HashMap<String, usize>,
+}
+
+impl std::fmt::Debug for Valve {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let neighbours_names = self
+            .neighbours
+            .iter()
+            .map(|n| n.borrow().name.clone())
+            .collect::<Vec<String>>()
+            .join(", ");
+        f.write_fmt(format_args!("Valve {}: ({})", self.name, neighbours_names))
+    }
+}
+
+impl Tunnels {
+    /// Minutes needed to walk from one valve to another, `None` if unreachable
+    pub fn shortest_path_cost(&self, from: &str, to: &str) -> Option<usize> {
+        let path = search::bfs(
+            [from.to_string()],
+            |name| {
+                self.valves[name]
+                    .borrow()
+                    .neighbours
+                    .iter()
+                    .map(|n| n.borrow().name.clone())
+                    .collect::<Vec<_>>()
+            },
+            |name| name == to,
+        )?;
+        Some(path.cost)
+    }
+
+    fn start_state(&self, minutes: usize) -> Option<State> {
+        let start_pos = self.valves.get(START)?;
+        Some(State::new(
+            0,
+            minutes,
+            0,
+            start_pos.clone(),
+            BTreeSet::new(),
+        ))
+    }
+
+    /// Most pressure released in `minutes` by opening valves from [`START`],
+    /// `None` without such a valve
+    pub fn max_pressure(&self, minutes: usize) -> Option<usize> {
+        let best = optimize::branch_and_bound(self, self.start_state(minutes)?);
+        Some(best.score)
+    }
+
+    /// Most pressure released in `minutes` by two explorers starting together
+    /// from [`START`], `None` without such a valve
+    pub fn max_pressure_with_elephant(&self, minutes: usize) -> Option<u64> {
+        // We first run as if we were alone and we save the best combinations of opened valves
+        // We then simply pick the two best disjoint sets of opened valves
+        let mut best = Best::default();
+        optimize::explore(self, self.start_state(minutes)?, |state| {
+            let pressure = best.entry(state.opened_valves.clone()).or_default();
+            *pressure = (*pressure).max(state.pressure as u64);
+        });
+        Some(best_disjoint_pair(&best))
+    }
+}
+
+/// Most pressure released by two disjoint sets of opened valves
+fn best_disjoint_pair(best: &Best) -> u64 {
+    let pressure = best
+        .iter()
+        .tuple_combinations()
+        .filter(|(human, elephant)| human.0.is_disjoint(elephant.0))
+        .map(|(human, elephant)| human.1 + elephant.1)
+        .max();
+    // Only the empty set when no valve is worth opening
+    pressure.unwrap_or(0)
+}
+
+/// Opening valves one after the other, most rewarding first
+impl Problem for Tunnels {
+    type Node = State;
+    type Score = usize;
+    /// Current valve, opened valves and minutes left
+    type Key = (String, BTreeSet<String>, usize);
+
+    fn successors(&self, state: &State) -> Vec<State> {
+        let mut moves = state.possible_moves(self);
+        moves.sort_by_key(|m| Reverse(m.reward));
+        moves.iter().map(|mv| state.apply(mv)).collect()
+    }
+
+    fn score(&self, state: &State) -> usize {
+        state.pressure
+    }
+
+    /// As if every valve could be reached straight from the current one
+    fn bound(&self, state: &State) -> Option<usize> {
+        let rewards: usize = state.possible_moves(self).iter().map(|m| m.reward).sum();
+        Some(state.pressure + rewards)
+    }
+
+    /// Opening the same valves in another order ends with the same choices
+    fn key(&self, state: &State) -> Option<Self::Key> {
+        let position = state.position.borrow().name.clone();
+        let minutes_left = state.max_turn - state.turn;
+        Some((position, state.opened_valves.clone(), minutes_left))
+    }
+}
+
+impl FromStr for Tunnels {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let mut tunnels = Tunnels {
+            valves: HashMap::new(),
+        };
+
+        let lines = parse::lines(s, |line| {
+            if line.trim().is_empty() {
+                return Ok(None);
+            }
+            let name = line
+                .split(" ")
+                .nth(1)
+                .parse_context("no valve name", line)?;
+            let (_, flow) = line.split_once("=").parse_context("no flow rate", line)?;
+            let flow = flow.split_once(";").parse_context("no flow rate", line)?.0;
+            let flow = parse::value::<usize>(flow, "invalid flow rate")?;
+            let (_, neighbours) = line
+                .split_once("to valve")
+                .parse_context("no neighbours", line)?;
+            let neighbours_names: Vec<String> = neighbours
+                .get(1..)
+                .unwrap_or_default()
+                .split(",")
+                .map(|n| n.trim().to_string())
+                .collect();
+            Ok(Some((name, flow, neighbours_names)))
+        })?;
+
+        for (name, flow, neighbours_names) in lines.into_iter().flatten() {
+            if let Some(v) = tunnels.valves.get(name) {
+                v.borrow_mut().flow = flow;
+            } else {
+                let v = Valve {
+                    name: name.to_string(),
+                    flow,
+                    neighbours: vec![],
+                    path_costs: HashMap::new(),
+                };
+                tunnels
+                    .valves
+                    .insert(name.to_string(), Rc::new(RefCell::new(v)));
+            }
+
+            for neighbour_name in neighbours_names.iter() {
+                if !tunnels.valves.contains_key(neighbour_name) {
+                    let v = Valve {
+                        name: neighbour_name.to_string(),
+                        flow: 0,
+                        neighbours: vec![],
+                        path_costs: HashMap::new(),
+                    };
+                    tunnels
+                        .valves
+                        .insert(neighbour_name.to_string(), Rc::new(RefCell::new(v)));
+                }
+                let valve = &tunnels.valves[name];
+                let neighbour = &tunnels.valves[neighbour_name];
+                valve.borrow_mut().neighbours.push(neighbour.clone());
+            }
+        }
+
+        for v1 in tunnels.valves.values() {
+            for v2 in tunnels.valves.values() {
+                if v1.borrow().name == v2.borrow().name {
+                    continue;
+                }
+                let Some(path_cost) =
+                    tunnels.shortest_path_cost(&v1.borrow().name, &v2.borrow().name)
+                else {
+                    continue;
+                };
+                // +1 for opening the valve
+                v1.borrow_mut()
+                    .path_costs
+                    .insert(v2.borrow().name.clone(), path_cost + 1);
+            }
+        }
+
+        Ok(tunnels)
+    }
+}
+
+/// A single explorer, after some valves were opened
+#[derive(Debug, Clone)]
+pub struct State {
+    pub turn: usize,
+    pub max_turn: usize,
+    pub pressure: usize,
+    pub position: Rc<RefCell<Valve>>,
+    pub opened_valves: BTreeSet<String>,
+}
+
+impl State {
+    pub fn new(
+        turn: usize,
+        max_turn: usize,
+        pressure: usize,
+        position: Rc<RefCell<Valve>>,
+        opened_valves: BTreeSet<String>,
+    ) -> State {
+        State {
+            turn,
+            max_turn,
+            pressure,
+            position,
+            opened_valves,
+        }
+    }
+
+    /// State after walking to a valve and opening it
+    pub fn apply(&self, mv: &Move) -> Self {
+        let mut next_state = self.clone();
+        next_state
+            .opened_valves
+            .insert(mv.pos.borrow().name.clone());
+        next_state.pressure += mv.reward;
+        next_state.position = mv.pos.clone();
+        next_state.turn = self.turn + mv.path_cost;
+        next_state
+    }
+
+    /// Moves opening a closed valve with some flow before the time runs out
+    pub fn possible_moves(&self, tunnels: &Tunnels) -> Vec<Move> {
+        let mut moves = vec![];
+        for v in tunnels.valves.values() {
+            let valve = v.borrow();
+            if valve.name == self.position.borrow().name {
+                continue;
+            }
+            if self.opened_valves.contains(&valve.name) {
+                continue;
+            }
+            // It is useless to open a 0-flow valve
+            if valve.flow == 0 {
+                continue;
+            }
+
+            // Ignore unreachable valves and invalid paths
+            let Some(&path_cost) = self.position.borrow().path_costs.get(&valve.name) else {
+                continue;
+            };
+            if path_cost + self.turn > self.max_turn {
+                continue;
+            }
+
+            let reward = valve.flow * (self.max_turn - (self.turn + path_cost));
+
+            let mv = Move {
+                path_cost,
+                reward,
+                pos: v.clone(),
+            };
+            moves.push(mv);
+        }
+        moves
+    }
+
+    /// Final state releasing the most pressure, and the moves leading to it
+    /// from the last to the first
+    pub fn find_best_moves(&self, tunnels: &Tunnels) -> (Self, Vec<Move>) {
+        let mut best_moves = vec![];
+        let mut best_state = self.clone();
+        let mut best_pressure = 0;
+
+        let mut moves = self.possible_moves(tunnels);
+        // Consider best moves first
+        moves.sort_by_key(|m| m.reward);
+        moves.reverse();
+
+        for mv in moves {
+            let next = self.apply(&mv);
+            let (next, mut next_moves) = next.find_best_moves(tunnels);
+            next_moves.push(mv);
+            if next.pressure > best_pressure {
+                best_pressure = next.pressure;
+                best_moves = next_moves;
+                best_state = next;
+            }
+        }
+        (best_state, best_moves)
+    }
+
+    /// Final state releasing the most pressure, recording along the way the
+    /// most pressure released by each set of opened valves
+    pub fn find_best_moves2(&self, tunnels: &Tunnels, best: &mut Best) -> Self {
+        let mut best_state = self.clone();
+
+        best.entry(self.opened_valves.clone())
+            .and_modify(|v| {
+                if self.pressure as u64 > *v {
+                    *v = self.pressure as u64
+                }
+            })
+            .or_insert(self.pressure.try_into().unwrap());
+
+        for mv in self.possible_moves(tunnels) {
+            let next = self.apply(&mv).find_best_moves2(tunnels, best);
+            if next.pressure > best_state.pressure {
+                best_state = next;
+            }
+        }
+        best_state
+    }
+}
+
+/// Walking to a valve and opening it
+#[derive(Debug, Clone)]
+pub struct Move {
+    pub pos: Rc<RefCell<Valve>>,
+    pub path_cost: usize,
+    pub reward: usize,
+}
+
+/// Most pressure released for each set of opened valves
+pub type Best = HashMap<BTreeSet<String>, u64>;
+
+pub struct Day16;
+
+impl Solution for Day16 {
+    const DAY: u8 = 16;
+
+    type Input = Tunnels;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(input.parse()?)
+    }
+
+    fn part1(tunnels: &Self::Input) -> Result<Answer> {
+        let pressure = tunnels.max_pressure(30).context("no valve AA")?;
+        Ok(pressure.into())
+    }
+
+    fn part2(tunnels: &Self::Input) -> Result<Answer> {
+        let pressure = tunnels
+            .max_pressure_with_elephant(26)
+            .context("no valve AA")?;
+        Ok(pressure.into())
+    }
+}
+
+/// Part 1 with the exhaustive search
+fn part1_exhaustive(tunnels: &Tunnels) -> Result<Answer> {
+    let start = tunnels.start_state(30).context("no valve AA")?;
+    let (best, _) = start.find_best_moves(tunnels);
+    Ok(best.pressure.into())
+}
+
+/// Part 2 with the recursive search recording the best sets of opened valves
+fn part2_recursive(tunnels: &Tunnels) -> Result<Answer> {
+    let start = tunnels.start_state(26).context("no valve AA")?;
+    let mut best = Best::default();
+    start.find_best_moves2(tunnels, &mut best);
+    Ok(best_disjoint_pair(&best).into())
+}
+
+impl Differential for Day16 {
+    /// The exhaustive searches take seconds from about 40 valves
+    const DIFF_SIZE: usize = 30;
+
+    fn variants() -> Vec<Variant<Self::Input>> {
+        vec![
+            Variant {
+                name: "find_best_moves",
+                part: 1,
+                run: part1_exhaustive,
+            },
+            Variant {
+                name: "find_best_moves2",
+                part: 2,
+                run: part2_recursive,
+            },
+        ]
+    }
+}
+
+impl Generate for Day16 {
+    /// Valves, between 2 and 676 as names are two letters
+    const SIZE: usize = 58;
+
+    /// A random tree of tunnels with a few more to make loops. A quarter of the
+    /// valves have a flow rate, up to fifteen like the real inputs
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        let size = size.clamp(2, 26 * 26);
+        let mut names: Vec<String> = (b'A'..=b'Z')
+            .flat_map(|a| (b'A'..=b'Z').map(move |b| String::from_utf8(vec![a, b]).unwrap()))
+            .filter(|n| n != "AA")
+            .collect();
+        rng.shuffle(&mut names);
+        names.insert(0, "AA".to_string());
+        names.truncate(size);
+
+        let mut tunnels = vec![vec![]; size];
+        let connect = |a: usize, b: usize, tunnels: &mut Vec<Vec<usize>>| {
+            if a != b && !tunnels[a].contains(&b) {
+                tunnels[a].push(b);
+                tunnels[b].push(a);
+            }
+        };
+        for i in 1..size {
+            connect(i, rng.usize(0..=i - 1), &mut tunnels);
+        }
+        for _ in 0..size / 3 {
+            connect(
+                rng.usize(0..=size - 1),
+                rng.usize(0..=size - 1),
+                &mut tunnels,
+            );
+        }
+
+        let mut flows = vec![0; size];
+        let mut with_flow: Vec<usize> = (1..size).collect();
+        rng.shuffle(&mut with_flow);
+        for &i in with_flow.iter().take((size / 4).min(15)) {
+            flows[i] = rng.usize(3..=25);
+        }
+
+        let mut lines: Vec<String> = (0..size)
+            .map(|i| {
+                let neighbours: Vec<&str> = tunnels[i].iter().map(|&n| names[n].as_str()).collect();
+                let tunnels = match neighbours.len() {
+                    1 => "tunnel leads to valve",
+                    _ => "tunnels lead to valves",
+                };
+                format!(
+                    "Valve {} has flow rate={}; {} {}\n",
+                    names[i],
+                    flows[i],
+                    tunnels,
+                    neighbours.join(", ")
+                )
+            })
+            .collect();
+        rng.shuffle(&mut lines);
+        lines.concat()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../example");
+
+    #[test]
+    fn path_costs() {
+        let tunnels = Day16::parse(EXAMPLE).unwrap();
+        assert_eq!(tunnels.shortest_path_cost("AA", "DD"), Some(1));
+        assert_eq!(tunnels.shortest_path_cost("AA", "HH"), Some(5));
+        assert_eq!(tunnels.shortest_path_cost("JJ", "HH"), Some(7));
+    }
+
+    #[test]
+    fn opening_order() {
+        let tunnels = Day16::parse(EXAMPLE).unwrap();
+        let start = State::new(0, 30, 0, tunnels.valves["AA"].clone(), BTreeSet::new());
+        let (best, moves) = start.find_best_moves(&tunnels);
+        let order: Vec<String> = moves
+            .iter()
+            .rev()
+            .map(|m| m.pos.borrow().name.clone())
+            .collect();
+        assert_eq!(order, vec!["DD", "BB", "JJ", "HH", "EE", "CC"]);
+        assert_eq!(best.pressure, 1651);
+    }
+
+    #[test]
+    fn pruned_search() {
+        let tunnels = Day16::parse(EXAMPLE).unwrap();
+        let start = tunnels.start_state(30).unwrap();
+        let best = optimize::branch_and_bound(&tunnels, start.clone());
+        assert_eq!(best.score, 1651);
+        let all = optimize::explore(&tunnels, start, |_| ());
+        assert!(best.stats.explored < all.explored / 5, "{:?}", best.stats);
+        // Opening the same valves in another order often ends on the same valve
+        // at the same minute
+        assert!(all.memoised > all.explored / 2, "{:?}", all);
+        assert!(best.stats.memoised > 0, "{:?}", best.stats);
+    }
+
+    #[test]
+    fn part1() {
+        let tunnels = Day16::parse(EXAMPLE).unwrap();
+        assert_eq!(Day16::part1(&tunnels).unwrap().to_string(), "1651");
+    }
+
+    #[test]
+    fn part2() {
+        let tunnels = Day16::parse(EXAMPLE).unwrap();
+        assert_eq!(Day16::part2(&tunnels).unwrap().to_string(), "1707");
+    }
+
+    #[test]
+    fn differential() {
+        common::diff::check::<Day16>(0..10, Some(20)).unwrap_or_else(|m| panic!("{}", m));
+    }
+}
@@ -0,0 +1,471 @@
+//! Day 17: Pyroclastic Flow.
+//!
+//! Also usable to stack rocks in the chamber with any jet pattern:
+//!
+//! ```
+//! use day17::{JetMove, RockQueue};
+//!
+//! let jets: Vec<JetMove> = ">>><<><>><<<>><>>><<<>>><<<><<<>><>><<>>"
+//!     .chars()
+//!     .map(|c| if c == '<' { JetMove::Left } else { JetMove::Right })
+//!     .collect();
+//! let mut queue = RockQueue::new();
+//! for _ in 0..10 {
+//!     queue.turn(&jets);
+//! }
+//! assert_eq!(queue.highest_y, 17);
+//! ```
+
+use std::collections::HashSet;
+
+use anyhow::Result;
+use common::animate::{Animate, Colour, Frame, Pixel, Simulation};
+use common::cycle::{self, Cycle};
+use common::gen::{Generate, Rng};
+use common::parse::ParseError;
+use common::point::Point;
+use common::{Answer, Solution};
+
+/// Falling rock, `y` growing upwards from the chamber floor
+#[derive(Clone, Debug, PartialEq)]
+pub struct Rock {
+    pub coords: Vec<Point<usize>>,
+}
+
+/// Shapes of the rocks, falling in the order of [`RockShape::next`]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum RockShape {
+    Plus,
+    Minus,
+    Stair,
+    Line,
+    Square,
+}
+
+/// Chamber seven units wide with the rocks at rest, the next shape to fall
+/// and the next jet to push it
+#[derive(Clone, Debug)]
+pub struct RockQueue {
+    pub current_shape: RockShape,
+    pub resting_rocks: HashSet<Point<usize>>,
+    pub highest_y: usize,
+    pub move_idx: usize,
+}
+
+/// Push of a jet of hot gas, `<` or `>`
+#[derive(Clone, Debug)]
+pub enum JetMove {
+    Left,
+    Right,
+}
+
+impl Rock {
+    pub fn new(shape: &RockShape, y: usize) -> Self {
+        let coords = match shape {
+            RockShape::Minus => vec![
+                Point { x: 2, y },
+                Point { x: 3, y },
+                Point { x: 4, y },
+                Point { x: 5, y },
+            ],
+            RockShape::Plus => vec![
+                Point { x: 3, y },
+                Point { x: 2, y: y + 1 },
+                Point { x: 3, y: y + 1 },
+                Point { x: 4, y: y + 1 },
+                Point { x: 3, y: y + 2 },
+            ],
+            RockShape::Stair => vec![
+                Point { x: 2, y },
+                Point { x: 3, y },
+                Point { x: 4, y },
+                Point { x: 4, y: y + 1 },
+                Point { x: 4, y: y + 2 },
+            ],
+            RockShape::Line => vec![
+                Point { x: 2, y },
+                Point { x: 2, y: y + 1 },
+                Point { x: 2, y: y + 2 },
+                Point { x: 2, y: y + 3 },
+            ],
+            RockShape::Square => vec![
+                Point { x: 2, y },
+                Point { x: 3, y },
+                Point { x: 2, y: y + 1 },
+                Point { x: 3, y: y + 1 },
+            ],
+        };
+        Rock { coords }
+    }
+
+    pub fn move_vertical(&mut self) {
+        for c in self.coords.iter_mut() {
+            c.y -= 1;
+        }
+    }
+
+    pub fn jet_move(&mut self, jet_move: &JetMove) {
+        for c in self.coords.iter_mut() {
+            if let JetMove::Left = jet_move {
+                c.x -= 1;
+            } else {
+                c.x += 1;
+            }
+        }
+    }
+}
+
+/// Rows of the tower top compared when looking for a cycle
+const FINGERPRINT_ROWS: usize = 64;
+
+impl Default for RockQueue {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RockQueue {
+    pub fn new() -> Self {
+        RockQueue {
+            current_shape: RockShape::Minus,
+            highest_y: 0,
+            resting_rocks: HashSet::new(),
+            move_idx: 0,
+        }
+    }
+
+    pub fn spawn_rock(&mut self) -> Rock {
+        let rock = Rock::new(&self.current_shape, self.highest_y + 3);
+        self.current_shape = self.current_shape.next();
+        rock
+    }
+
+    /// Drop the next rock until it comes to rest
+    pub fn turn(&mut self, jet_moves: &[JetMove]) {
+        let mut rock = self.spawn_rock();
+
+        for jet_move in jet_moves.iter().cycle().skip(self.move_idx) {
+            self.move_idx = (self.move_idx + 1) % jet_moves.len();
+            if !self.horizontal_collision(&rock, jet_move) {
+                rock.jet_move(jet_move);
+            }
+
+            if self.vertical_collision(&rock) {
+                break;
+            }
+            rock.move_vertical();
+        }
+
+        self.update_state(rock);
+        common::event!(
+            DEBUG,
+            height = self.highest_y,
+            jet = self.move_idx,
+            "rock at rest"
+        );
+    }
+
+    /// Next shape, jet and the top rows of the tower, one bitmask per row.
+    /// Falling rocks do not get below the top rows in practice, so equal
+    /// fingerprints are assumed to lead to the same future
+    pub fn fingerprint(&self) -> (RockShape, usize, Vec<u8>) {
+        let rows = (0..FINGERPRINT_ROWS.min(self.highest_y))
+            .map(|dy| {
+                let y = self.highest_y - 1 - dy;
+                (0..7)
+                    .filter(|&x| self.resting_rocks.contains(&Point { x, y }))
+                    .fold(0, |row, x| row | 1 << x)
+            })
+            .collect();
+        (self.current_shape.clone(), self.move_idx, rows)
+    }
+
+    pub fn vertical_collision(&self, rock: &Rock) -> bool {
+        for c in rock.coords.iter() {
+            if c.y.checked_sub(1).is_none() {
+                return true;
+            }
+        }
+        for c in rock.coords.iter() {
+            let new_c = Point { x: c.x, y: c.y - 1 };
+            if self.resting_rocks.contains(&new_c) {
+                return true;
+            }
+        }
+        false
+    }
+
+    pub fn horizontal_collision(&self, rock: &Rock, jet_move: &JetMove) -> bool {
+        for c in rock.coords.iter() {
+            match jet_move {
+                JetMove::Left => {
+                    if c.x.checked_sub(1).is_none() {
+                        return true;
+                    }
+                    let new_c = Point { x: c.x - 1, y: c.y };
+                    if self.resting_rocks.contains(&new_c) {
+                        return true;
+                    }
+                }
+                JetMove::Right => {
+                    if c.x == 6 {
+                        return true;
+                    }
+                    let new_c = Point { x: c.x + 1, y: c.y };
+                    if self.resting_rocks.contains(&new_c) {
+                        return true;
+                    }
+                }
+            }
+        }
+        false
+    }
+
+    pub fn update_state(&mut self, rock: Rock) {
+        let mut best_y = self.highest_y;
+        for c in rock.coords {
+            if c.y >= best_y {
+                best_y = c.y + 1;
+            }
+            self.resting_rocks.insert(c);
+        }
+        if best_y != self.highest_y {
+            self.highest_y = best_y;
+        }
+    }
+}
+
+impl RockShape {
+    pub fn next(&self) -> Self {
+        match self {
+            RockShape::Minus => RockShape::Plus,
+            RockShape::Plus => RockShape::Stair,
+            RockShape::Stair => RockShape::Line,
+            RockShape::Line => RockShape::Square,
+            RockShape::Square => RockShape::Minus,
+        }
+    }
+}
+
+impl std::fmt::Display for RockQueue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut grid = String::new();
+        for _ in 0..self.highest_y + 8 {
+            grid += ".......\n";
+        }
+        for c in self.resting_rocks.iter() {
+            let i = c.x + c.y * 8;
+            grid.replace_range(i..i + 1, "#");
+        }
+        f.write_fmt(format_args!("{}", grid))
+    }
+}
+
+/// Rocks falling one at a time. Part 1 stops after 2022 rocks, part 2 once the
+/// tower starts repeating itself
+#[derive(Clone)]
+pub struct RockSimulation {
+    queue: RockQueue,
+    jet_moves: Vec<JetMove>,
+    rocks: usize,
+    max_rocks: usize,
+    /// Cycle of part 2, detected beforehand and shown once reached
+    cycle: Option<Cycle>,
+}
+
+impl RockSimulation {
+    fn cycle_found(&self) -> Option<Cycle> {
+        self.cycle.filter(|_| self.rocks == self.max_rocks)
+    }
+}
+
+impl Simulation for RockSimulation {
+    fn step(&mut self) -> bool {
+        if self.rocks == self.max_rocks {
+            return false;
+        }
+        self.queue.turn(&self.jet_moves);
+        self.rocks += 1;
+        true
+    }
+
+    /// The tower grows upwards, so rows are drawn at `-y`
+    fn frame(&self) -> Frame {
+        let top = self.queue.highest_y as isize + 3;
+        let mut frame = Frame::new(Point { x: -1, y: -top }, 9, top as usize + 2);
+        for y in -top..=0 {
+            frame.set(Point { x: -1, y }, Pixel::new('|', Colour::White));
+            frame.set(Point { x: 7, y }, Pixel::new('|', Colour::White));
+        }
+        for x in -1..=7 {
+            frame.set(Point { x, y: 1 }, Pixel::new('-', Colour::White));
+        }
+        for c in self.queue.resting_rocks.iter() {
+            let p = Point {
+                x: c.x as isize,
+                y: -(c.y as isize),
+            };
+            frame.set(p, Pixel::new('#', Colour::Yellow));
+        }
+        let status = match self.cycle_found() {
+            Some(Cycle { mu, lambda }) => format!(
+                "rock {}, height {}, cycle of {} rocks from rock {}",
+                self.rocks, self.queue.highest_y, lambda, mu
+            ),
+            None => format!("rock {}, height {}", self.rocks, self.queue.highest_y),
+        };
+        frame
+            .with_focus(Point { x: 3, y: -top })
+            .with_status(status)
+    }
+
+    fn inspect(&self) -> Vec<(&'static str, String)> {
+        let mut values = vec![
+            ("rocks", self.rocks.to_string()),
+            ("height", self.queue.highest_y.to_string()),
+            ("next shape", format!("{:?}", self.queue.current_shape)),
+            (
+                "next jet",
+                format!("{}/{}", self.queue.move_idx, self.jet_moves.len()),
+            ),
+        ];
+        if let Some(Cycle { mu, lambda }) = self.cycle_found() {
+            values.push(("cycle start", mu.to_string()));
+            values.push(("cycle length", lambda.to_string()));
+        }
+        values
+    }
+
+    fn snapshot(&self) -> Option<Box<dyn Simulation>> {
+        Some(Box::new(self.clone()))
+    }
+}
+
+pub struct Day17;
+
+impl Solution for Day17 {
+    const DAY: u8 = 17;
+
+    type Input = Vec<JetMove>;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        let jet_moves = input
+            .trim_end()
+            .chars()
+            .enumerate()
+            .map(|(i, c)| match c {
+                '>' => Ok(JetMove::Right),
+                '<' => Ok(JetMove::Left),
+                _ => Err(ParseError::new("invalid jet", &c.to_string()).at(1, i + 1)),
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(jet_moves)
+    }
+
+    fn part1(jet_moves: &Self::Input) -> Result<Answer> {
+        let mut queue = RockQueue::new();
+
+        let n_rocks = 2022;
+        for _ in 0..n_rocks {
+            queue.turn(jet_moves);
+        }
+
+        Ok(queue.highest_y.into())
+    }
+
+    fn part2(jet_moves: &Self::Input) -> Result<Answer> {
+        let n_rocks = 1_000_000_000_000;
+        let (cycle, heights) = cycle::detect_with_history(
+            RockQueue::new(),
+            |queue| queue.turn(jet_moves),
+            RockQueue::fingerprint,
+            |queue| queue.highest_y,
+        );
+        Ok(cycle.extrapolate(n_rocks, &heights).into())
+    }
+}
+
+impl Animate for Day17 {
+    fn simulation(jet_moves: &Self::Input, part: u8) -> Result<Box<dyn Simulation>> {
+        let cycle = (part == 2).then(|| {
+            cycle::detect(
+                RockQueue::new(),
+                |queue| queue.turn(jet_moves),
+                RockQueue::fingerprint,
+            )
+        });
+        Ok(Box::new(RockSimulation {
+            queue: RockQueue::new(),
+            jet_moves: jet_moves.clone(),
+            rocks: 0,
+            max_rocks: cycle.map_or(2022, |c| c.mu + c.lambda),
+            cycle,
+        }))
+    }
+}
+
+impl Generate for Day17 {
+    /// Jets in the pattern
+    const SIZE: usize = 10091;
+
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        let jets: String = (0..size.max(1)).map(|_| *rng.pick(&['<', '>'])).collect();
+        jets + "\n"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../example");
+
+    #[test]
+    fn height_after_rocks() {
+        let jet_moves = Day17::parse(EXAMPLE).unwrap();
+        let mut queue = RockQueue::new();
+        let mut heights = vec![];
+        for _ in 0..10 {
+            queue.turn(&jet_moves);
+            heights.push(queue.highest_y);
+        }
+        assert_eq!(heights, vec![1, 4, 6, 7, 9, 10, 13, 15, 17, 17]);
+    }
+
+    #[test]
+    fn simulation_stops_at_cycle() {
+        let jet_moves = Day17::parse(EXAMPLE).unwrap();
+        let mut sim = Day17::simulation(&jet_moves, 2).unwrap();
+        let cycle = cycle::detect(
+            RockQueue::new(),
+            |queue| queue.turn(&jet_moves),
+            RockQueue::fingerprint,
+        );
+        let mut rocks = 0;
+        while sim.step() {
+            rocks += 1;
+            if rocks < cycle.mu + cycle.lambda {
+                assert_eq!(sim.inspect().len(), 4);
+            }
+        }
+        assert_eq!(rocks, cycle.mu + cycle.lambda);
+        let values = sim.inspect();
+        assert!(values.contains(&("cycle start", cycle.mu.to_string())));
+        assert!(values.contains(&("cycle length", cycle.lambda.to_string())));
+    }
+
+    #[test]
+    fn part1() {
+        let jet_moves = Day17::parse(EXAMPLE).unwrap();
+        assert_eq!(Day17::part1(&jet_moves).unwrap().to_string(), "3068");
+    }
+
+    #[test]
+    fn part2() {
+        let jet_moves = Day17::parse(EXAMPLE).unwrap();
+        assert_eq!(
+            Day17::part2(&jet_moves).unwrap().to_string(),
+            "1514285714288"
+        );
+    }
+}
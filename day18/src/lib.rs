@@ -0,0 +1,189 @@
+//! Day 18: Boiling Boulders.
+//!
+//! Also usable to measure the surface of any droplet of lava:
+//!
+//! ```
+//! use std::collections::HashSet;
+//!
+//! // A hollow 3x3x3 cube traps a single pocket of air
+//! let mut cubes = HashSet::new();
+//! for x in 0..3 {
+//!     for y in 0..3 {
+//!         for z in 0..3 {
+//!             cubes.insert(day18::Cube { x, y, z });
+//!         }
+//!     }
+//! }
+//! cubes.remove(&day18::parse_cube("1,1,1").unwrap());
+//! assert_eq!(day18::surface_area(&cubes), 54 + 6);
+//! assert_eq!(day18::exterior_surface_area(&cubes), 54);
+//! ```
+
+use std::collections::HashSet;
+
+use anyhow::Result;
+use common::gen::{Generate, Rng};
+use common::parse::{self, ParseContext, ParseError};
+use common::point::Point3;
+use common::{Answer, Solution};
+
+/// Unit cube of lava, by the coordinates of its corner
+pub type Cube = Point3<isize>;
+
+/// Cube from its `x,y,z` coordinates
+pub fn parse_cube(s: &str) -> Result<Cube, ParseError> {
+    let mut splits = s.split(",");
+    let x = parse::value(splits.next().parse_context("no x", s)?, "invalid x")?;
+    let y = parse::value(splits.next().parse_context("no y", s)?, "invalid y")?;
+    let z = parse::value(splits.next().parse_context("no z", s)?, "invalid z")?;
+    if let Some(extra) = splits.next() {
+        return Err(ParseError::new("too many coordinates", extra));
+    }
+    Ok(Cube { x, y, z })
+}
+
+/// Sides of the cubes not touching another cube
+pub fn surface_area(cubes: &HashSet<Cube>) -> usize {
+    let mut total_free_sides: usize = 0;
+    for cube in cubes.iter() {
+        let mut free_sides = 6;
+        for c in cube.neighbours6() {
+            if cubes.contains(&c) {
+                free_sides -= 1;
+            }
+        }
+        total_free_sides += free_sides;
+    }
+
+    total_free_sides
+}
+
+/// Sides of the cubes reachable from outside, pockets of air trapped inside
+/// not counted
+pub fn exterior_surface_area(cubes: &HashSet<Cube>) -> usize {
+    let mut max_x = 0;
+    let mut max_y = 0;
+    let mut max_z = 0;
+    let mut min_x = 0;
+    let mut min_y = 0;
+    let mut min_z = 0;
+    for cube in cubes.iter() {
+        max_x = cube.x.max(max_x);
+        max_y = cube.y.max(max_y);
+        max_z = cube.z.max(max_z);
+        min_x = cube.x.min(min_x);
+        min_y = cube.y.min(min_y);
+        min_z = cube.z.min(min_z);
+    }
+
+    let mut bounding_cube = HashSet::new();
+    for x in min_x - 1..=max_x + 1 {
+        for y in min_y - 1..=max_y + 1 {
+            for z in min_z - 1..=max_z + 1 {
+                let c = Cube { x, y, z };
+                bounding_cube.insert(c);
+            }
+        }
+    }
+
+    let mut queue = vec![Cube {
+        x: min_x - 1,
+        y: min_y - 1,
+        z: min_z - 1,
+    }];
+    while let Some(cube) = queue.pop() {
+        for c in cube.neighbours6() {
+            if c == cube {
+                continue;
+            }
+            if bounding_cube.contains(&c) && !cubes.contains(&c) {
+                bounding_cube.remove(&c);
+                queue.push(c);
+            }
+        }
+    }
+
+    let mut total_free_sides: usize = 0;
+    for cube in bounding_cube.iter() {
+        let mut free_sides = 6;
+        for c in cube.neighbours6() {
+            if bounding_cube.contains(&c) {
+                free_sides -= 1;
+            }
+        }
+        total_free_sides += free_sides;
+    }
+
+    total_free_sides
+}
+
+pub struct Day18;
+
+impl Solution for Day18 {
+    const DAY: u8 = 18;
+
+    type Input = HashSet<Cube>;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        let cubes = parse::lines(input, parse_cube)?;
+        Ok(cubes.into_iter().collect())
+    }
+
+    fn part1(cubes: &Self::Input) -> Result<Answer> {
+        Ok(surface_area(cubes).into())
+    }
+
+    fn part2(cubes: &Self::Input) -> Result<Answer> {
+        Ok(exterior_surface_area(cubes).into())
+    }
+}
+
+impl Generate for Day18 {
+    /// Cubes, at most the 4169 of the ball they are drawn from
+    const SIZE: usize = 2800;
+
+    /// A ball of radius 10 with random holes, some of them trapped inside
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        let mut ball: Vec<Cube> = vec![];
+        for x in 0..=20 {
+            for y in 0..=20 {
+                for z in 0..=20 {
+                    let c = Cube { x, y, z };
+                    if (x - 10).pow(2) + (y - 10).pow(2) + (z - 10).pow(2) <= 100 {
+                        ball.push(c);
+                    }
+                }
+            }
+        }
+        rng.shuffle(&mut ball);
+        ball.truncate(size);
+        ball.iter()
+            .map(|c| format!("{},{},{}\n", c.x, c.y, c.z))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../example");
+
+    #[test]
+    fn two_cubes() {
+        let cubes = Day18::parse("1,1,1\n2,1,1\n").unwrap();
+        assert_eq!(Day18::part1(&cubes).unwrap().to_string(), "10");
+    }
+
+    #[test]
+    fn part1() {
+        let cubes = Day18::parse(EXAMPLE).unwrap();
+        assert_eq!(Day18::part1(&cubes).unwrap().to_string(), "64");
+    }
+
+    #[test]
+    fn part2() {
+        let cubes = Day18::parse(EXAMPLE).unwrap();
+        assert_eq!(Day18::part2(&cubes).unwrap().to_string(), "58");
+    }
+}
@@ -0,0 +1,321 @@
+//! Day 19: Not Enough Minerals.
+//!
+//! Also usable to find the best geode count of any blueprint:
+//!
+//! ```
+//! let blueprint: day19::Blueprint = "Blueprint 1: \
+//!     Each ore robot costs 4 ore. \
+//!     Each clay robot costs 2 ore. \
+//!     Each obsidian robot costs 3 ore and 14 clay. \
+//!     Each geode robot costs 2 ore and 7 obsidian."
+//!     .parse()
+//!     .unwrap();
+//! assert_eq!(blueprint.robots.len(), 4);
+//! assert_eq!(day19::best_geodes(&[blueprint], 24), vec![9]);
+//! ```
+
+use std::str::FromStr;
+
+use anyhow::Result;
+use common::gen::{Generate, Rng};
+use common::optimize::{self, Problem};
+use common::parse::{self, ParseContext, ParseError};
+use common::{Answer, Solution};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// Costs of the four robots, in the order of [`RobotType`]
+#[derive(Debug, Clone)]
+pub struct Blueprint {
+    pub id: usize,
+    pub robots: Vec<Money>,
+}
+
+#[derive(Debug, Clone)]
+pub enum RobotType {
+    Ore,
+    Clay,
+    Obsidian,
+    Geode,
+}
+
+/// Amount of each mineral. In a blueprint, the cost of a robot of type `typ`
+#[derive(Debug, Clone)]
+pub struct Money {
+    pub typ: RobotType,
+    pub ore: usize,
+    pub clay: usize,
+    pub obsidian: usize,
+    pub geode: usize,
+}
+
+#[derive(Debug, Clone)]
+struct State {
+    pub blueprint: Blueprint,
+    pub money: Money,
+    pub turn: usize,
+    pub ore_robots: usize,
+    pub clay_robots: usize,
+    pub obsidian_robots: usize,
+    pub geode_robots: usize,
+}
+
+#[derive(Debug, Clone)]
+enum Move {
+    Wait,
+    Pay(Money),
+}
+
+impl FromStr for Blueprint {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let re = regex::Regex::new(r"Blueprint (\d+): Each ore robot costs (\d+) ore. Each clay robot costs (\d+) ore. Each obsidian robot costs (\d+) ore and (\d+) clay. Each geode robot costs (\d+) ore and (\d+) obsidian").unwrap();
+        let caps = re.captures(s).parse_context("invalid blueprint", s)?;
+        let id = parse::value(&caps[1], "invalid number")?;
+        let ore = Money {
+            typ: RobotType::Ore,
+            ore: parse::value(&caps[2], "invalid number")?,
+            clay: 0,
+            obsidian: 0,
+            geode: 0,
+        };
+        let clay = Money {
+            typ: RobotType::Clay,
+            ore: parse::value(&caps[3], "invalid number")?,
+            clay: 0,
+            obsidian: 0,
+            geode: 0,
+        };
+        let obsidian = Money {
+            typ: RobotType::Obsidian,
+            ore: parse::value(&caps[4], "invalid number")?,
+            clay: parse::value(&caps[5], "invalid number")?,
+            obsidian: 0,
+            geode: 0,
+        };
+        let geode = Money {
+            typ: RobotType::Geode,
+            ore: parse::value(&caps[6], "invalid number")?,
+            clay: 0,
+            obsidian: parse::value(&caps[7], "invalid number")?,
+            geode: 0,
+        };
+        Ok(Blueprint {
+            id,
+            robots: vec![ore, clay, obsidian, geode],
+        })
+    }
+}
+
+impl Money {
+    pub fn can_afford(&self, other: &Self) -> bool {
+        self.ore >= other.ore
+            && self.clay >= other.clay
+            && self.obsidian >= other.obsidian
+            && self.geode >= other.geode
+    }
+
+    pub fn pay(&mut self, other: &Self) {
+        self.ore -= other.ore;
+        self.clay -= other.clay;
+        self.obsidian -= other.obsidian;
+        self.geode -= other.geode;
+    }
+}
+
+impl State {
+    /// Turn 0 with a single ore robot
+    pub fn new(blueprint: Blueprint) -> Self {
+        State {
+            blueprint,
+            money: Money {
+                typ: RobotType::Geode,
+                ore: 0,
+                clay: 0,
+                obsidian: 0,
+                geode: 0,
+            },
+            turn: 0,
+            ore_robots: 1,
+            clay_robots: 0,
+            obsidian_robots: 0,
+            geode_robots: 0,
+        }
+    }
+
+    pub fn apply(&self, mv: &Move) -> Self {
+        let mut new_state = self.clone();
+        if let Move::Pay(robot) = mv {
+            new_state.money.pay(robot);
+            new_state.mine();
+            match robot.typ {
+                RobotType::Ore => new_state.ore_robots += 1,
+                RobotType::Clay => new_state.clay_robots += 1,
+                RobotType::Obsidian => new_state.obsidian_robots += 1,
+                RobotType::Geode => new_state.geode_robots += 1,
+            };
+        } else {
+            new_state.mine();
+        }
+        new_state.turn += 1;
+
+        new_state
+    }
+
+    pub fn mine(&mut self) {
+        self.money.ore += self.ore_robots;
+        self.money.clay += self.clay_robots;
+        self.money.obsidian += self.obsidian_robots;
+        self.money.geode += self.geode_robots;
+    }
+
+    pub fn moves(&self) -> Vec<Move> {
+        let mut moves = vec![];
+        for robot in self.blueprint.robots.iter() {
+            if self.money.can_afford(robot) {
+                moves.push(Move::Pay(robot.clone()));
+            }
+        }
+        moves.push(Move::Wait);
+        moves
+    }
+}
+
+/// States kept after each turn, a narrower beam misses the best geode count
+/// of the first example blueprint
+const BEAM_WIDTH: usize = 5000;
+
+/// Best number of geodes of each blueprint, in order. The blueprints are
+/// searched concurrently with the `parallel` feature
+pub fn best_geodes(blueprints: &[Blueprint], turns: usize) -> Vec<usize> {
+    #[cfg(feature = "parallel")]
+    let blueprints = blueprints.par_iter();
+    #[cfg(not(feature = "parallel"))]
+    let blueprints = blueprints.iter();
+    blueprints
+        .map(|blueprint| get_best_geode(State::new(blueprint.clone()), turns))
+        .collect()
+}
+
+/// Robots to build until the last turn
+struct Factory {
+    turns: usize,
+}
+
+impl Problem for Factory {
+    type Node = State;
+    type Score = usize;
+    type Key = ();
+
+    fn successors(&self, state: &State) -> Vec<State> {
+        if state.turn == self.turns {
+            return vec![];
+        }
+        state.moves().iter().map(|mv| state.apply(mv)).collect()
+    }
+
+    fn score(&self, state: &State) -> usize {
+        state.money.geode
+    }
+}
+
+/// Get the best geode for a given starting state and number of turns.
+/// Not my cleanest solution, we only keep the most promising states of each
+/// turn to avoid having to go through low score states
+fn get_best_geode(starting_state: State, turns: usize) -> usize {
+    common::span!(INFO, "blueprint", id = starting_state.blueprint.id);
+    let factory = Factory { turns };
+    let best = optimize::beam(&factory, starting_state, BEAM_WIDTH, |s| {
+        (
+            s.money.geode + s.geode_robots,
+            s.money.obsidian + s.obsidian_robots,
+            s.money.clay + s.clay_robots,
+            s.money.ore + s.ore_robots,
+            s.geode_robots,
+            s.obsidian_robots,
+            s.clay_robots,
+            s.ore_robots,
+        )
+    });
+    best.score
+}
+
+pub struct Day19;
+
+impl Solution for Day19 {
+    const DAY: u8 = 19;
+
+    type Input = Vec<Blueprint>;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(parse::lines(input, str::parse)?)
+    }
+
+    fn part1(blueprints: &Self::Input) -> Result<Answer> {
+        let quality: usize = blueprints
+            .iter()
+            .zip(best_geodes(blueprints, 24))
+            .map(|(blueprint, best_geode)| blueprint.id * best_geode)
+            .sum();
+        Ok(quality.into())
+    }
+
+    fn part2(blueprints: &Self::Input) -> Result<Answer> {
+        let res: usize = best_geodes(&blueprints[..blueprints.len().min(3)], 32)
+            .into_iter()
+            .product();
+        Ok(res.into())
+    }
+}
+
+impl Generate for Day19 {
+    /// Blueprints
+    const SIZE: usize = 30;
+
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        (1..=size)
+            .map(|id| {
+                format!(
+                    "Blueprint {}: Each ore robot costs {} ore. Each clay robot costs {} ore. Each obsidian robot costs {} ore and {} clay. Each geode robot costs {} ore and {} obsidian.\n",
+                    id,
+                    rng.usize(2..=4),
+                    rng.usize(2..=4),
+                    rng.usize(2..=4),
+                    rng.usize(5..=20),
+                    rng.usize(2..=4),
+                    rng.usize(5..=20)
+                )
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../example");
+
+    fn example_best_geodes(turns: usize) -> Vec<usize> {
+        best_geodes(&Day19::parse(EXAMPLE).unwrap(), turns)
+    }
+
+    #[test]
+    fn geodes_per_blueprint() {
+        assert_eq!(example_best_geodes(24), vec![9, 12]);
+    }
+
+    #[test]
+    fn part1() {
+        let blueprints = Day19::parse(EXAMPLE).unwrap();
+        assert_eq!(Day19::part1(&blueprints).unwrap().to_string(), "33");
+    }
+
+    #[test]
+    fn part2() {
+        assert_eq!(example_best_geodes(32), vec![56, 62]);
+        let blueprints = Day19::parse(EXAMPLE).unwrap();
+        assert_eq!(Day19::part2(&blueprints).unwrap().to_string(), "3472");
+    }
+}
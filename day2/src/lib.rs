@@ -0,0 +1,191 @@
+//! Day 2: Rock Paper Scissors.
+//!
+//! Also usable to score rounds of the elves' strategy guide:
+//!
+//! ```
+//! use day2::{duel_score1, Shape};
+//!
+//! let (opponent, response): (Shape, Shape) = ("A".parse().unwrap(), "Y".parse().unwrap());
+//! assert_eq!(Shape::score(&response) + duel_score1(&opponent, &response), 8);
+//! ```
+
+use std::str::FromStr;
+
+use anyhow::Result;
+use common::gen::{Generate, Rng};
+use common::parse::{self, ParseContext, ParseError};
+use common::{Answer, Solution};
+
+/// A shape, `A`/`X` for rock, `B`/`Y` for paper and `C`/`Z` for scissors
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shape {
+    Rock,
+    Paper,
+    Scissor,
+}
+
+/// How a round must end, `X` to lose, `Y` to draw and `Z` to win
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Lose,
+    Draw,
+    Win,
+}
+
+impl FromStr for Shape {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let shape = match s {
+            "A" | "X" => Shape::Rock,
+            "B" | "Y" => Shape::Paper,
+            "C" | "Z" => Shape::Scissor,
+            _ => return Err(ParseError::new("invalid shape", s)),
+        };
+        Ok(shape)
+    }
+}
+
+impl FromStr for Outcome {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let outcome = match s {
+            "X" => Outcome::Lose,
+            "Y" => Outcome::Draw,
+            "Z" => Outcome::Win,
+            _ => return Err(ParseError::new("invalid outcome", s)),
+        };
+        Ok(outcome)
+    }
+}
+
+impl Shape {
+    /// Score of playing the shape
+    pub fn score(shape: &Shape) -> usize {
+        match shape {
+            Shape::Rock => 1,
+            Shape::Paper => 2,
+            Shape::Scissor => 3,
+        }
+    }
+}
+
+impl Outcome {
+    /// Score of the shape to play against `play1` for the round to end as `play2`
+    pub fn score(play1: &Shape, play2: &Outcome) -> usize {
+        match (play1, play2) {
+            (Shape::Rock, Outcome::Lose) => Shape::score(&Shape::Scissor),
+            (Shape::Rock, Outcome::Win) => Shape::score(&Shape::Paper),
+            (Shape::Scissor, Outcome::Lose) => Shape::score(&Shape::Paper),
+            (Shape::Scissor, Outcome::Win) => Shape::score(&Shape::Rock),
+            (Shape::Paper, Outcome::Lose) => Shape::score(&Shape::Rock),
+            (Shape::Paper, Outcome::Win) => Shape::score(&Shape::Scissor),
+            (_, Outcome::Draw) => Shape::score(play1),
+        }
+    }
+}
+
+/// Score of the outcome of `play2` against `play1`
+pub fn duel_score1(play1: &Shape, play2: &Shape) -> usize {
+    match (play1, play2) {
+        (Shape::Rock, Shape::Scissor) => 0,
+        (Shape::Rock, Shape::Paper) => 6,
+        (Shape::Scissor, Shape::Rock) => 6,
+        (Shape::Scissor, Shape::Paper) => 0,
+        (Shape::Paper, Shape::Scissor) => 6,
+        (Shape::Paper, Shape::Rock) => 0,
+        _ => 3,
+    }
+}
+
+/// Score of the outcome of a round
+pub fn duel_score2(play: &Outcome) -> usize {
+    match play {
+        Outcome::Lose => 0,
+        Outcome::Draw => 3,
+        Outcome::Win => 6,
+    }
+}
+
+/// A line of the strategy guide, its second column read both ways
+pub struct Duel {
+    /// Shape of the opponent
+    pub play1: Shape,
+    /// Second column as the shape to play, for part 1
+    pub play2: Shape,
+    /// Second column as the outcome to reach, for part 2
+    pub outcome: Outcome,
+}
+
+pub struct Day2;
+
+impl Solution for Day2 {
+    const DAY: u8 = 2;
+
+    type Input = Vec<Duel>;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        let duels = parse::lines(input, |line| {
+            let (a, b) = line.split_once(' ').parse_context("invalid duel", line)?;
+            Ok(Duel {
+                play1: a.parse()?,
+                play2: b.parse()?,
+                outcome: b.parse()?,
+            })
+        })?;
+        Ok(duels)
+    }
+
+    fn part1(duels: &Self::Input) -> Result<Answer> {
+        let mut final_score = 0;
+        for duel in duels.iter() {
+            final_score += Shape::score(&duel.play2) + duel_score1(&duel.play1, &duel.play2);
+        }
+        Ok(final_score.into())
+    }
+
+    fn part2(duels: &Self::Input) -> Result<Answer> {
+        let mut final_score = 0;
+        for duel in duels.iter() {
+            final_score += Outcome::score(&duel.play1, &duel.outcome) + duel_score2(&duel.outcome);
+        }
+        Ok(final_score.into())
+    }
+}
+
+impl Generate for Day2 {
+    /// Rounds
+    const SIZE: usize = 2500;
+
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        (0..size)
+            .map(|_| {
+                format!(
+                    "{} {}\n",
+                    rng.pick(&['A', 'B', 'C']),
+                    rng.pick(&['X', 'Y', 'Z'])
+                )
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../example");
+
+    #[test]
+    fn part1() {
+        let rounds = Day2::parse(EXAMPLE).unwrap();
+        assert_eq!(Day2::part1(&rounds).unwrap().to_string(), "15");
+    }
+
+    #[test]
+    fn part2() {
+        let rounds = Day2::parse(EXAMPLE).unwrap();
+        assert_eq!(Day2::part2(&rounds).unwrap().to_string(), "12");
+    }
+}
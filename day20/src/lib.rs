@@ -0,0 +1,192 @@
+//! Day 20: Grove Positioning System.
+//!
+//! Also usable to mix any encrypted file:
+//!
+//! ```
+//! let mut list = day20::CircularList::new([1, 2, -3, 3, -2, 0, 4]);
+//! list.mix(1);
+//! assert_eq!(list.get_coords(), Some((4, -3, 2)));
+//! ```
+
+use std::collections::VecDeque;
+
+use anyhow::{Context, Result};
+use common::gen::{Generate, Rng};
+use common::parse::{self, ParseError};
+use common::{Answer, Solution};
+
+/// Numbers of an encrypted file, the last one followed by the first one
+#[derive(Debug)]
+pub struct CircularList {
+    elements: VecDeque<isize>,
+}
+
+impl CircularList {
+    pub fn new(numbers: impl IntoIterator<Item = isize>) -> Self {
+        CircularList {
+            elements: numbers.into_iter().collect(),
+        }
+    }
+
+    /// Numbers from the current start of the list
+    pub fn elements(&self) -> &VecDeque<isize> {
+        &self.elements
+    }
+
+    /// Move each number as many positions as its value, in the original
+    /// order, `iterations` times
+    pub fn mix(&mut self, iterations: usize) {
+        let mut indexes = VecDeque::from_iter(0..self.elements.len());
+
+        for _ in 0..iterations {
+            for i in 0..self.elements.len() {
+                let cur_idx = indexes.iter().position(|&e| e == i).unwrap();
+
+                indexes.rotate_left(cur_idx);
+                self.elements.rotate_left(cur_idx);
+                let elt = self.elements.pop_front().unwrap();
+                indexes.pop_front();
+
+                // The absolute value is important to prevent modifying non-overlapping
+                // negative numbers. A single number has nowhere to go
+                let rotations = elt.unsigned_abs() % self.elements.len().max(1);
+                if elt.is_positive() {
+                    self.elements.rotate_left(rotations);
+                    indexes.rotate_left(rotations);
+                } else {
+                    self.elements.rotate_right(rotations);
+                    indexes.rotate_right(rotations);
+                }
+                self.elements.push_front(elt);
+                indexes.push_front(i);
+            }
+        }
+    }
+
+    /// Index `offset` positions after `idx`, wrapping around
+    pub fn offset_idx(&self, idx: isize, offset: isize) -> usize {
+        (idx + offset).rem_euclid(self.elements.len().max(1) as isize) as usize
+    }
+
+    /// The 1000th, 2000th and 3000th numbers after 0
+    pub fn get_coords(&self) -> Option<(isize, isize, isize)> {
+        let zero_idx = self.elements.iter().position(|&e| e == 0)?;
+        let x = self.elements[self.offset_idx(zero_idx as isize, 1000)];
+        let y = self.elements[self.offset_idx(zero_idx as isize, 2000)];
+        let z = self.elements[self.offset_idx(zero_idx as isize, 3000)];
+        Some((x, y, z))
+    }
+}
+
+/// Sum of the grove coordinates
+fn grove_sum(list: &CircularList) -> Result<Answer> {
+    let (x, y, z) = list.get_coords().context("failed to get coords")?;
+    let sum = x.checked_add(y).and_then(|s| s.checked_add(z));
+    Ok(sum.context("grove coordinates sum overflow")?.into())
+}
+
+pub struct Day20;
+
+impl Solution for Day20 {
+    const DAY: u8 = 20;
+
+    type Input = Vec<isize>;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        let numbers: Vec<isize> = parse::lines(input, |l| parse::value(l, "invalid number"))?;
+        // The coordinates are counted from the only 0
+        let mut zeros = numbers.iter().enumerate().filter(|(_, &n)| n == 0);
+        if zeros.next().is_none() {
+            return Err(ParseError::new("no 0 in the file", "0").into());
+        }
+        if let Some((i, _)) = zeros.next() {
+            return Err(ParseError::new("more than one 0 in the file", "0")
+                .at(i + 1, 1)
+                .into());
+        }
+        Ok(numbers)
+    }
+
+    fn part1(numbers: &Self::Input) -> Result<Answer> {
+        let mut list = CircularList::new(numbers.iter().copied());
+        list.mix(1);
+        grove_sum(&list)
+    }
+
+    fn part2(numbers: &Self::Input) -> Result<Answer> {
+        let numbers = numbers
+            .iter()
+            .map(|n| n.checked_mul(811589153))
+            .collect::<Option<Vec<_>>>()
+            .context("number too large for the decryption key")?;
+        let mut list = CircularList::new(numbers);
+        list.mix(10);
+        grove_sum(&list)
+    }
+}
+
+impl Generate for Day20 {
+    /// Numbers in the file, one of them being 0
+    const SIZE: usize = 5000;
+
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        let mut numbers: Vec<isize> = (1..size.max(1))
+            .map(|_| loop {
+                let n = rng.isize(-10000..=10000);
+                if n != 0 {
+                    break n;
+                }
+            })
+            .collect();
+        numbers.push(0);
+        rng.shuffle(&mut numbers);
+        numbers.iter().map(|n| format!("{}\n", n)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../example");
+
+    #[test]
+    fn mix_once() {
+        let numbers = Day20::parse(EXAMPLE).unwrap();
+        let mut list = CircularList::new(numbers);
+        list.mix(1);
+        let zero = list.elements.iter().position(|&e| e == 0).unwrap();
+        list.elements.rotate_left(zero);
+        assert_eq!(list.elements, vec![0, 3, -2, 1, 2, -3, 4]);
+        assert_eq!(list.get_coords(), Some((4, -3, 2)));
+    }
+
+    #[test]
+    fn malformed_files() {
+        let error = |input: &str| Day20::parse(input).unwrap_err().to_string();
+        assert_eq!(error("1\n2\n"), "no 0 in the file in \"0\"");
+        assert_eq!(
+            error("0\n1\n0\n"),
+            "line 3, column 1: more than one 0 in the file in \"0\""
+        );
+
+        let single = Day20::parse("0\n").unwrap();
+        assert_eq!(Day20::part1(&single).unwrap().to_string(), "0");
+        assert_eq!(Day20::part2(&single).unwrap().to_string(), "0");
+        let large = Day20::parse("0\n-9223372036854775808\n").unwrap();
+        assert!(Day20::part1(&large).is_ok());
+        assert!(Day20::part2(&large).is_err());
+    }
+
+    #[test]
+    fn part1() {
+        let numbers = Day20::parse(EXAMPLE).unwrap();
+        assert_eq!(Day20::part1(&numbers).unwrap().to_string(), "3");
+    }
+
+    #[test]
+    fn part2() {
+        let numbers = Day20::parse(EXAMPLE).unwrap();
+        assert_eq!(Day20::part2(&numbers).unwrap().to_string(), "1623178306");
+    }
+}
@@ -0,0 +1,417 @@
+//! Day 21: Monkey Math.
+//!
+//! Also usable to evaluate any riddle of yelling monkeys:
+//!
+//! ```
+//! use day21::{Monkey, Riddle};
+//!
+//! let monkeys: Vec<Monkey> = ["root: abcd * efgh", "abcd: 4", "efgh: 5"]
+//!     .iter()
+//!     .map(|m| m.parse().unwrap())
+//!     .collect();
+//! let mut riddle = Riddle::new(&monkeys);
+//! while riddle.pass() {}
+//! assert_eq!(riddle.value("root"), Some(20.0));
+//! ```
+
+use std::cell::RefCell;
+use std::{
+    collections::{HashMap, HashSet},
+    str::FromStr,
+};
+
+use anyhow::{bail, Context, Result};
+use common::gen::{Generate, Rng};
+use common::parse::{self, ParseContext, ParseError};
+use common::{Answer, Solution};
+
+/// Four letter name of a monkey
+pub type MonkeyId = String;
+
+/// Monkeys by name, each one waiting for the numbers its job depends on
+#[derive(Debug)]
+pub struct Riddle {
+    pub monkeys: HashMap<MonkeyId, RefCell<Monkey>>,
+}
+
+/// Monkey yelling a number, `root: pppw + sjmn` or `dbpl: 5`
+#[derive(Debug, Clone)]
+pub struct Monkey {
+    pub id: MonkeyId,
+    pub job: Job,
+}
+
+/// Number yelled by a monkey, once known, and the operation giving it
+#[derive(Debug, Clone)]
+pub struct Job {
+    pub res: Option<f64>,
+    pub operation: Option<Operation>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Operation {
+    pub typ: OpType,
+    pub m1: MonkeyId,
+    pub m2: MonkeyId,
+}
+
+/// Operations of the jobs, `Eq` being the check of `root` in part 2
+#[derive(Debug, Clone)]
+pub enum OpType {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Eq,
+}
+
+impl FromStr for Monkey {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let (id, _) = s.split_once(":").parse_context("invalid name", s)?;
+        let (_, job) = s.split_once(" ").parse_context("invalid job", s)?;
+
+        let job = if let Ok(v) = job.parse::<f64>() {
+            Job {
+                res: Some(v),
+                operation: None,
+            }
+        } else {
+            let mut splits = job.split(" ");
+            let m1 = splits.next().parse_context("invalid job", job)?;
+            let op = splits.next().parse_context("no operation", job)?;
+            let m2 = splits.next().parse_context("no second monkey", job)?;
+            let m1 = m1.to_string();
+            let m2 = m2.to_string();
+            let typ = match op {
+                "+" => OpType::Add,
+                "-" => OpType::Sub,
+                "*" => OpType::Mul,
+                "/" => OpType::Div,
+                _ => return Err(ParseError::new("invalid operation", op)),
+            };
+            Job {
+                res: None,
+                operation: Some(Operation { typ, m1, m2 }),
+            }
+        };
+
+        Ok(Monkey {
+            id: id.to_string(),
+            job,
+        })
+    }
+}
+
+impl Riddle {
+    pub fn new(monkeys: &[Monkey]) -> Self {
+        let monkeys = monkeys
+            .iter()
+            .map(|m| (m.id.clone(), RefCell::new(m.clone())))
+            .collect();
+        Riddle { monkeys }
+    }
+
+    /// Number yelled by monkey `id`, once known
+    pub fn value(&self, id: &str) -> Option<f64> {
+        self.monkeys.get(id)?.borrow().job.res
+    }
+
+    /// Compute the jobs whose operands are known, `false` when none was
+    pub fn pass(&mut self) -> bool {
+        let mut moved = false;
+        for monkey in self
+            .monkeys
+            .values()
+            .filter(|m| m.borrow().job.res.is_none())
+        {
+            let mut monkey = monkey.borrow_mut();
+            if let Some(op) = &monkey.job.operation {
+                if let OpType::Eq = op.typ {
+                    continue;
+                }
+                let res1 = self.known(&op.m1);
+                let res2 = self.known(&op.m2);
+                if let (Some(r1), Some(r2)) = (res1, res2) {
+                    match op.typ {
+                        OpType::Mul => monkey.job.res = Some(r1 * r2),
+                        OpType::Div => monkey.job.res = Some(r1 / r2),
+                        OpType::Add => monkey.job.res = Some(r1 + r2),
+                        OpType::Sub => monkey.job.res = Some(r1 - r2),
+                        _ => unreachable!(),
+                    };
+                    moved = true;
+                };
+            }
+        }
+        moved
+    }
+
+    /// Number yelled by monkey `id`, `None` while unknown or when it is the
+    /// monkey being computed
+    fn known(&self, id: &str) -> Option<f64> {
+        self.monkeys.get(id)?.try_borrow().ok()?.job.res
+    }
+
+    /// Number `humn` has to yell for monkey `id` to yell `exp_value`, the
+    /// riddle being solved as far as possible without it
+    pub fn dfs_solve_x(&self, id: &str, exp_value: f64) -> Result<f64> {
+        let (mut id, mut exp_value) = (id.to_string(), exp_value);
+        // Each monkey is only gone through once, unless they wait for each other
+        for _ in 0..=self.monkeys.len() {
+            if id == "humn" {
+                return Ok(exp_value);
+            }
+            let monkey = self
+                .monkeys
+                .get(&id)
+                .with_context(|| format!("no monkey {}", id))?;
+            let monkey = monkey.borrow();
+            let op = monkey
+                .job
+                .operation
+                .as_ref()
+                .with_context(|| format!("{} does not depend on humn", id))?;
+
+            let (v, child, left) = match (self.known(&op.m1), self.known(&op.m2)) {
+                (None, Some(v)) => (v, &op.m1, true),
+                (Some(v), None) => (v, &op.m2, false),
+                (Some(_), Some(_)) => bail!("{} does not depend on humn", id),
+                (None, None) => bail!("both monkeys {} waits for depend on humn", id),
+            };
+            exp_value = match op.typ {
+                OpType::Eq => v,
+                OpType::Add => exp_value - v,
+                OpType::Sub => {
+                    if left {
+                        exp_value + v
+                    } else {
+                        v - exp_value
+                    }
+                }
+                OpType::Mul => exp_value / v,
+                OpType::Div => {
+                    if left {
+                        exp_value * v
+                    } else {
+                        v / exp_value
+                    }
+                }
+            };
+            let child = child.clone();
+            drop(monkey);
+            id = child;
+        }
+        bail!("the monkeys {} depends on wait for each other", id)
+    }
+}
+
+pub struct Day21;
+
+impl Solution for Day21 {
+    const DAY: u8 = 21;
+
+    type Input = Vec<Monkey>;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        let monkeys: Vec<Monkey> = parse::lines(input, str::parse)?;
+        let names: HashSet<&str> = monkeys.iter().map(|m| m.id.as_str()).collect();
+        for name in ["root", "humn"] {
+            if !names.contains(name) {
+                return Err(ParseError::new(format!("no {} monkey", name), name).into());
+            }
+        }
+        for (i, (line, monkey)) in input.lines().zip(monkeys.iter()).enumerate() {
+            let Some(op) = &monkey.job.operation else {
+                continue;
+            };
+            let job_start = line.find(':').map_or(0, |c| c + 1);
+            let columns = [line[job_start..].find(&op.m1), line.rfind(&op.m2)];
+            let columns = columns.map(|c| c.map_or(1, |c| c + 1));
+            for (name, column) in [(&op.m1, columns[0] + job_start), (&op.m2, columns[1])] {
+                if !names.contains(name.as_str()) {
+                    let err = ParseError::new("no monkey with this name", name);
+                    return Err(err.at(i + 1, column).into());
+                }
+            }
+        }
+        Ok(monkeys)
+    }
+
+    fn part1(jobs: &Self::Input) -> Result<Answer> {
+        let mut riddle = Riddle::new(jobs);
+        while riddle.pass() {}
+        let res = riddle
+            .value("root")
+            .context("root waits for monkeys waiting for each other")?;
+        Ok(res.into())
+    }
+
+    fn part2(jobs: &Self::Input) -> Result<Answer> {
+        let mut riddle = Riddle::new(jobs);
+        let monkeys = &riddle.monkeys;
+        let root = monkeys.get("root").context("no root monkey")?;
+        root.borrow_mut()
+            .job
+            .operation
+            .as_mut()
+            .context("root yells a number instead of comparing two")?
+            .typ = OpType::Eq;
+        let humn = monkeys.get("humn").context("no humn monkey")?;
+        humn.borrow_mut().job = Job {
+            res: None,
+            operation: None,
+        };
+
+        while riddle.pass() {}
+        let x = riddle.dfs_solve_x("root", 0.0)?;
+        Ok(x.into())
+    }
+}
+
+/// Unique four letter name, never `root` nor `humn`
+fn random_name(rng: &mut Rng, taken: &mut HashSet<String>) -> String {
+    loop {
+        let name = rng.word(4..=4);
+        if name != "root" && name != "humn" && taken.insert(name.clone()) {
+            return name;
+        }
+    }
+}
+
+/// Random tree of about `size` monkeys not depending on `humn`, returns its
+/// root and value. Divisions are only used when exact so that the `f64`
+/// evaluation of the solution stays exact
+fn random_tree(
+    rng: &mut Rng,
+    taken: &mut HashSet<String>,
+    lines: &mut Vec<String>,
+    size: usize,
+) -> (String, i64) {
+    let name = random_name(rng, taken);
+    if size < 3 {
+        let v = rng.isize(1..=20) as i64;
+        lines.push(format!("{}: {}", name, v));
+        return (name, v);
+    }
+    let left = rng.usize(1..=size - 2);
+    let (m1, a) = random_tree(rng, taken, lines, left);
+    let (m2, b) = random_tree(rng, taken, lines, size - 1 - left);
+    let mut ops = vec![('+', a + b), ('-', a - b)];
+    if let Some(v) = a.checked_mul(b).filter(|v| v.abs() <= 1_000_000_000) {
+        ops.push(('*', v));
+    }
+    if b != 0 && a % b == 0 {
+        ops.push(('/', a / b));
+    }
+    let (op, v) = *rng.pick(&ops);
+    lines.push(format!("{}: {} {} {}", name, m1, op, m2));
+    (name, v)
+}
+
+impl Generate for Day21 {
+    /// Monkeys, roughly
+    const SIZE: usize = 2000;
+
+    /// `root` adds the branch of `humn`, built from additions, subtractions and
+    /// multiplications by non zero values so that a single number balances it,
+    /// and a branch adjusted to the value of this branch for a random number
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        let mut taken = HashSet::new();
+        let mut lines = vec![];
+
+        let humn = rng.isize(1..=1000) as i64;
+        let solution = rng.isize(1..=10000) as i64;
+        lines.push(format!("humn: {}", humn));
+        let (mut branch, mut value, mut balanced) = ("humn".to_string(), humn, solution);
+        let depth = (size / 30).max(1);
+        for _ in 0..depth {
+            let (m, c) = random_tree(rng, &mut taken, &mut lines, size / 2 / depth);
+            let humn_left = rng.chance(0.5);
+            let mut ops = vec!['+', '-'];
+            let fits = |v: i64| (v * c).abs() <= 1_000_000_000_000;
+            if c != 0 && c.abs() <= 10 && fits(value) && fits(balanced) {
+                ops.push('*');
+            }
+            let op = *rng.pick(&ops);
+            let apply = |v: i64| match (op, humn_left) {
+                ('+', _) => v + c,
+                ('-', true) => v - c,
+                ('-', false) => c - v,
+                _ => v * c,
+            };
+            value = apply(value);
+            balanced = apply(balanced);
+            let name = random_name(rng, &mut taken);
+            let (m1, m2) = if humn_left {
+                (&branch, &m)
+            } else {
+                (&m, &branch)
+            };
+            lines.push(format!("{}: {} {} {}", name, m1, op, m2));
+            branch = name;
+        }
+
+        let (m, c) = random_tree(rng, &mut taken, &mut lines, size / 2);
+        let (adjust, name) = (random_name(rng, &mut taken), random_name(rng, &mut taken));
+        let (op, k) = if balanced >= c {
+            ('+', balanced - c)
+        } else {
+            ('-', c - balanced)
+        };
+        lines.push(format!("{}: {}", adjust, k));
+        lines.push(format!("{}: {} {} {}", name, m, op, adjust));
+        lines.push(format!("root: {} + {}", branch, name));
+
+        rng.shuffle(&mut lines);
+        lines.iter().map(|l| format!("{}\n", l)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../example");
+
+    #[test]
+    fn malformed_riddles() {
+        let error = |input: &str| Day21::parse(input).unwrap_err().to_string();
+        let unknown = EXAMPLE.replace("root: pppw + sjmn", "root: pppw + abcd");
+        assert_eq!(
+            error(&unknown),
+            "line 1, column 14: no monkey with this name in \"abcd\""
+        );
+        let unknown = EXAMPLE.replace("root: pppw + sjmn", "root: abcd + sjmn");
+        assert!(error(&unknown).starts_with("line 1, column 7: no monkey"));
+        assert!(error(&EXAMPLE.replace("humn", "hmmn")).starts_with("no humn monkey"));
+
+        // Parsed fine, but the jobs cannot be done
+        let parse = |input: &str| Day21::parse(input).unwrap();
+        let cycle = parse("root: abcd + humn\nabcd: root * humn\nhumn: 5\n");
+        let e = Day21::part1(&cycle).unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "root waits for monkeys waiting for each other"
+        );
+        assert!(Day21::part2(&cycle).is_err());
+        let twice = parse("root: abcd + humn\nabcd: humn * humn\nhumn: 5\n");
+        let e = Day21::part2(&twice).unwrap_err();
+        assert_eq!(e.to_string(), "both monkeys root waits for depend on humn");
+        let number = parse("root: 4\nhumn: 5\n");
+        assert!(Day21::part2(&number).is_err());
+    }
+
+    #[test]
+    fn part1() {
+        let jobs = Day21::parse(EXAMPLE).unwrap();
+        assert_eq!(Day21::part1(&jobs).unwrap().to_string(), "152");
+    }
+
+    #[test]
+    fn part2() {
+        let jobs = Day21::parse(EXAMPLE).unwrap();
+        assert_eq!(Day21::part2(&jobs).unwrap().to_string(), "301");
+    }
+}
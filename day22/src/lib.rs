@@ -0,0 +1,496 @@
+//! Day 22: Monkey Map.
+//!
+//! Also usable to walk any board, flat or folded into a cube whose faces are
+//! laid out like in the real inputs:
+//!
+//! ```
+//! use day22::{Board, Instructions, State};
+//!
+//! let board: Board = "  ..#\n  ...\n....\n".parse().unwrap();
+//! let instructions: Instructions = "3R2L1".parse().unwrap();
+//! let mut state = State::new(board, false);
+//! state.walk(&instructions);
+//! assert_eq!(state.cur_pos, day22::Point::new(0, 2));
+//! assert_eq!(state.password(), 3004);
+//! ```
+
+use std::{collections::HashMap, str::FromStr};
+
+use anyhow::Result;
+use common::animate::{Animate, Colour, Frame, Pixel, Simulation};
+use common::gen::{Generate, Rng};
+use common::parse::{self, ParseContext, ParseError};
+pub use common::point::{Direction, Point};
+use common::{Answer, Solution};
+use grid::{Cell, SparseGrid};
+
+/// Position on a board and the direction faced, the board being folded into
+/// a cube when `is_cube`
+#[derive(Debug, Clone)]
+pub struct State {
+    pub board: Board,
+    pub cur_pos: Point<isize>,
+    pub direction: Direction,
+    pub is_cube: bool,
+}
+
+/// Open tiles and walls of the map, the rest being off the board
+#[derive(Debug, Clone)]
+pub struct Board {
+    first_pos: Point<isize>,
+    tiles: SparseGrid<Tile>,
+    /// Side of the faces of the cube
+    face_size: isize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Tile {
+    Wall,
+    Free,
+}
+
+impl Cell for Tile {
+    fn from_char(c: char) -> Option<Self> {
+        match c {
+            '.' => Some(Tile::Free),
+            '#' => Some(Tile::Wall),
+            _ => None,
+        }
+    }
+
+    fn to_char(&self) -> char {
+        match self {
+            Tile::Free => '.',
+            Tile::Wall => '#',
+        }
+    }
+}
+
+/// Step forward, or turn 90 degrees in place
+#[derive(Debug, Clone)]
+pub enum Instruction {
+    Forward(usize),
+    Left,
+    Right,
+}
+
+/// Path to follow, `10R5L5`
+#[derive(Debug)]
+pub struct Instructions(pub Vec<Instruction>);
+
+impl FromStr for Board {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let tiles = SparseGrid::parse_with(s, |c| match c {
+            ' ' => Ok(None),
+            c => Tile::from_char(c)
+                .map(Some)
+                .ok_or_else(|| ParseError::new("invalid tile", &c.to_string())),
+        })?;
+        let first_pos = tiles
+            .row(0)
+            .find(|(_, tile)| **tile == Tile::Free)
+            .map(|(c, _)| c)
+            .parse_context(
+                "no free tile on the first row",
+                s.lines().next().unwrap_or_default(),
+            )?;
+        let face_size = ((tiles.len() / 6) as f64).sqrt() as isize;
+        Ok(Board {
+            tiles,
+            first_pos,
+            face_size,
+        })
+    }
+}
+
+impl FromStr for Instructions {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let mut instructions = Vec::new();
+        let mut cur_num = String::new();
+        for (i, c) in s.trim().chars().enumerate() {
+            if !c.is_ascii_digit() && !cur_num.is_empty() {
+                instructions.push(Instruction::Forward(parse::value(
+                    &cur_num,
+                    "invalid step count",
+                )?));
+                cur_num.clear();
+            }
+            match c {
+                '0'..='9' => cur_num.push(c),
+                'R' => instructions.push(Instruction::Right),
+                'L' => instructions.push(Instruction::Left),
+                _ => {
+                    let err = ParseError::new("invalid instruction", &c.to_string());
+                    return Err(err.at(1, i + 1));
+                }
+            }
+        }
+        if !cur_num.is_empty() {
+            instructions.push(Instruction::Forward(parse::value(
+                &cur_num,
+                "invalid step count",
+            )?));
+        }
+        Ok(Instructions(instructions))
+    }
+}
+
+/// Facing value used in the final password
+fn score(d: &Direction) -> usize {
+    match d {
+        Direction::Right => 0,
+        Direction::Down => 1,
+        Direction::Left => 2,
+        Direction::Up => 3,
+    }
+}
+
+/// Arrow drawn for a tile left facing `d`
+fn arrow(d: &Direction) -> char {
+    match d {
+        Direction::Right => '>',
+        Direction::Down => 'v',
+        Direction::Left => '<',
+        Direction::Up => '^',
+    }
+}
+
+impl State {
+    /// Facing right on the leftmost open tile of the top row
+    pub fn new(board: Board, is_cube: bool) -> Self {
+        State {
+            cur_pos: board.first_pos,
+            board,
+            direction: Direction::Right,
+            is_cube,
+        }
+    }
+
+    /// Follow every instruction of the path
+    pub fn walk(&mut self, instructions: &Instructions) {
+        for instruction in instructions.0.iter().cloned() {
+            self.apply(instruction);
+        }
+    }
+
+    /// Final password for the current row, column and facing
+    pub fn password(&self) -> usize {
+        1000 * (self.cur_pos.y as usize + 1)
+            + 4 * (self.cur_pos.x as usize + 1)
+            + score(&self.direction)
+    }
+
+    /// Move one tile forward, `false` if a wall is in the way
+    pub fn forward(&mut self) -> bool {
+        let mut new_c = self.cur_pos.step(self.direction);
+        let mut new_dir = self.direction;
+        if self.is_cube {
+            self.wrap_around_cube(&mut new_c, &mut new_dir);
+        } else {
+            self.wrap_around_2d(&mut new_c);
+        }
+        let tile = self.board.tiles.get(new_c);
+        if let Some(Tile::Free) = tile {
+            self.cur_pos = new_c;
+            self.direction = new_dir;
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn apply(&mut self, instruction: Instruction) {
+        match instruction {
+            Instruction::Forward(n) => {
+                for _ in 0..n {
+                    if !self.forward() {
+                        break;
+                    }
+                }
+            }
+            Instruction::Left => {
+                self.direction = self.direction.rotate_left();
+            }
+            Instruction::Right => {
+                self.direction = self.direction.rotate_right();
+            }
+        };
+    }
+
+    pub fn wrap_around_2d(&self, c: &mut Point<isize>) {
+        if !self.board.tiles.contains(*c) {
+            let (new_c, _) = match self.direction {
+                Direction::Left => self.board.tiles.row(c.y).next_back(),
+                Direction::Right => self.board.tiles.row(c.y).next(),
+                Direction::Up => self.board.tiles.column(c.x).next_back(),
+                Direction::Down => self.board.tiles.column(c.x).next(),
+            }
+            .unwrap();
+            *c = new_c;
+        }
+    }
+
+    pub fn wrap_around_cube(&self, c: &mut Point<isize>, cur_dir: &mut Direction) {
+        let cube_size = self.board.face_size;
+
+        let cur_sq = (
+            self.cur_pos.x.div_euclid(cube_size),
+            self.cur_pos.y.div_euclid(cube_size),
+        );
+        let new_sq = (c.x.div_euclid(cube_size), c.y.div_euclid(cube_size));
+
+        if cur_sq != new_sq {
+            let (sq_y, sq_x, new_dir) = match (cur_sq.1, cur_sq.0, &self.direction) {
+                (0, 1, Direction::Up) => (3, 0, Direction::Right),
+                (0, 1, Direction::Left) => (2, 0, Direction::Right),
+                (0, 2, Direction::Up) => (3, 0, Direction::Up),
+                (0, 2, Direction::Right) => (2, 1, Direction::Left),
+                (0, 2, Direction::Down) => (1, 1, Direction::Left),
+                (1, 1, Direction::Right) => (0, 2, Direction::Up),
+                (1, 1, Direction::Left) => (2, 0, Direction::Down),
+                (2, 0, Direction::Up) => (1, 1, Direction::Right),
+                (2, 0, Direction::Left) => (0, 1, Direction::Right),
+                (2, 1, Direction::Right) => (0, 2, Direction::Left),
+                (2, 1, Direction::Down) => (3, 0, Direction::Left),
+                (3, 0, Direction::Right) => (2, 1, Direction::Up),
+                (3, 0, Direction::Down) => (0, 2, Direction::Down),
+                (3, 0, Direction::Left) => (0, 1, Direction::Down),
+                _ => return,
+            };
+            let (mod_x, mod_y) = (self.cur_pos.x % cube_size, self.cur_pos.y % cube_size);
+            let offset_val = match &self.direction {
+                Direction::Up => mod_x,
+                Direction::Right => mod_y,
+                Direction::Down => (cube_size - 1) - mod_x,
+                Direction::Left => (cube_size - 1) - mod_y,
+            };
+            let (new_x, new_y) = match new_dir {
+                Direction::Up => (offset_val, cube_size - 1),
+                Direction::Right => (0, offset_val),
+                Direction::Down => ((cube_size - 1) - offset_val, 0),
+                Direction::Left => (cube_size - 1, (cube_size - 1) - offset_val),
+            };
+            c.x = sq_x * cube_size + new_x;
+            c.y = sq_y * cube_size + new_y;
+            *cur_dir = new_dir;
+        }
+    }
+}
+
+/// The path walked one tile or one turn at a time
+#[derive(Clone)]
+pub struct PathSimulation {
+    state: State,
+    instructions: Vec<Instruction>,
+    next: usize,
+    /// Tiles left to walk for the current instruction
+    remaining: usize,
+    /// Last direction faced on each visited tile
+    trail: HashMap<Point<isize>, Direction>,
+}
+
+impl Simulation for PathSimulation {
+    fn step(&mut self) -> bool {
+        while self.remaining == 0 {
+            let Some(instruction) = self.instructions.get(self.next).cloned() else {
+                return false;
+            };
+            self.next += 1;
+            match instruction {
+                Instruction::Forward(n) => self.remaining = n,
+                turn => {
+                    self.state.apply(turn);
+                    self.trail.insert(self.state.cur_pos, self.state.direction);
+                    return true;
+                }
+            }
+        }
+        self.remaining -= 1;
+        if !self.state.forward() {
+            self.remaining = 0;
+        }
+        self.trail.insert(self.state.cur_pos, self.state.direction);
+        true
+    }
+
+    fn frame(&self) -> Frame {
+        let tiles = &self.state.board.tiles;
+        let mut frame = Frame::bounding(tiles.coords());
+        for (c, tile) in tiles.iter() {
+            let pixel = match tile {
+                Tile::Wall => Pixel::new('#', Colour::White),
+                Tile::Free => Pixel::new('.', Colour::Grey),
+            };
+            frame.set(c, pixel);
+        }
+        for (&c, d) in self.trail.iter() {
+            frame.set(c, Pixel::new(arrow(d), Colour::Yellow));
+        }
+        let pos = self.state.cur_pos;
+        frame.set(pos, Pixel::new(arrow(&self.state.direction), Colour::Red));
+        frame.with_focus(pos).with_status(format!(
+            "instruction {} of {}, at {},{}",
+            self.next,
+            self.instructions.len(),
+            pos.x,
+            pos.y
+        ))
+    }
+
+    fn inspect(&self) -> Vec<(&'static str, String)> {
+        let pos = self.state.cur_pos;
+        let instruction = match self.next.checked_sub(1).map(|i| &self.instructions[i]) {
+            None => "-".to_string(),
+            Some(Instruction::Forward(n)) => format!("forward {}", n),
+            Some(Instruction::Left) => "left".to_string(),
+            Some(Instruction::Right) => "right".to_string(),
+        };
+        let password =
+            1000 * (pos.y as usize + 1) + 4 * (pos.x as usize + 1) + score(&self.state.direction);
+        vec![
+            (
+                "instruction",
+                format!("{}/{}", self.next, self.instructions.len()),
+            ),
+            ("doing", instruction),
+            ("tiles left", self.remaining.to_string()),
+            ("position", format!("{},{}", pos.x, pos.y)),
+            ("facing", format!("{:?}", self.state.direction)),
+            ("password", password.to_string()),
+        ]
+    }
+
+    fn snapshot(&self) -> Option<Box<dyn Simulation>> {
+        Some(Box::new(self.clone()))
+    }
+}
+
+pub struct Day22;
+
+impl Solution for Day22 {
+    const DAY: u8 = 22;
+
+    type Input = (Board, Instructions);
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        let (board, instructions, inst_line) = parse::sections(input)?;
+        let board = board.parse::<Board>().map_err(|e| e.locate(1, board))?;
+        let instructions = instructions
+            .parse::<Instructions>()
+            .map_err(|e| e.locate(inst_line, instructions))?;
+        Ok((board, instructions))
+    }
+
+    fn part1((board, instructions): &Self::Input) -> Result<Answer> {
+        let mut state = State::new(board.clone(), false);
+        state.walk(instructions);
+        Ok(state.password().into())
+    }
+
+    fn part2((board, instructions): &Self::Input) -> Result<Answer> {
+        let mut state = State::new(board.clone(), true);
+        state.walk(instructions);
+        Ok(state.password().into())
+    }
+}
+
+impl Animate for Day22 {
+    fn simulation((board, instructions): &Self::Input, part: u8) -> Result<Box<dyn Simulation>> {
+        let state = State::new(board.clone(), part == 2);
+        let trail = HashMap::from([(state.cur_pos, state.direction)]);
+        Ok(Box::new(PathSimulation {
+            state,
+            instructions: instructions.0.clone(),
+            next: 0,
+            remaining: 0,
+            trail,
+        }))
+    }
+}
+
+impl Generate for Day22 {
+    /// Side of the faces of the cube, always folded from the net of the real
+    /// inputs
+    const SIZE: usize = 50;
+
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        const FACES: [(usize, usize); 6] = [(1, 0), (2, 0), (1, 1), (0, 2), (1, 2), (0, 3)];
+        let side = size.max(1);
+        let mut rows = vec![vec![' '; 3 * side]; 4 * side];
+        for (fx, fy) in FACES {
+            for row in &mut rows[fy * side..(fy + 1) * side] {
+                for tile in &mut row[fx * side..(fx + 1) * side] {
+                    *tile = if rng.chance(0.1) { '#' } else { '.' };
+                }
+            }
+        }
+        rows[0][side] = '.';
+
+        let mut input = String::new();
+        for row in rows {
+            input += row.iter().collect::<String>().trim_end();
+            input.push('\n');
+        }
+        input.push('\n');
+        for i in 0..80 * side {
+            if i % 2 == 0 {
+                input += &rng.usize(1..=side).to_string();
+            } else {
+                input.push(*rng.pick(&['L', 'R']));
+            }
+        }
+        input + "\n"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Part 2 folds the cube net of the real input (50x50 faces), which is laid out
+    // differently from the example, so only the flat map is checked here
+    const EXAMPLE: &str = include_str!("../example");
+
+    #[test]
+    fn parse_board() {
+        let (board, instructions) = Day22::parse(EXAMPLE).unwrap();
+        assert_eq!(board.first_pos, Point::new(8, 0));
+        assert_eq!(board.tiles.len(), 6 * 4 * 4);
+        assert_eq!(instructions.0.len(), 13);
+    }
+
+    #[test]
+    fn final_state() {
+        let (board, instructions) = Day22::parse(EXAMPLE).unwrap();
+        let mut state = State::new(board, false);
+        state.walk(&instructions);
+        assert_eq!(state.cur_pos, Point::new(7, 5));
+        assert_eq!(state.direction, Direction::Right);
+    }
+
+    #[test]
+    fn walked_path() {
+        let input = Day22::parse(EXAMPLE).unwrap();
+        let mut sim = Day22::simulation(&input, 1).unwrap();
+        while sim.step() {}
+        let frame = sim.frame();
+        assert_eq!(
+            frame.get(Point::new(7, 5)),
+            Some(Pixel::new('>', Colour::Red))
+        );
+        assert_eq!(
+            frame.get(Point::new(8, 0)),
+            Some(Pixel::new('>', Colour::Yellow))
+        );
+    }
+
+    #[test]
+    fn part1() {
+        let input = Day22::parse(EXAMPLE).unwrap();
+        assert_eq!(Day22::part1(&input).unwrap().to_string(), "6032");
+    }
+}
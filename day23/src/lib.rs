@@ -0,0 +1,343 @@
+//! Day 23: Unstable Diffusion.
+//!
+//! Also usable to spread out any group of elves:
+//!
+//! ```
+//! let mut grid: day23::Grid = ".....\n..##.\n..#..\n.....\n..##.\n.....\n"
+//!     .parse()
+//!     .unwrap();
+//! let mut rounds = 1;
+//! while grid.step() {
+//!     rounds += 1;
+//! }
+//! assert_eq!(rounds, 4);
+//! assert_eq!(grid.count_empty_tiles_in_rect(), 25);
+//! ```
+
+use std::{
+    collections::{hash_map::Entry, HashMap, HashSet},
+    str::FromStr,
+};
+
+use anyhow::Result;
+use common::animate::{Animate, Colour, Frame, Pixel, Simulation};
+use common::gen::{Generate, Rng};
+use common::parse::ParseError;
+use common::point::Point;
+use common::{Answer, Solution};
+use grid::SparseGrid;
+
+/// Elves of the grove, the rest of the ground being empty
+#[derive(Debug, Clone)]
+pub struct Grid {
+    pub elves: Vec<Elf>,
+    /// Index of the elf standing on each occupied tile
+    pub elves_coord: SparseGrid<usize>,
+}
+
+/// Elf with the index of the first direction it considers next round, in
+/// north, south, west, east order
+#[derive(Debug, Clone)]
+pub struct Elf {
+    pub dir_i: usize,
+    pub coord: Point<isize>,
+}
+
+impl FromStr for Grid {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let mut elves = vec![];
+        let elves_coord = SparseGrid::parse_with(s, |c| {
+            Ok((c != '.').then(|| {
+                elves.push(Elf {
+                    dir_i: 0,
+                    coord: Point::default(),
+                });
+                elves.len() - 1
+            }))
+        })?;
+        for (coord, &elf_i) in elves_coord.iter() {
+            elves[elf_i].coord = coord;
+        }
+
+        Ok(Grid { elves, elves_coord })
+    }
+}
+
+impl Grid {
+    /// Play a round, `false` when no elf moved
+    pub fn step(&mut self) -> bool {
+        let mut new_moves = HashMap::new();
+        let mut to_remove = HashSet::new();
+        for elf_i in 0..self.elves.len() {
+            let new_c = self.elf_move(elf_i);
+            let elf = &mut self.elves[elf_i];
+            elf.dir_i = (elf.dir_i + 1) % 4;
+            if let Some(new_c) = new_c {
+                if let Entry::Vacant(e) = new_moves.entry(new_c) {
+                    e.insert(elf_i);
+                } else {
+                    to_remove.insert(new_c);
+                }
+            }
+        }
+        for c in to_remove {
+            new_moves.remove(&c);
+        }
+        let moved = !new_moves.is_empty();
+        common::event!(DEBUG, elves = new_moves.len(), "elves moved");
+        for (new_c, elf_i) in new_moves {
+            let elf = &mut self.elves[elf_i];
+            self.elves_coord.remove(elf.coord);
+            elf.coord = new_c;
+            self.elves_coord.insert(elf.coord, elf_i);
+        }
+        moved
+    }
+
+    /// Tile proposed by elf `elf_i`, `None` when it has no neighbour or no
+    /// free direction
+    pub fn elf_move(&self, elf_i: usize) -> Option<Point<isize>> {
+        let elf = &self.elves[elf_i];
+        let move_coords = [
+            [(0, -1), (-1, -1), (1, -1)], // North
+            [(0, 1), (1, 1), (-1, 1)],    // South
+            [(-1, 0), (-1, -1), (-1, 1)], // West
+            [(1, 0), (1, -1), (1, 1)],    // East
+        ];
+
+        let mut fully_free = true;
+        let mut first_c = None;
+        for dir_moves in move_coords.iter().cycle().skip(elf.dir_i).take(4) {
+            let mut free = true;
+            for c in dir_moves {
+                let new_c = elf.coord.offset(c.0, c.1);
+                if self.elves_coord.contains(new_c) {
+                    free = false;
+                    break;
+                }
+            }
+            fully_free &= free;
+            if free && first_c.is_none() {
+                let c = elf.coord.offset(dir_moves[0].0, dir_moves[0].1);
+                if !fully_free {
+                    return Some(c);
+                }
+                first_c = Some(c);
+            }
+        }
+        if fully_free {
+            return None;
+        }
+        first_c
+    }
+
+    /// Empty tiles of the smallest rectangle containing every elf
+    pub fn count_empty_tiles_in_rect(&self) -> usize {
+        let Some((min, max)) = self.elves_coord.bounds() else {
+            return 0;
+        };
+        let r_width = max.x - min.x + 1;
+        let r_height = max.y - min.y + 1;
+        (r_width * r_height) as usize - self.elves.len()
+    }
+}
+
+impl std::fmt::Display for Grid {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = self
+            .elves_coord
+            .render_with(|_, elf| if elf.is_some() { '#' } else { '.' });
+        f.write_fmt(format_args!("{}\n", s))
+    }
+}
+
+/// Elves spreading out one round at a time. Part 1 stops after 10 rounds,
+/// part 2 once no elf moves
+#[derive(Clone)]
+pub struct ElvesSimulation {
+    grid: Grid,
+    round: usize,
+    max_rounds: Option<usize>,
+    done: bool,
+}
+
+impl Simulation for ElvesSimulation {
+    fn step(&mut self) -> bool {
+        if self.done || Some(self.round) == self.max_rounds {
+            return false;
+        }
+        self.round += 1;
+        self.done = !self.grid.step();
+        true
+    }
+
+    fn frame(&self) -> Frame {
+        let mut frame = Frame::bounding(self.grid.elves.iter().map(|e| e.coord));
+        for elf in self.grid.elves.iter() {
+            frame.set(elf.coord, Pixel::new('#', Colour::Green));
+        }
+        let centre = Point::new(
+            frame.origin.x + frame.width as isize / 2,
+            frame.origin.y + frame.height as isize / 2,
+        );
+        frame.with_focus(centre).with_status(format!(
+            "round {}, {} empty tiles",
+            self.round,
+            self.grid.count_empty_tiles_in_rect()
+        ))
+    }
+
+    fn inspect(&self) -> Vec<(&'static str, String)> {
+        const DIRECTIONS: [&str; 4] = ["north", "south", "west", "east"];
+        let first = self.grid.elves.first().map_or(0, |e| e.dir_i);
+        vec![
+            ("round", self.round.to_string()),
+            ("elves", self.grid.elves.len().to_string()),
+            ("first direction", DIRECTIONS[first].to_string()),
+            (
+                "empty tiles",
+                self.grid.count_empty_tiles_in_rect().to_string(),
+            ),
+            ("settled", self.done.to_string()),
+        ]
+    }
+
+    fn snapshot(&self) -> Option<Box<dyn Simulation>> {
+        Some(Box::new(self.clone()))
+    }
+}
+
+pub struct Day23;
+
+impl Solution for Day23 {
+    const DAY: u8 = 23;
+
+    type Input = Grid;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(input.parse()?)
+    }
+
+    fn part1(grid: &Self::Input) -> Result<Answer> {
+        let mut grid = grid.clone();
+        for round in 1..=10 {
+            common::span!(DEBUG, "round", round);
+            grid.step();
+        }
+        Ok(grid.count_empty_tiles_in_rect().into())
+    }
+
+    fn part2(grid: &Self::Input) -> Result<Answer> {
+        let mut grid = grid.clone();
+        let mut count: usize = 1;
+        loop {
+            common::span!(DEBUG, "round", round = count);
+            if !grid.step() {
+                break;
+            }
+            count += 1;
+        }
+        Ok(count.into())
+    }
+}
+
+impl Animate for Day23 {
+    fn simulation(grid: &Self::Input, part: u8) -> Result<Box<dyn Simulation>> {
+        Ok(Box::new(ElvesSimulation {
+            grid: grid.clone(),
+            round: 0,
+            max_rounds: (part == 1).then_some(10),
+            done: false,
+        }))
+    }
+}
+
+impl Generate for Day23 {
+    /// Side of the square scan
+    const SIZE: usize = 73;
+
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        (0..size.max(1))
+            .map(|_| {
+                let row: String = (0..size.max(1))
+                    .map(|_| if rng.chance(0.5) { '#' } else { '.' })
+                    .collect();
+                row + "\n"
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../example");
+
+    const SMALL_EXAMPLE: &str = "\
+.....
+..##.
+..#..
+.....
+..##.
+.....
+";
+
+    fn after_rounds(input: &str, rounds: usize) -> Grid {
+        let mut grid = Day23::parse(input).unwrap();
+        for _ in 0..rounds {
+            grid.step();
+        }
+        grid
+    }
+
+    #[test]
+    fn small_grid_after_3_rounds() {
+        let grid = after_rounds(SMALL_EXAMPLE, 3);
+        let expected = "\
+..#..
+....#
+#....
+....#
+.....
+..#..
+
+";
+        assert_eq!(grid.to_string(), expected);
+    }
+
+    #[test]
+    fn grid_after_10_rounds() {
+        let grid = after_rounds(EXAMPLE, 10);
+        let expected = "\
+......#.....
+..........#.
+.#.#..#.....
+.....#......
+..#.....#..#
+#......##...
+....##......
+.#........#.
+...#.#..#...
+............
+...#..#..#..
+
+";
+        assert_eq!(grid.to_string(), expected);
+        assert_eq!(grid.count_empty_tiles_in_rect(), 110);
+    }
+
+    #[test]
+    fn part1() {
+        let grid = Day23::parse(EXAMPLE).unwrap();
+        assert_eq!(Day23::part1(&grid).unwrap().to_string(), "110");
+    }
+
+    #[test]
+    fn part2() {
+        let grid = Day23::parse(EXAMPLE).unwrap();
+        assert_eq!(Day23::part2(&grid).unwrap().to_string(), "20");
+    }
+}
@@ -0,0 +1,524 @@
+//! Day 24: Blizzard Basin.
+//!
+//! Also usable to cross any valley, back and forth:
+//!
+//! ```
+//! let grid: day24::Grid = "#.######\n#>>.<^<#\n#.<..<<#\n#>v.><>#\n#<^v^^>#\n######.#\n"
+//!     .parse()
+//!     .unwrap();
+//! let blizzards = grid.blizzard_cycle();
+//! let route = day24::expedition(&grid, &blizzards, 3).unwrap();
+//! assert_eq!(route.last().unwrap().turn, 54);
+//! assert_eq!(route.last().unwrap().player, grid.end());
+//! ```
+
+use std::str::FromStr;
+
+use anyhow::{Context, Result};
+use common::animate::{Animate, Colour, Frame, Pixel, Simulation};
+use common::cycle::{self, Cycle};
+use common::gen::{Generate, Rng};
+use common::parse::{ParseContext, ParseError};
+use common::point::{Direction, Point};
+use common::search;
+use common::{Answer, Solution};
+use grid::{Bounds, DenseGrid};
+
+/// Blizzards of the valley inside its walls, entered from the top left and
+/// left from the bottom right
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid {
+    pub bounds: Bounds,
+    pub blizzards: Vec<Blizzard>,
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct Blizzard {
+    pub coord: Point<isize>,
+    pub direction: Direction,
+}
+
+/// Position of the expedition at a turn, heading to `end`
+#[derive(Debug, Clone)]
+pub struct State {
+    pub turn: usize,
+    /// Equivalent turn in the blizzard cycle, states are compared on it rather than
+    /// on the turn so that a search where the expedition is stuck ends
+    pub phase: usize,
+    pub player: Point<isize>,
+    pub end: Point<isize>,
+}
+
+impl PartialEq for State {
+    fn eq(&self, other: &Self) -> bool {
+        (self.phase, self.player, self.end) == (other.phase, other.player, other.end)
+    }
+}
+
+impl Eq for State {}
+
+impl std::hash::Hash for State {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.phase.hash(state);
+        self.player.hash(state);
+        self.end.hash(state);
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Move {
+    Dir(Direction),
+    Wait,
+}
+
+impl FromStr for Grid {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let lines: Vec<Vec<char>> = s.lines().map(|l| l.chars().collect()).collect();
+        let first = lines.first().parse_context("missing top wall", s)?;
+        let width = first
+            .len()
+            .checked_sub(2)
+            .filter(|&w| w > 0)
+            .parse_context(
+                "top wall is too short",
+                s.lines().next().unwrap_or_default(),
+            )?;
+        let Some(height) = lines.len().checked_sub(2).filter(|&h| h > 0) else {
+            let last = s.lines().last().unwrap_or_default();
+            return Err(ParseError::new("missing bottom wall", last).at(lines.len(), 1));
+        };
+
+        // The expedition enters on the left of the top wall and leaves on the right
+        // of the bottom one
+        let wall = |gap: usize| -> Vec<char> {
+            (0..width + 2)
+                .map(|x| if x == gap { '.' } else { '#' })
+                .collect()
+        };
+        let walls = [
+            (0, wall(1), "invalid top wall"),
+            (height + 1, wall(width), "invalid bottom wall"),
+        ];
+        for (y, expected, message) in walls {
+            let line = &lines[y];
+            if let Some(x) =
+                (0..expected.len().max(line.len())).find(|&x| line.get(x) != expected.get(x))
+            {
+                let text: String = line.iter().collect();
+                return Err(ParseError::new(message, &text).at(y + 1, x + 1));
+            }
+        }
+
+        let mut blizzards = vec![];
+        for (y, line) in lines[1..=height].iter().enumerate() {
+            let text: String = line.iter().collect();
+            if line.len() != width + 2 {
+                let err = ParseError::new("row width differs from the top wall", &text);
+                return Err(err.at(y + 2, line.len().min(width + 2) + 1));
+            }
+            for x in [0, width + 1] {
+                if line[x] != '#' {
+                    let err = ParseError::new("missing side wall", &text);
+                    return Err(err.at(y + 2, x + 1));
+                }
+            }
+            for (x, &c) in line[1..=width].iter().enumerate() {
+                let direction = match c {
+                    '.' => continue,
+                    '>' => Direction::Right,
+                    '<' => Direction::Left,
+                    '^' => Direction::Up,
+                    'v' => Direction::Down,
+                    c => {
+                        let err = ParseError::new("invalid blizzard", &c.to_string());
+                        return Err(err.at(y + 2, x + 2));
+                    }
+                };
+                blizzards.push(Blizzard {
+                    coord: Point::new(x as isize, y as isize),
+                    direction,
+                });
+            }
+        }
+
+        Ok(Grid {
+            bounds: Bounds::new(width, height),
+            blizzards,
+        })
+    }
+}
+
+/// Blizzard positions over time, they loop after `lcm(width, height)` turns
+pub struct Blizzards {
+    pub cycle: Cycle,
+    states: Vec<DenseGrid<bool>>,
+}
+
+impl Blizzards {
+    /// Tiles covered by a blizzard at the given turn
+    pub fn at(&self, turn: usize) -> &DenseGrid<bool> {
+        &self.states[self.cycle.equivalent_step(turn)]
+    }
+}
+
+impl Grid {
+    /// Every blizzard position until they loop
+    pub fn blizzard_cycle(&self) -> Blizzards {
+        let (cycle, mut states) = cycle::detect_with_history(
+            self.blizzards.clone(),
+            |blizzards| {
+                for blizzard in blizzards.iter_mut() {
+                    let new_c = blizzard.coord.step(blizzard.direction);
+                    blizzard.coord = self.bounds.wrap(new_c).signed();
+                }
+            },
+            |blizzards| blizzards.clone(),
+            |blizzards| {
+                let mut tiles = DenseGrid::new(self.bounds.width, self.bounds.height, false);
+                for blizzard in blizzards.iter() {
+                    tiles[blizzard.coord.unsigned().unwrap()] = true;
+                }
+                tiles
+            },
+        );
+        states.truncate(cycle.mu + cycle.lambda);
+        Blizzards { cycle, states }
+    }
+
+    /// Blizzards after the given number of turns
+    pub fn blizzards_at(&self, turn: usize) -> impl Iterator<Item = Blizzard> + '_ {
+        let (width, height) = (self.bounds.width as isize, self.bounds.height as isize);
+        self.blizzards.iter().map(move |blizzard| {
+            let moved = blizzard.coord + blizzard.direction.delta() * turn as isize;
+            Blizzard {
+                coord: Point::new(moved.x.rem_euclid(width), moved.y.rem_euclid(height)),
+                direction: blizzard.direction,
+            }
+        })
+    }
+
+    pub fn start(&self) -> Point<isize> {
+        Point::new(0, -1)
+    }
+
+    pub fn end(&self) -> Point<isize> {
+        Point::new(self.bounds.width as isize - 1, self.bounds.height as isize)
+    }
+
+    pub fn valid_coord(&self, c: &Point<isize>) -> bool {
+        self.bounds.contains_signed(*c) || *c == self.start() || *c == self.end()
+    }
+}
+
+impl State {
+    const MOVES: [Move; 5] = [
+        Move::Dir(Direction::Left),
+        Move::Dir(Direction::Right),
+        Move::Dir(Direction::Up),
+        Move::Dir(Direction::Down),
+        Move::Wait,
+    ];
+
+    fn apply(&self, mv: Move) -> Self {
+        let mut new_state = self.clone();
+        match mv {
+            Move::Dir(d) => {
+                new_state.player = new_state.player.step(d);
+            }
+            Move::Wait => {}
+        };
+        new_state.turn += 1;
+        new_state
+    }
+
+    /// States of the next turn not caught by a blizzard
+    pub fn moves(&self, grid: &Grid, blizzards: &Blizzards) -> Vec<Self> {
+        let mut moves = vec![];
+        for mv in Self::MOVES {
+            let mut new_state = self.apply(mv);
+            new_state.phase = blizzards.cycle.equivalent_step(new_state.turn);
+            if !grid.valid_coord(&new_state.player) {
+                continue;
+            }
+            if blizzards.at(new_state.turn).get_signed(new_state.player) != Some(&true) {
+                moves.push(new_state);
+            }
+        }
+        moves
+    }
+}
+
+/// Every state of the expedition crossing the valley `trips` times, back and forth
+pub fn expedition(grid: &Grid, blizzards: &Blizzards, trips: usize) -> Option<Vec<State>> {
+    let mut states = vec![State {
+        player: grid.start(),
+        end: grid.end(),
+        turn: 0,
+        phase: 0,
+    }];
+    for trip in 0..trips {
+        let mut init_state = states.last().unwrap().clone();
+        init_state.end = if trip % 2 == 0 {
+            grid.end()
+        } else {
+            grid.start()
+        };
+        let path = search::bfs(
+            [init_state],
+            |state| state.moves(grid, blizzards),
+            |state| state.player == state.end,
+        )?;
+        states.extend(path.nodes.into_iter().skip(1));
+    }
+    Some(states)
+}
+
+/// The expedition walking its route minute by minute
+#[derive(Clone)]
+pub struct ExpeditionSimulation {
+    grid: Grid,
+    route: Vec<State>,
+    minute: usize,
+}
+
+impl Simulation for ExpeditionSimulation {
+    fn step(&mut self) -> bool {
+        if self.minute + 1 >= self.route.len() {
+            return false;
+        }
+        self.minute += 1;
+        true
+    }
+
+    fn frame(&self) -> Frame {
+        let (width, height) = (self.grid.bounds.width, self.grid.bounds.height);
+        let mut frame = Frame::new(Point::new(-1, -1), width + 2, height + 2);
+        for (x, y) in (-1..=width as isize).flat_map(|x| [(x, -1), (x, height as isize)]) {
+            frame.set(Point::new(x, y), Pixel::new('#', Colour::White));
+        }
+        for y in 0..height as isize {
+            frame.set(Point::new(-1, y), Pixel::new('#', Colour::White));
+            frame.set(
+                Point::new(width as isize, y),
+                Pixel::new('#', Colour::White),
+            );
+        }
+        frame.set(self.grid.start(), Pixel::EMPTY);
+        frame.set(self.grid.end(), Pixel::EMPTY);
+
+        for blizzard in self.grid.blizzards_at(self.minute) {
+            let pixel = match frame.get(blizzard.coord) {
+                Some(p) if p != Pixel::EMPTY => {
+                    let n = p.ch.to_digit(10).unwrap_or(1) + 1;
+                    Pixel::new(char::from_digit(n.min(9), 10).unwrap(), Colour::Blue)
+                }
+                _ => {
+                    let ch = match blizzard.direction {
+                        Direction::Up => '^',
+                        Direction::Right => '>',
+                        Direction::Down => 'v',
+                        Direction::Left => '<',
+                    };
+                    Pixel::new(ch, Colour::Cyan)
+                }
+            };
+            frame.set(blizzard.coord, pixel);
+        }
+
+        let state = &self.route[self.minute];
+        frame.set(state.player, Pixel::new('E', Colour::Red));
+        frame.with_focus(state.player).with_status(format!(
+            "minute {}/{}",
+            self.minute,
+            self.route.len() - 1
+        ))
+    }
+
+    fn inspect(&self) -> Vec<(&'static str, String)> {
+        let state = &self.route[self.minute];
+        // Each trip ends where the next one starts
+        let trip = self.route[..=self.minute]
+            .windows(2)
+            .filter(|w| w[0].end != w[1].end)
+            .count()
+            + 1;
+        let nearby = self
+            .grid
+            .blizzards_at(self.minute)
+            .filter(|b| {
+                (b.coord.x - state.player.x).abs() + (b.coord.y - state.player.y).abs() <= 1
+            })
+            .count();
+        vec![
+            (
+                "minute",
+                format!("{}/{}", self.minute, self.route.len() - 1),
+            ),
+            ("trip", trip.to_string()),
+            ("position", format!("{},{}", state.player.x, state.player.y)),
+            ("heading to", format!("{},{}", state.end.x, state.end.y)),
+            ("blizzards around", nearby.to_string()),
+        ]
+    }
+
+    fn snapshot(&self) -> Option<Box<dyn Simulation>> {
+        Some(Box::new(self.clone()))
+    }
+}
+
+pub struct Day24;
+
+impl Solution for Day24 {
+    const DAY: u8 = 24;
+
+    type Input = Grid;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(input.parse()?)
+    }
+
+    fn part1(grid: &Self::Input) -> Result<Answer> {
+        let blizzards = grid.blizzard_cycle();
+        let route = expedition(grid, &blizzards, 1).context("no path found")?;
+        Ok(route.last().unwrap().turn.into())
+    }
+
+    fn part2(grid: &Self::Input) -> Result<Answer> {
+        let blizzards = grid.blizzard_cycle();
+        let route = expedition(grid, &blizzards, 3).context("no path found")?;
+        Ok(route.last().unwrap().turn.into())
+    }
+}
+
+impl Animate for Day24 {
+    fn simulation(grid: &Self::Input, part: u8) -> Result<Box<dyn Simulation>> {
+        let blizzards = grid.blizzard_cycle();
+        let trips = if part == 1 { 1 } else { 3 };
+        let route = expedition(grid, &blizzards, trips).context("no path found")?;
+        Ok(Box::new(ExpeditionSimulation {
+            grid: grid.clone(),
+            route,
+            minute: 0,
+        }))
+    }
+}
+
+impl Generate for Day24 {
+    /// Width of the valley, its height is a fifth of it
+    const SIZE: usize = 120;
+
+    /// Vertical blizzards would block the entrance and the exit forever, so
+    /// they are kept out of the first and last columns
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        let (width, height) = (size.max(2), (size / 5).max(3));
+        let wall = |open: usize| -> String {
+            (0..width + 2)
+                .map(|x| if x == open { '.' } else { '#' })
+                .collect::<String>()
+                + "\n"
+        };
+        let mut input = wall(1);
+        for _ in 0..height {
+            input.push('#');
+            for x in 0..width {
+                let c = if !rng.chance(0.7) {
+                    '.'
+                } else if x == 0 || x == width - 1 {
+                    *rng.pick(&['<', '>'])
+                } else {
+                    *rng.pick(&['<', '>', '^', 'v'])
+                };
+                input.push(c);
+            }
+            input += "#\n";
+        }
+        input + &wall(width)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../example");
+
+    #[test]
+    fn blizzard_period() {
+        let grid = Day24::parse(EXAMPLE).unwrap();
+        assert_eq!(grid.bounds, Bounds::new(6, 4));
+        assert_eq!(grid.blizzards.len(), 19);
+        let cycle = grid.blizzard_cycle().cycle;
+        assert_eq!(cycle, Cycle { mu: 0, lambda: 12 });
+    }
+
+    #[test]
+    fn trips() {
+        let grid = Day24::parse(EXAMPLE).unwrap();
+        let blizzards = grid.blizzard_cycle();
+        let turns: Vec<usize> = (1..=3)
+            .map(|trips| {
+                let route = expedition(&grid, &blizzards, trips).unwrap();
+                route.last().unwrap().turn
+            })
+            .collect();
+        assert_eq!(turns, vec![18, 41, 54]);
+    }
+
+    #[test]
+    fn blizzards_after_turns() {
+        let grid = Day24::parse(EXAMPLE).unwrap();
+        let blizzards = grid.blizzard_cycle();
+        for turn in [0, 1, 5, 13, 30] {
+            let mut covered = DenseGrid::new(6, 4, false);
+            for blizzard in grid.blizzards_at(turn) {
+                covered[blizzard.coord.unsigned().unwrap()] = true;
+            }
+            assert_eq!(&covered, blizzards.at(turn), "turn {}", turn);
+        }
+    }
+
+    #[test]
+    fn malformed_valleys() {
+        let error = |input: &str| Day24::parse(input).unwrap_err().to_string();
+        assert_eq!(
+            error("#.###\n#>>>>>#\n###.#\n"),
+            "line 2, column 6: row width differs from the top wall in \"#>>>>>#\""
+        );
+        assert_eq!(
+            error("#.###\n#>>.#\n"),
+            "line 2, column 1: missing bottom wall in \"#>>.#\""
+        );
+        assert_eq!(
+            error("#.###\n#>.>#\n#..##\n"),
+            "line 3, column 2: invalid bottom wall in \"#..##\""
+        );
+        assert_eq!(
+            error("#.###\n#>#>#\n###.#\n"),
+            "line 2, column 3: invalid blizzard in \"#\""
+        );
+        assert!(error("#.###\n").starts_with("line 1, column 1: missing bottom wall"));
+    }
+
+    #[test]
+    fn blocked_valley() {
+        let grid = Day24::parse("#.####\n#^^^^#\n#^^^^#\n####.#\n").unwrap();
+        assert_eq!(
+            Day24::part1(&grid).unwrap_err().to_string(),
+            "no path found"
+        );
+    }
+
+    #[test]
+    fn part1() {
+        let grid = Day24::parse(EXAMPLE).unwrap();
+        assert_eq!(Day24::part1(&grid).unwrap().to_string(), "18");
+    }
+
+    #[test]
+    fn part2() {
+        let grid = Day24::parse(EXAMPLE).unwrap();
+        assert_eq!(Day24::part2(&grid).unwrap().to_string(), "54");
+    }
+}
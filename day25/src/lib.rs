@@ -0,0 +1,133 @@
+//! Day 25: Full of Hot Air.
+//!
+//! Also usable as a codec for SNAFU numbers, written in base 5 with the digits
+//! `=` (-2), `-` (-1), `0`, `1` and `2`:
+//!
+//! ```
+//! assert_eq!(day25::snafu_encode(2022), "1=11-2");
+//! assert_eq!(day25::snafu_decode("1=11-2").unwrap(), 2022);
+//! ```
+
+use anyhow::Result;
+use common::gen::{Generate, Rng};
+use common::parse::{self, ParseError};
+use common::{Answer, Solution};
+
+/// Value of a SNAFU number. The error points at the first invalid digit
+pub fn snafu_decode(l: &str) -> Result<i64, ParseError> {
+    let mut total = 0;
+    for (i, c) in l.chars().rev().enumerate() {
+        let v = match c {
+            '=' => -2 * 5_i64.pow(i as u32),
+            '-' => -5_i64.pow(i as u32),
+            '0' => 0,
+            '1' => 5_i64.pow(i as u32),
+            '2' => 2 * 5_i64.pow(i as u32),
+            c => {
+                let err = ParseError::new("invalid snafu digit", &c.to_string());
+                return Err(err.at_column(l.len() - i));
+            }
+        };
+        total += v;
+    }
+    Ok(total)
+}
+
+/// SNAFU representation of a number, negative ones included
+pub fn snafu_encode(v: i64) -> String {
+    if v == 0 {
+        return "0".to_string();
+    }
+    let mut s = vec![];
+    let mut res = v;
+    while res != 0 {
+        // Digits 3 and 4 are written as -2 and -1 with a carry
+        let (c, carry) = match res.rem_euclid(5) {
+            4 => ('-', 1),
+            3 => ('=', 1),
+            2 => ('2', 0),
+            1 => ('1', 0),
+            _ => ('0', 0),
+        };
+        res = res.div_euclid(5) + carry;
+        s.push(c);
+    }
+    s.reverse();
+    String::from_iter(s.iter())
+}
+
+pub struct Day25;
+
+impl Solution for Day25 {
+    const DAY: u8 = 25;
+    const PARTS: u8 = 1;
+
+    type Input = Vec<i64>;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(parse::lines(input, snafu_decode)?)
+    }
+
+    fn part1(numbers: &Self::Input) -> Result<Answer> {
+        let sum: i64 = numbers.iter().sum();
+        let v = snafu_encode(sum);
+        Ok(v.into())
+    }
+}
+
+impl Generate for Day25 {
+    /// Fuel requirements
+    const SIZE: usize = 120;
+
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        (0..size.max(1))
+            .map(|_| {
+                let digits = rng.usize(1..=20) as u32;
+                snafu_encode(rng.isize(1..=5isize.pow(digits) / 2) as i64) + "\n"
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../example");
+
+    const NUMBERS: [(i64, &str); 15] = [
+        (1, "1"),
+        (2, "2"),
+        (3, "1="),
+        (4, "1-"),
+        (5, "10"),
+        (6, "11"),
+        (7, "12"),
+        (8, "2="),
+        (9, "2-"),
+        (10, "20"),
+        (15, "1=0"),
+        (20, "1-0"),
+        (2022, "1=11-2"),
+        (12345, "1-0---0"),
+        (314159265, "1121-1110-1=0"),
+    ];
+
+    #[test]
+    fn snafu_codec() {
+        for (decimal, snafu) in NUMBERS {
+            assert_eq!(snafu_decode(snafu).unwrap(), decimal, "{}", snafu);
+            assert_eq!(snafu_encode(decimal), snafu, "{}", decimal);
+        }
+        for v in [0, -1, -2, -3, -2022, i64::MAX / 2, i64::MIN / 2] {
+            assert_eq!(snafu_decode(&snafu_encode(v)).unwrap(), v, "{}", v);
+        }
+    }
+
+    #[test]
+    fn part1() {
+        let numbers = Day25::parse(EXAMPLE).unwrap();
+        assert_eq!(numbers.iter().sum::<i64>(), 4890);
+        assert_eq!(Day25::part1(&numbers).unwrap().to_string(), "2=-1=0");
+    }
+}
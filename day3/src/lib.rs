@@ -0,0 +1,182 @@
+//! Day 3: Rucksack Reorganization.
+//!
+//! Also usable to find the misplaced items of a rucksack:
+//!
+//! ```
+//! use day3::Rucksack;
+//!
+//! let rucksack: Rucksack = "vJrwpWtwJgWrhcsFMMfFFhFp".parse().unwrap();
+//! let common = rucksack.compartments_common_items();
+//! assert_eq!(common.into_iter().collect::<Vec<_>>(), vec!['p']);
+//! assert_eq!(Rucksack::item_priority('p').unwrap(), 16);
+//! ```
+
+use anyhow::{anyhow, Result};
+use common::gen::{Generate, Rng};
+use common::parse::{self, ParseError};
+use common::{Answer, Solution};
+use std::{collections::HashSet, str::FromStr};
+
+/// The items of the two compartments of a rucksack, each half of its line
+pub struct Rucksack {
+    compartment1: HashSet<char>,
+    compartment2: HashSet<char>,
+}
+
+impl FromStr for Rucksack {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some((i, c)) = s.char_indices().find(|(_, c)| !c.is_ascii_alphabetic()) {
+            return Err(ParseError::new("invalid item", &c.to_string()).at_column(i + 1));
+        }
+        if !s.len().is_multiple_of(2) {
+            return Err(ParseError::new("odd number of items", s));
+        }
+        let middle = s.len() / 2;
+        let (c1, c2) = s.split_at(middle);
+        let h1 = HashSet::from_iter(c1.chars());
+        let h2 = HashSet::from_iter(c2.chars());
+        Ok(Rucksack {
+            compartment1: h1,
+            compartment2: h2,
+        })
+    }
+}
+
+impl Rucksack {
+    /// Items of both compartments
+    pub fn all_items(&self) -> HashSet<char> {
+        self.compartment1
+            .union(&self.compartment2)
+            .copied()
+            .collect()
+    }
+
+    /// Items found in both compartments
+    pub fn compartments_common_items(&self) -> HashSet<char> {
+        self.compartment1
+            .intersection(&self.compartment2)
+            .copied()
+            .collect()
+    }
+
+    /// `a` to `z` are worth 1 to 26, `A` to `Z` 27 to 52
+    pub fn item_priority(c: char) -> Result<usize> {
+        let p = match c {
+            'a'..='z' => c as usize - 'a' as usize + 1,
+            'A'..='Z' => c as usize - 'A' as usize + 27,
+            _ => return Err(anyhow!("invalid item value")),
+        };
+        Ok(p)
+    }
+}
+
+pub struct Day3;
+
+impl Solution for Day3 {
+    const DAY: u8 = 3;
+
+    type Input = Vec<Rucksack>;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(parse::lines(input, str::parse)?)
+    }
+
+    fn part1(rucksacks: &Self::Input) -> Result<Answer> {
+        let mut priority_sum = 0;
+        for rucksack in rucksacks.iter() {
+            for item in rucksack.compartments_common_items().iter().copied() {
+                priority_sum += Rucksack::item_priority(item)?;
+            }
+        }
+        Ok(priority_sum.into())
+    }
+
+    fn part2(rucksacks: &Self::Input) -> Result<Answer> {
+        let mut priority_sum = 0;
+        for group_rucksacks in rucksacks.chunks(3) {
+            let first_items = group_rucksacks[0].all_items();
+            let common_items = group_rucksacks.iter().skip(1).fold(first_items, |acc, x| {
+                acc.intersection(&x.all_items()).copied().collect()
+            });
+            for item in common_items.iter().copied() {
+                priority_sum += Rucksack::item_priority(item)?;
+            }
+        }
+        Ok(priority_sum.into())
+    }
+}
+
+impl Generate for Day3 {
+    /// Rucksacks, rounded up to whole groups of three
+    const SIZE: usize = 300;
+
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        let items: Vec<char> = ('a'..='z').chain('A'..='Z').collect();
+        let mut input = String::new();
+        for _ in 0..size.div_ceil(3) {
+            // Every item but the badge is only found in one rucksack of the group,
+            // and only the shared item is found in both compartments
+            let mut others = items.clone();
+            rng.shuffle(&mut others);
+            let badge = others.pop().unwrap();
+            for pool in others.chunks(others.len() / 3).take(3) {
+                let (shared, rest) = pool.split_first().unwrap();
+                let (left, right) = rest.split_at(rest.len() / 2);
+                let len = rng.usize(8..=16);
+                let badge_left = rng.chance(0.5);
+                let mut compartments = [vec![*shared], vec![*shared]];
+                compartments[if badge_left { 0 } else { 1 }].push(badge);
+                for (compartment, pool) in compartments.iter_mut().zip([left, right]) {
+                    while compartment.len() < len {
+                        compartment.push(*rng.pick(pool));
+                    }
+                    rng.shuffle(compartment);
+                    input.extend(compartment.iter());
+                }
+                input.push('\n');
+            }
+        }
+        input
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../example");
+
+    #[test]
+    fn common_items() {
+        let rucksacks = Day3::parse(EXAMPLE).unwrap();
+        let common: Vec<Vec<char>> = rucksacks
+            .iter()
+            .map(|r| r.compartments_common_items().into_iter().collect())
+            .collect();
+        assert_eq!(
+            common,
+            vec![
+                vec!['p'],
+                vec!['L'],
+                vec!['P'],
+                vec!['v'],
+                vec!['t'],
+                vec!['s']
+            ]
+        );
+    }
+
+    #[test]
+    fn part1() {
+        let rucksacks = Day3::parse(EXAMPLE).unwrap();
+        assert_eq!(Day3::part1(&rucksacks).unwrap().to_string(), "157");
+    }
+
+    #[test]
+    fn part2() {
+        let rucksacks = Day3::parse(EXAMPLE).unwrap();
+        assert_eq!(Day3::part2(&rucksacks).unwrap().to_string(), "70");
+    }
+}
@@ -0,0 +1,117 @@
+//! Day 4: Camp Cleanup.
+//!
+//! Also usable to compare the section assignments of pairs of elves:
+//!
+//! ```
+//! use day4::ElfPair;
+//!
+//! let pair: ElfPair = "2-8,3-7".parse().unwrap();
+//! assert!(pair.fully_contained() && pair.overlap());
+//! let pair: ElfPair = "2-4,6-8".parse().unwrap();
+//! assert!(!pair.overlap());
+//! ```
+
+use std::{ops::Range, str::FromStr};
+
+use anyhow::Result;
+use common::gen::{Generate, Rng};
+use common::parse::{self, ParseContext, ParseError};
+use common::{Answer, Solution};
+
+/// Assignments of two elves, `a-b,c-d`
+pub struct ElfPair(pub ElfRange, pub ElfRange);
+
+/// Sections assigned to an elf, `a-b`. Unlike the usual ranges, `end` is
+/// included
+pub struct ElfRange(pub Range<usize>);
+
+impl FromStr for ElfPair {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (r1, r2) = s.split_once(",").parse_context("invalid pair", s)?;
+        Ok(ElfPair(r1.parse()?, r2.parse()?))
+    }
+}
+
+impl FromStr for ElfRange {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (a, b) = s.split_once("-").parse_context("invalid range", s)?;
+        let range = Range {
+            start: parse::value(a, "invalid section")?,
+            end: parse::value(b, "invalid section")?,
+        };
+        Ok(ElfRange(range))
+    }
+}
+
+impl ElfPair {
+    /// One of the assignments contains the other
+    pub fn fully_contained(&self) -> bool {
+        self.0 .0.start <= self.1 .0.start && self.0 .0.end >= self.1 .0.end
+            || self.1 .0.start <= self.0 .0.start && self.1 .0.end >= self.0 .0.end
+    }
+
+    /// The assignments share at least a section
+    pub fn overlap(&self) -> bool {
+        self.0 .0.end >= self.1 .0.start && self.1 .0.end >= self.0 .0.start
+    }
+}
+
+pub struct Day4;
+
+impl Solution for Day4 {
+    const DAY: u8 = 4;
+
+    type Input = Vec<ElfPair>;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(parse::lines(input, str::parse)?)
+    }
+
+    fn part1(pairs: &Self::Input) -> Result<Answer> {
+        let fc = pairs.iter().filter(|p| p.fully_contained()).count();
+        Ok(fc.into())
+    }
+
+    fn part2(pairs: &Self::Input) -> Result<Answer> {
+        let fc = pairs.iter().filter(|p| p.overlap()).count();
+        Ok(fc.into())
+    }
+}
+
+impl Generate for Day4 {
+    /// Pairs of elves
+    const SIZE: usize = 1000;
+
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        let mut range = || {
+            let start = rng.usize(1..=99);
+            format!("{}-{}", start, rng.usize(start..=99))
+        };
+        (0..size)
+            .map(|_| format!("{},{}\n", range(), range()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../example");
+
+    #[test]
+    fn part1() {
+        let pairs = Day4::parse(EXAMPLE).unwrap();
+        assert_eq!(Day4::part1(&pairs).unwrap().to_string(), "2");
+    }
+
+    #[test]
+    fn part2() {
+        let pairs = Day4::parse(EXAMPLE).unwrap();
+        assert_eq!(Day4::part2(&pairs).unwrap().to_string(), "4");
+    }
+}
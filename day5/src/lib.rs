@@ -0,0 +1,503 @@
+//! Day 5: Supply Stacks.
+//!
+//! Also usable to run the rearrangements of both cranes:
+//!
+//! ```
+//! use day5::{get_top_crates, Instruction, Stacks};
+//!
+//! let mut stacks: Stacks = "    [D]\n[N] [C]\n 1   2\n".parse().unwrap();
+//! let instruction: Instruction = "move 2 from 2 to 1".parse().unwrap();
+//! stacks.execute_instructions_9001(&[instruction]).unwrap();
+//! assert_eq!(get_top_crates(&stacks), b"D");
+//! assert_eq!(stacks.stacks()[0].crates, b"NCD");
+//! ```
+
+use anyhow::{Context, Result};
+use common::animate::{Animate, Colour, Frame, Pixel, Simulation};
+use common::diff::{Differential, Variant};
+use common::gen::{Generate, Rng};
+use common::parse::{self, ParseContext, ParseError};
+use common::point::Point;
+use common::{Answer, Solution};
+use std::str::FromStr;
+
+/// The stacks of crates, parsed from their drawing
+#[derive(Debug, Clone)]
+pub struct Stacks(Vec<Stack>);
+
+#[derive(Debug, Clone)]
+pub struct Stack {
+    /// Crates from the bottom to the top
+    pub crates: Vec<u8>,
+}
+
+/// `move n from a to b`, with stacks numbered from 0
+#[derive(Debug, Clone, Copy)]
+pub struct Instruction {
+    pub n: usize,
+    pub from: usize,
+    pub to: usize,
+}
+
+impl FromStr for Stacks {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut stacks = vec![];
+
+        for l in s.lines().rev().skip(1) {
+            if stacks.is_empty() {
+                let nstacks = (l.len() + 1) / 4;
+                for _ in 0..nstacks {
+                    stacks.push(Stack { crates: vec![] });
+                }
+            }
+            for (i, chunk) in l.as_bytes().chunks(4).enumerate() {
+                let val = chunk.get(1).copied().parse_context("no crate", l)?;
+                if val == b' ' {
+                    continue;
+                }
+                let stack = stacks.get_mut(i).parse_context("too many stacks", l)?;
+                stack.crates.push(val);
+            }
+        }
+        Ok(Stacks(stacks))
+    }
+}
+
+impl FromStr for Instruction {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let rest = s
+            .strip_prefix("move ")
+            .parse_context("expected \"move\"", s)?;
+        let (n, rest) = rest
+            .split_once(" from ")
+            .parse_context("expected \"from\"", s)?;
+        let (from, to) = rest
+            .split_once(" to ")
+            .parse_context("expected \"to\"", s)?;
+        let n = parse::value(n, "invalid crate count")?;
+        let from = parse::value::<usize>(from, "invalid stack")?
+            .checked_sub(1)
+            .parse_context("stacks start at 1", from)?;
+        let to = parse::value::<usize>(to, "invalid stack")?
+            .checked_sub(1)
+            .parse_context("stacks start at 1", to)?;
+
+        Ok(Instruction { n, from, to })
+    }
+}
+
+impl Stacks {
+    /// Stacks from left to right
+    pub fn stacks(&self) -> &[Stack] {
+        &self.0
+    }
+
+    /// Move the crates one at a time, like the CrateMover 9000
+    pub fn execute_instructions_9000(&mut self, instructions: &[Instruction]) -> Result<()> {
+        for instruction in instructions.iter() {
+            for _ in 0..instruction.n {
+                let from = self.0.get_mut(instruction.from).context("invalid from")?;
+                let val = from.crates.pop().context("no more crates")?;
+                let to = self.0.get_mut(instruction.to).context("invalid to")?;
+                to.crates.push(val);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Move the crates of an instruction at once, like the CrateMover 9001
+    pub fn execute_instructions_9001(&mut self, instructions: &[Instruction]) -> Result<()> {
+        for instruction in instructions.iter() {
+            let from = self.0.get_mut(instruction.from).context("invalid from")?;
+            let at = from
+                .crates
+                .len()
+                .checked_sub(instruction.n)
+                .context("no more crates")?;
+            let mut values = from.crates.split_off(at);
+            let to = self.0.get_mut(instruction.to).context("invalid to")?;
+            to.crates.append(&mut values);
+        }
+
+        Ok(())
+    }
+}
+
+/// Crate on top of each stack, skipping the empty ones
+pub fn get_top_crates(stacks: &Stacks) -> Vec<u8> {
+    stacks
+        .0
+        .iter()
+        .filter(|s| !s.crates.is_empty())
+        .map(|s| s.crates.last().copied().unwrap())
+        .collect()
+}
+
+/// The crane carrying out the rearrangement one instruction at a time
+#[derive(Clone)]
+pub struct CraneSimulation {
+    stacks: Stacks,
+    instructions: Vec<Instruction>,
+    next: usize,
+    /// Whether the crane moves several crates at once
+    model_9001: bool,
+    /// Why the last instruction could not be carried out
+    error: Option<String>,
+}
+
+impl Simulation for CraneSimulation {
+    fn step(&mut self) -> bool {
+        if self.error.is_some() {
+            return false;
+        }
+        let Some(&instruction) = self.instructions.get(self.next) else {
+            return false;
+        };
+        self.next += 1;
+        let res = match self.model_9001 {
+            true => self.stacks.execute_instructions_9001(&[instruction]),
+            false => self.stacks.execute_instructions_9000(&[instruction]),
+        };
+        if let Err(e) = res {
+            self.error = Some(e.to_string());
+        }
+        true
+    }
+
+    /// Stacks drawn like in the puzzle, the crates of the last move in yellow
+    fn frame(&self) -> Frame {
+        let stacks = &self.stacks.0;
+        let height = stacks.iter().map(|s| s.crates.len()).max().unwrap_or(0);
+        let width = (stacks.len() * 4).saturating_sub(1);
+        let mut frame = Frame::new(Point::new(0, 0), width, height + 1);
+        for y in 0..=height as isize {
+            for x in 0..width as isize {
+                frame.set(Point::new(x, y), Pixel::new(' ', Colour::Default));
+            }
+        }
+        let last = self.next.checked_sub(1).map(|i| self.instructions[i]);
+        for (i, stack) in stacks.iter().enumerate() {
+            let x = i as isize * 4;
+            let moved = match last {
+                Some(m) if m.to == i && self.error.is_none() => m.n,
+                _ => 0,
+            };
+            for (level, &c) in stack.crates.iter().enumerate() {
+                let y = (height - 1 - level) as isize;
+                let colour = match level + moved >= stack.crates.len() {
+                    true => Colour::Yellow,
+                    false => Colour::White,
+                };
+                frame.set(Point::new(x, y), Pixel::new('[', Colour::Grey));
+                frame.set(Point::new(x + 1, y), Pixel::new(c as char, colour));
+                frame.set(Point::new(x + 2, y), Pixel::new(']', Colour::Grey));
+            }
+            let number = char::from_digit((i as u32 + 1) % 10, 10).unwrap_or(' ');
+            frame.set(
+                Point::new(x + 1, height as isize),
+                Pixel::new(number, Colour::Grey),
+            );
+        }
+        let focus = match last {
+            Some(m) => {
+                let top = stacks.get(m.to).map_or(0, |s| s.crates.len());
+                Point::new(m.to as isize * 4 + 1, (height - top) as isize)
+            }
+            None => Point::new(1, height as isize),
+        };
+        frame.with_focus(focus).with_status(format!(
+            "instruction {}/{}",
+            self.next,
+            self.instructions.len()
+        ))
+    }
+
+    fn inspect(&self) -> Vec<(&'static str, String)> {
+        let last = match self.next.checked_sub(1).map(|i| self.instructions[i]) {
+            Some(m) => format!("move {} from {} to {}", m.n, m.from + 1, m.to + 1),
+            None => "-".to_string(),
+        };
+        let top_crates = String::from_utf8_lossy(&get_top_crates(&self.stacks)).into_owned();
+        let tallest = self.stacks.0.iter().map(|s| s.crates.len()).max();
+        let mut values = vec![
+            (
+                "crane",
+                if self.model_9001 { "9001" } else { "9000" }.to_string(),
+            ),
+            (
+                "instruction",
+                format!("{}/{}", self.next, self.instructions.len()),
+            ),
+            ("last move", last),
+            ("top crates", top_crates),
+            ("tallest stack", tallest.unwrap_or(0).to_string()),
+        ];
+        if let Some(e) = &self.error {
+            values.push(("error", e.clone()));
+        }
+        values
+    }
+
+    fn snapshot(&self) -> Option<Box<dyn Simulation>> {
+        Some(Box::new(self.clone()))
+    }
+}
+
+pub struct Day5;
+
+impl Solution for Day5 {
+    const DAY: u8 = 5;
+
+    type Input = (Stacks, Vec<Instruction>);
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        let (stacks, inst, inst_line) = parse::sections(input)?;
+        let stacks = stacks.parse::<Stacks>().map_err(|e| e.locate(1, stacks))?;
+        let instructions = parse::lines(inst, str::parse).map_err(|e| e.locate(inst_line, inst))?;
+        Ok((stacks, instructions))
+    }
+
+    fn part1((stacks, instructions): &Self::Input) -> Result<Answer> {
+        let mut stacks = stacks.clone();
+        stacks.execute_instructions_9000(instructions)?;
+        let top_crates = get_top_crates(&stacks);
+        Ok(String::from_utf8(top_crates)?.into())
+    }
+
+    fn part2((stacks, instructions): &Self::Input) -> Result<Answer> {
+        let mut stacks = stacks.clone();
+        stacks.execute_instructions_9001(instructions)?;
+        let top_crates = get_top_crates(&stacks);
+        Ok(String::from_utf8(top_crates)?.into())
+    }
+}
+
+/// Part 1 with the CrateMover 9001, moving a single crate at a time
+fn part1_single_crates((stacks, instructions): &(Stacks, Vec<Instruction>)) -> Result<Answer> {
+    let mut stacks = stacks.clone();
+    let single: Vec<Instruction> = instructions
+        .iter()
+        .flat_map(|i| (0..i.n).map(|_| Instruction { n: 1, ..*i }))
+        .collect();
+    stacks.execute_instructions_9001(&single)?;
+    let top_crates = get_top_crates(&stacks);
+    Ok(String::from_utf8(top_crates)?.into())
+}
+
+/// Part 2 with the CrateMover 9000, through a spare stack reversing the
+/// crates twice
+fn part2_spare_stack((stacks, instructions): &(Stacks, Vec<Instruction>)) -> Result<Answer> {
+    let mut stacks = stacks.clone();
+    let spare = stacks.0.len();
+    stacks.0.push(Stack { crates: vec![] });
+    let relayed: Vec<Instruction> = instructions
+        .iter()
+        .flat_map(|i| {
+            [
+                Instruction { to: spare, ..*i },
+                Instruction { from: spare, ..*i },
+            ]
+        })
+        .collect();
+    stacks.execute_instructions_9000(&relayed)?;
+    stacks.0.pop();
+    let top_crates = get_top_crates(&stacks);
+    Ok(String::from_utf8(top_crates)?.into())
+}
+
+impl Animate for Day5 {
+    fn simulation((stacks, instructions): &Self::Input, part: u8) -> Result<Box<dyn Simulation>> {
+        Ok(Box::new(CraneSimulation {
+            stacks: stacks.clone(),
+            instructions: instructions.clone(),
+            next: 0,
+            model_9001: part == 2,
+            error: None,
+        }))
+    }
+}
+
+impl Differential for Day5 {
+    fn variants() -> Vec<Variant<Self::Input>> {
+        vec![
+            Variant {
+                name: "9001 single crates",
+                part: 1,
+                run: part1_single_crates,
+            },
+            Variant {
+                name: "9000 spare stack",
+                part: 2,
+                run: part2_spare_stack,
+            },
+        ]
+    }
+}
+
+impl Generate for Day5 {
+    /// Rearrangement steps, the nine stacks start with up to eight crates
+    const SIZE: usize = 500;
+
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        let mut stacks: Vec<Vec<u8>> = (0..9)
+            .map(|_| {
+                (0..rng.usize(1..=8))
+                    .map(|_| b'A' + rng.below(26) as u8)
+                    .collect()
+            })
+            .collect();
+        let height = stacks.iter().map(|s| s.len()).max().unwrap_or(0);
+        let mut input = String::new();
+        for y in (0..height).rev() {
+            let row: Vec<String> = stacks
+                .iter()
+                .map(|s| match s.get(y) {
+                    Some(&c) => format!("[{}]", c as char),
+                    None => "   ".to_string(),
+                })
+                .collect();
+            input += &row.join(" ");
+            input.push('\n');
+        }
+        let numbers: Vec<String> = (1..=stacks.len()).map(|i| format!(" {} ", i)).collect();
+        input += &numbers.join(" ");
+        input += "\n\n";
+
+        // Moves are replayed so that they never take more crates than there are
+        for _ in 0..size {
+            let from = loop {
+                let i = rng.below(stacks.len() as u64) as usize;
+                if !stacks[i].is_empty() {
+                    break i;
+                }
+            };
+            let to = (from + rng.usize(1..=stacks.len() - 1)) % stacks.len();
+            let n = rng.usize(1..=stacks[from].len());
+            let at = stacks[from].len() - n;
+            let moved = stacks[from].split_off(at);
+            stacks[to].extend(moved);
+            input += &format!("move {} from {} to {}\n", n, from + 1, to + 1);
+        }
+        input
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../example");
+
+    fn crates(stacks: &Stacks) -> Vec<&str> {
+        stacks
+            .0
+            .iter()
+            .map(|s| std::str::from_utf8(&s.crates).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn parse_stacks() {
+        let (stacks, instructions) = Day5::parse(EXAMPLE).unwrap();
+        assert_eq!(crates(&stacks), vec!["ZN", "MCD", "P"]);
+        assert_eq!(instructions.len(), 4);
+    }
+
+    #[test]
+    fn crate_mover_9000() {
+        let (mut stacks, instructions) = Day5::parse(EXAMPLE).unwrap();
+        stacks.execute_instructions_9000(&instructions).unwrap();
+        assert_eq!(crates(&stacks), vec!["C", "M", "PDNZ"]);
+    }
+
+    #[test]
+    fn crate_mover_9001() {
+        let (mut stacks, instructions) = Day5::parse(EXAMPLE).unwrap();
+        stacks.execute_instructions_9001(&instructions).unwrap();
+        assert_eq!(crates(&stacks), vec!["M", "C", "PZND"]);
+    }
+
+    #[test]
+    fn crane_simulation() {
+        let input = Day5::parse(EXAMPLE).unwrap();
+        let mut sim = Day5::simulation(&input, 2).unwrap();
+        let mut steps = 0;
+        while sim.step() {
+            steps += 1;
+        }
+        assert_eq!(steps, 4);
+        let values = sim.inspect();
+        assert!(values.contains(&("top crates", "MCD".to_string())));
+        assert!(values.contains(&("last move", "move 1 from 1 to 2".to_string())));
+        assert_eq!(
+            sim.frame().get(Point::new(9, 0)),
+            Some(Pixel::new('D', Colour::White))
+        );
+    }
+
+    #[test]
+    fn part1() {
+        let input = Day5::parse(EXAMPLE).unwrap();
+        assert_eq!(Day5::part1(&input).unwrap().to_string(), "CMZ");
+    }
+
+    #[test]
+    fn part2() {
+        let input = Day5::parse(EXAMPLE).unwrap();
+        assert_eq!(Day5::part2(&input).unwrap().to_string(), "MCD");
+    }
+
+    #[test]
+    fn differential() {
+        common::diff::check::<Day5>(0..20, Some(100)).unwrap_or_else(|m| panic!("{}", m));
+    }
+
+    /// Part 1 wrongly done with the CrateMover 9001
+    struct Buggy;
+
+    impl Solution for Buggy {
+        const DAY: u8 = 5;
+        type Input = <Day5 as Solution>::Input;
+
+        fn parse(input: &str) -> Result<Self::Input> {
+            Day5::parse(input)
+        }
+
+        fn part1(input: &Self::Input) -> Result<Answer> {
+            Day5::part1(input)
+        }
+    }
+
+    impl Generate for Buggy {
+        const SIZE: usize = Day5::SIZE;
+
+        fn generate(rng: &mut Rng, size: usize) -> String {
+            Day5::generate(rng, size)
+        }
+    }
+
+    impl Differential for Buggy {
+        fn variants() -> Vec<Variant<Self::Input>> {
+            vec![Variant {
+                name: "9001",
+                part: 1,
+                run: Day5::part2,
+            }]
+        }
+    }
+
+    #[test]
+    fn shrunk_mismatch() {
+        let mismatch = common::diff::check::<Buggy>(0..20, None).unwrap_err();
+        let (_, moves) = Day5::parse(&mismatch.input).unwrap();
+        // A single move of several crates is enough to tell the two cranes apart
+        assert_eq!(moves.len(), 1);
+        assert!(moves[0].n > 1);
+        assert_ne!(mismatch.outcomes[0].1, mismatch.outcomes[1].1);
+    }
+}
@@ -0,0 +1,98 @@
+//! Day 6: Tuning Trouble.
+//!
+//! Also usable to find the markers of a datastream:
+//!
+//! ```
+//! let stream = "mjqjpqmgbljsphdztnvjfqwrcgsmlb";
+//! assert_eq!(day6::find_marker(stream, 4).unwrap(), 7);
+//! assert_eq!(day6::find_marker(stream, 14).unwrap(), 19);
+//! ```
+
+use anyhow::{anyhow, Result};
+use common::gen::{Generate, Rng};
+use common::{Answer, Solution};
+use std::collections::HashSet;
+
+/// Number of characters read when the last `size` ones are all different
+pub fn find_marker(input: &str, size: usize) -> Result<usize> {
+    for (i, win) in input.as_bytes().windows(size).enumerate() {
+        let mut set = HashSet::new();
+        let unique = win.iter().all(|v| set.insert(v));
+        if unique {
+            return Ok(i + win.len());
+        }
+    }
+    Err(anyhow!("no packet found"))
+}
+
+pub struct Day6;
+
+impl Solution for Day6 {
+    const DAY: u8 = 6;
+
+    type Input = String;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(input.to_string())
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer> {
+        Ok(find_marker(input, 4)?.into())
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer> {
+        Ok(find_marker(input, 14)?.into())
+    }
+}
+
+impl Generate for Day6 {
+    /// Characters of the datastream
+    const SIZE: usize = 4096;
+
+    /// Only the first ten letters are used, so that the start of message marker
+    /// is the run of fourteen distinct letters written in the second half
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        let size = size.max(14);
+        let mut stream: Vec<u8> = (0..size).map(|_| b'a' + rng.below(10) as u8).collect();
+        let mut marker: Vec<u8> = (b'a'..=b'z').collect();
+        rng.shuffle(&mut marker);
+        let start = rng.usize(size / 2..=size - 14);
+        stream[start..start + 14].copy_from_slice(&marker[..14]);
+        String::from_utf8(stream).unwrap() + "\n"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../example");
+
+    const MARKERS: [(&str, usize, usize); 5] = [
+        ("mjqjpqmgbljsphdztnvjfqwrcgsmlb", 7, 19),
+        ("bvwbjplbgvbhsrlpgdmjqwftvncz", 5, 23),
+        ("nppdvjthqldpwncqszvftbrmjlhg", 6, 23),
+        ("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg", 10, 29),
+        ("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw", 11, 26),
+    ];
+
+    #[test]
+    fn markers() {
+        for (stream, packet, message) in MARKERS {
+            assert_eq!(find_marker(stream, 4).unwrap(), packet, "{}", stream);
+            assert_eq!(find_marker(stream, 14).unwrap(), message, "{}", stream);
+        }
+    }
+
+    #[test]
+    fn part1() {
+        let stream = Day6::parse(EXAMPLE).unwrap();
+        assert_eq!(Day6::part1(&stream).unwrap().to_string(), "7");
+    }
+
+    #[test]
+    fn part2() {
+        let stream = Day6::parse(EXAMPLE).unwrap();
+        assert_eq!(Day6::part2(&stream).unwrap().to_string(), "19");
+    }
+}
@@ -0,0 +1,305 @@
+//! Day 7: No Space Left On Device.
+//!
+//! Also usable to measure directories from the output of a terminal session:
+//!
+//! ```
+//! let session = "$ cd /\n$ ls\ndir a\n10 b.txt\n$ cd a\n$ ls\n20 c.txt\n";
+//! let sizes = day7::directory_sizes(session).unwrap();
+//! assert_eq!(sizes, vec![("a".to_string(), 20), ("/".to_string(), 30)]);
+//! ```
+
+use std::convert::{TryFrom, TryInto};
+
+use anyhow::{Context, Result};
+use common::gen::{Generate, Rng};
+use common::parse::{self, ParseContext, ParseError};
+use common::{Answer, Solution};
+
+#[derive(Debug, PartialEq)]
+enum CommandType {
+    Ls,
+    Cd,
+}
+
+#[derive(Debug)]
+struct Command<'a> {
+    r#type: CommandType,
+    arguments: Vec<&'a str>,
+    output: &'a str,
+}
+
+impl<'a> TryFrom<&'a str> for Command<'a> {
+    type Error = ParseError;
+
+    fn try_from(s: &'a str) -> Result<Self, Self::Error> {
+        let (prompt, output) = s.split_once("\n").parse_context("invalid cmd", s)?;
+        let prompt = prompt.trim_start();
+        let (cmd_name, arguments) = match prompt.split_once(" ") {
+            Some((a, b)) => (a, b.split(" ").collect::<Vec<&str>>()),
+            None => (prompt, vec![]),
+        };
+        let cmd_type = match cmd_name {
+            "ls" => CommandType::Ls,
+            "cd" => CommandType::Cd,
+            _ => return Err(ParseError::new("invalid cmd type", cmd_name)),
+        };
+        Ok(Command {
+            r#type: cmd_type,
+            arguments,
+            output,
+        })
+    }
+}
+
+/// Adds `size` to the total of the directory, which must not overflow
+fn grow(
+    dir: Option<&mut (&str, usize)>,
+    size: usize,
+    context: &str,
+    text: &str,
+) -> Result<(), ParseError> {
+    let dir = dir.parse_context(context, text)?;
+    dir.1 = dir
+        .1
+        .checked_add(size)
+        .parse_context("directory size overflow", text)?;
+    Ok(())
+}
+
+/// Total size of each directory, in the order they are left
+fn parse_filesystem<'a>(commands: &[Command<'a>]) -> Result<Vec<(&'a str, usize)>, ParseError> {
+    let mut final_fs: Vec<(&str, usize)> = vec![];
+    let mut tmp_fs: Vec<(&str, usize)> = vec![];
+
+    for command in commands.iter() {
+        match command.r#type {
+            CommandType::Cd => {
+                let loc = command
+                    .arguments
+                    .first()
+                    .parse_context("missing directory", "cd")?;
+                if loc == &".." {
+                    let (name, sum) = tmp_fs
+                        .pop()
+                        .parse_context("no directory to leave", "cd ..")?;
+                    final_fs.push((name, sum));
+                    grow(tmp_fs.last_mut(), sum, "cannot leave the root", "cd ..")?;
+                } else {
+                    tmp_fs.push((loc, 0));
+                }
+            }
+            CommandType::Ls => {
+                for line in command.output.split("\n").filter(|l| !l.is_empty()) {
+                    let (val, _) = line.split_once(" ").parse_context("invalid output", line)?;
+
+                    if val != "dir" {
+                        let size = parse::value::<usize>(val, "invalid file size")?;
+                        grow(tmp_fs.last_mut(), size, "ls outside of a directory", line)?;
+                    }
+                }
+            }
+        }
+    }
+    while let Some((name, sum)) = tmp_fs.pop() {
+        final_fs.push((name, sum));
+        if !tmp_fs.is_empty() {
+            grow(tmp_fs.last_mut(), sum, "cannot leave the root", name)?;
+        }
+    }
+
+    Ok(final_fs)
+}
+
+/// Total size of every directory visited by the `cd` and `ls` commands of a
+/// session, nested directories included. The root comes last
+pub fn directory_sizes(session: &str) -> Result<Vec<(String, usize)>, ParseError> {
+    let commands = session
+        .split('$')
+        .skip(1)
+        .map(|s| s.try_into())
+        .collect::<Result<Vec<Command>, ParseError>>()
+        .map_err(|e| e.locate(1, session))?;
+    let fs = parse_filesystem(&commands).map_err(|e| e.locate(1, session))?;
+    Ok(fs
+        .into_iter()
+        .map(|(name, size)| (name.to_string(), size))
+        .collect())
+}
+
+pub struct Day7;
+
+impl Solution for Day7 {
+    const DAY: u8 = 7;
+
+    /// Size of every directory, the root being the last one
+    type Input = Vec<(String, usize)>;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        let fs = directory_sizes(input)?;
+        if fs.is_empty() {
+            return Err(ParseError::new(
+                "no directory visited",
+                input.lines().next().unwrap_or(""),
+            )
+            .into());
+        }
+        Ok(fs)
+    }
+
+    fn part1(fs: &Self::Input) -> Result<Answer> {
+        let threshold = 100_000;
+        let sum: usize = fs.iter().map(|p| p.1).filter(|s| s <= &threshold).sum();
+        Ok(sum.into())
+    }
+
+    fn part2(fs: &Self::Input) -> Result<Answer> {
+        let total_space: usize = 70_000_000;
+        let update_space: usize = 30_000_000;
+        let taken_space = fs.last().context("no root directory")?.1;
+        let remaining_space = total_space
+            .checked_sub(taken_space)
+            .context("the filesystem does not fit on the disk")?;
+        let required_space = update_space
+            .checked_sub(remaining_space)
+            .filter(|&s| s > 0)
+            .context("the update already fits, no directory needs deleting")?;
+        let deleted_space = fs
+            .iter()
+            .map(|p| p.1)
+            .filter(|s| s >= &required_space)
+            .min()
+            .context("no directory is large enough")?;
+
+        Ok(deleted_space.into())
+    }
+}
+
+impl Generate for Day7 {
+    /// Directories, the root included
+    const SIZE: usize = 200;
+
+    /// New directories are often nested in the last one, giving deep trees.
+    /// The disk ends up between 40M and 70M full, so that part 2 has an answer
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        let size = size.max(1);
+        let mut children = vec![vec![]; size];
+        for i in 1..size {
+            let parent = if rng.chance(0.4) {
+                i - 1
+            } else {
+                rng.usize(0..=i - 1)
+            };
+            children[parent].push(i);
+        }
+        let mut files: Vec<Vec<(String, usize)>> = vec![vec![]; size];
+        let mut weights = vec![];
+        for dir in files.iter_mut() {
+            for _ in 0..rng.usize(0..=4) {
+                let ext = ["", ".txt", ".dat", ".log", ".lst"];
+                let name = format!("{}{}", rng.word(1..=8), rng.pick(&ext));
+                dir.push((name, 0));
+                weights.push(rng.usize(1..=1000));
+            }
+        }
+        // Sizes are drawn as weights, then scaled to the disk usage
+        let total: usize = weights.iter().sum::<usize>().max(1);
+        let target = rng.usize(40_000_001..=69_999_999);
+        let mut weights = weights.into_iter();
+        for dir in files.iter_mut() {
+            for file in dir.iter_mut() {
+                file.1 = (weights.next().unwrap() * target / total).max(1);
+            }
+        }
+
+        let names: Vec<String> = (0..size)
+            .map(|d| {
+                if d == 0 {
+                    "/".to_string()
+                } else {
+                    format!("{}{}", rng.word(1..=6), d)
+                }
+            })
+            .collect();
+        let mut input = String::new();
+        let mut stack = vec![(0, false)];
+        while let Some((dir, listed)) = stack.pop() {
+            if listed {
+                input += "$ cd ..\n";
+                continue;
+            }
+            input += &format!("$ cd {}\n$ ls\n", names[dir]);
+            let mut entries: Vec<String> = children[dir]
+                .iter()
+                .map(|&d| format!("dir {}", names[d]))
+                .collect();
+            entries.extend(
+                files[dir]
+                    .iter()
+                    .map(|(name, size)| format!("{} {}", size, name)),
+            );
+            rng.shuffle(&mut entries);
+            for entry in entries {
+                input += &entry;
+                input.push('\n');
+            }
+            if dir != 0 {
+                stack.push((dir, true));
+            }
+            let mut subdirs = children[dir].clone();
+            rng.shuffle(&mut subdirs);
+            stack.extend(subdirs.into_iter().map(|d| (d, false)));
+        }
+        input
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../example");
+
+    #[test]
+    fn directory_sizes() {
+        let fs = Day7::parse(EXAMPLE).unwrap();
+        let sizes: Vec<(&str, usize)> = fs.iter().map(|(n, s)| (n.as_str(), *s)).collect();
+        assert_eq!(
+            sizes,
+            vec![("e", 584), ("a", 94853), ("d", 24933642), ("/", 48381165)]
+        );
+    }
+
+    #[test]
+    fn part1() {
+        let fs = Day7::parse(EXAMPLE).unwrap();
+        assert_eq!(Day7::part1(&fs).unwrap().to_string(), "95437");
+    }
+
+    #[test]
+    fn part2() {
+        let fs = Day7::parse(EXAMPLE).unwrap();
+        assert_eq!(Day7::part2(&fs).unwrap().to_string(), "24933642");
+    }
+
+    #[test]
+    fn malformed_sessions() {
+        let error = |input: &str| Day7::parse(input).unwrap_err().to_string();
+        assert_eq!(error(""), "no directory visited in \"\"");
+        assert!(error("$ cd /\n$ ls\n18446744073709551615 a\n1 b\n")
+            .ends_with("directory size overflow in \"1 b\""));
+
+        let part2 = |input: &str| {
+            Day7::part2(&Day7::parse(input).unwrap())
+                .unwrap_err()
+                .to_string()
+        };
+        assert_eq!(
+            part2("$ cd /\n$ ls\n100 a\n"),
+            "the update already fits, no directory needs deleting"
+        );
+        assert_eq!(
+            part2("$ cd /\n$ ls\n80000000 a\n"),
+            "the filesystem does not fit on the disk"
+        );
+    }
+}
@@ -0,0 +1,241 @@
+//! Day 8: Treetop Tree House.
+//!
+//! Also usable to look at a forest from inside and outside:
+//!
+//! ```
+//! use common::Solution;
+//! use grid::Coord;
+//!
+//! let forest = day8::Day8::parse("30373\n25512\n65332\n33549\n35390\n").unwrap();
+//! assert!(day8::visible(&forest, Coord::new(1, 1)));
+//! assert!(!day8::visible(&forest, Coord::new(2, 2)));
+//! assert_eq!(day8::scenic_score(&forest, Coord::new(2, 3)), 8);
+//! ```
+
+use anyhow::Result;
+use common::animate::{Animate, Colour, Frame, Pixel, Simulation};
+use common::gen::{Generate, Rng};
+use common::parse::ParseError;
+use common::{Answer, Solution};
+use grid::{Coord, DenseGrid, NEIGHBOURS4};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// Result of `f` on the coordinates of each row, top to bottom. The rows are
+/// handled concurrently with the `parallel` feature
+fn per_row<T: Send>(
+    grid: &DenseGrid<u8>,
+    f: impl Fn(&mut dyn Iterator<Item = Coord>) -> T + Sync,
+) -> Vec<T> {
+    #[cfg(feature = "parallel")]
+    let rows = (0..grid.height()).into_par_iter();
+    #[cfg(not(feature = "parallel"))]
+    let rows = 0..grid.height();
+    rows.map(|y| f(&mut (0..grid.width()).map(|x| Coord::new(x, y))))
+        .collect()
+}
+
+fn is_shorter(grid: &DenseGrid<u8>, val: u8, c: Coord) -> bool {
+    grid[c] < val
+}
+
+/// The tree at `c` can be seen from outside the forest, all the trees between
+/// it and an edge being shorter
+pub fn visible(grid: &DenseGrid<u8>, c: Coord) -> bool {
+    if grid.is_edge(c) {
+        return true;
+    }
+    let val = grid[c];
+
+    NEIGHBOURS4
+        .into_iter()
+        .any(|dir| grid.ray(c, dir).all(|c| is_shorter(grid, val, c)))
+}
+
+/// Trees seen from the tree of height `val` at `c` looking towards `dir`
+fn compute_direction_score(grid: &DenseGrid<u8>, c: Coord, dir: (isize, isize), val: u8) -> usize {
+    let mut score = 0;
+    for c in grid.ray(c, dir) {
+        score += 1;
+        if !is_shorter(grid, val, c) {
+            break;
+        }
+    }
+    score
+}
+
+/// Product of the viewing distances of the tree at `c` in the 4 directions
+pub fn scenic_score(grid: &DenseGrid<u8>, c: Coord) -> usize {
+    if grid.is_edge(c) {
+        return 0;
+    }
+    let val = grid[c];
+
+    NEIGHBOURS4
+        .into_iter()
+        .map(|dir| compute_direction_score(grid, c, dir, val))
+        .product()
+}
+
+/// Trees checked one row at a time. Part 1 marks the visible trees, part 2
+/// the tree with the best scenic score so far
+pub struct ForestSimulation {
+    grid: DenseGrid<u8>,
+    part: u8,
+    /// Rows already checked
+    rows: usize,
+    visible: Vec<Coord>,
+    best: Option<(Coord, usize)>,
+}
+
+impl Simulation for ForestSimulation {
+    fn step(&mut self) -> bool {
+        if self.rows == self.grid.height() {
+            return false;
+        }
+        for x in 0..self.grid.width() {
+            let c = Coord::new(x, self.rows);
+            if self.part == 1 {
+                if visible(&self.grid, c) {
+                    self.visible.push(c);
+                }
+            } else {
+                let score = scenic_score(&self.grid, c);
+                if self.best.is_none_or(|(_, best)| score > best) {
+                    self.best = Some((c, score));
+                }
+            }
+        }
+        self.rows += 1;
+        true
+    }
+
+    fn frame(&self) -> Frame {
+        let mut frame = Frame::new(Default::default(), self.grid.width(), self.grid.height());
+        for (c, &height) in self.grid.iter() {
+            let ch = char::from(b'0' + height);
+            frame.set(
+                c.signed(),
+                Pixel::new(ch, Colour::scale(height as usize, 9)),
+            );
+        }
+        for &c in self.visible.iter() {
+            frame.set(
+                c.signed(),
+                Pixel::new(char::from(b'0' + self.grid[c]), Colour::Green),
+            );
+        }
+        if let Some((c, _)) = self.best {
+            frame.set(
+                c.signed(),
+                Pixel::new(char::from(b'0' + self.grid[c]), Colour::Red),
+            );
+        }
+        let focus = Coord::new(self.grid.width() / 2, self.rows).signed();
+        let status = match (self.part, self.best) {
+            (1, _) => format!("row {}, {} visible trees", self.rows, self.visible.len()),
+            (_, Some((c, score))) => format!(
+                "row {}, best scenic score {} at {},{}",
+                self.rows, score, c.x, c.y
+            ),
+            (_, None) => format!("row {}", self.rows),
+        };
+        frame.with_focus(focus).with_status(status)
+    }
+}
+
+pub struct Day8;
+
+impl Solution for Day8 {
+    const DAY: u8 = 8;
+
+    type Input = DenseGrid<u8>;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        let grid = DenseGrid::parse_with(input, |c| {
+            c.to_digit(10)
+                .map(|d| d as u8)
+                .ok_or_else(|| ParseError::new("invalid tree height", &c.to_string()))
+        })?;
+        Ok(grid)
+    }
+
+    fn part1(grid: &Self::Input) -> Result<Answer> {
+        let c: usize = per_row(grid, |row| row.filter(|&c| visible(grid, c)).count())
+            .into_iter()
+            .sum();
+        Ok(c.into())
+    }
+
+    fn part2(grid: &Self::Input) -> Result<Answer> {
+        let max_score = per_row(grid, |row| row.map(|c| scenic_score(grid, c)).max())
+            .into_iter()
+            .flatten()
+            .max()
+            .unwrap_or(0);
+        Ok(max_score.into())
+    }
+}
+
+impl Animate for Day8 {
+    fn simulation(grid: &Self::Input, part: u8) -> Result<Box<dyn Simulation>> {
+        Ok(Box::new(ForestSimulation {
+            grid: grid.clone(),
+            part,
+            rows: 0,
+            visible: vec![],
+            best: None,
+        }))
+    }
+}
+
+impl Generate for Day8 {
+    /// Side of the forest
+    const SIZE: usize = 99;
+
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        (0..size)
+            .map(|_| {
+                let row: String = (0..size)
+                    .map(|_| (b'0' + rng.below(10) as u8) as char)
+                    .collect();
+                row + "\n"
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../example");
+
+    #[test]
+    fn visibility() {
+        let grid = Day8::parse(EXAMPLE).unwrap();
+        assert!(visible(&grid, Coord::new(1, 1)));
+        assert!(visible(&grid, Coord::new(2, 1)));
+        assert!(!visible(&grid, Coord::new(3, 1)));
+        assert!(!visible(&grid, Coord::new(2, 2)));
+    }
+
+    #[test]
+    fn scenic_scores() {
+        let grid = Day8::parse(EXAMPLE).unwrap();
+        assert_eq!(scenic_score(&grid, Coord::new(2, 1)), 4);
+        assert_eq!(scenic_score(&grid, Coord::new(2, 3)), 8);
+    }
+
+    #[test]
+    fn part1() {
+        let grid = Day8::parse(EXAMPLE).unwrap();
+        assert_eq!(Day8::part1(&grid).unwrap().to_string(), "21");
+    }
+
+    #[test]
+    fn part2() {
+        let grid = Day8::parse(EXAMPLE).unwrap();
+        assert_eq!(Day8::part2(&grid).unwrap().to_string(), "8");
+    }
+}
@@ -0,0 +1,276 @@
+//! Day 9: Rope Bridge.
+//!
+//! Also usable to pull ropes of any length around:
+//!
+//! ```
+//! use day9::{Move, Rope};
+//!
+//! let moves: Vec<Move> = ["R 4", "U 4", "L 3"].iter().map(|m| m.parse().unwrap()).collect();
+//! let mut rope = Rope::new(2);
+//! rope.mov(&moves).unwrap();
+//! assert_eq!(rope.knots()[0], day9::Pos::new(1, -4));
+//! assert_eq!(rope.tail_positions().len(), 9);
+//! ```
+
+use std::collections::HashSet;
+use std::str::FromStr;
+
+use anyhow::Result;
+use common::animate::{Animate, Colour, Frame, Pixel, Simulation};
+use common::gen::{Generate, Rng};
+use common::parse::{self, ParseContext, ParseError};
+use common::point::{Direction, Point};
+use common::{Answer, Solution};
+
+/// Knots of a rope, the head first, and the positions its tail went through
+#[derive(Debug, Clone)]
+pub struct Rope {
+    knots: Vec<Pos>,
+    visited_tail_pos: HashSet<Pos>,
+}
+
+/// Position of a knot, `y` growing downwards
+pub type Pos = Point<isize>;
+
+/// Steps of the head in a direction, `R 4`
+pub struct Move {
+    pub direction: Direction,
+    pub n: usize,
+}
+
+impl FromStr for Move {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (m, n) = s.split_once(" ").parse_context("invalid move", s)?;
+        let n = parse::value(n, "invalid step count")?;
+        let direction = match m {
+            "R" => Direction::Right,
+            "L" => Direction::Left,
+            "U" => Direction::Up,
+            "D" => Direction::Down,
+            _ => return Err(ParseError::new("unknown move", m)),
+        };
+        Ok(Move { direction, n })
+    }
+}
+
+impl Rope {
+    /// Rope of `n` knots, at least one, all at the origin
+    pub fn new(n: usize) -> Self {
+        let mut knots = vec![];
+        for _ in 0..n {
+            knots.push(Pos::new(0, 0))
+        }
+        Rope {
+            knots,
+            visited_tail_pos: HashSet::new(),
+        }
+    }
+
+    pub fn knots(&self) -> &[Pos] {
+        &self.knots
+    }
+
+    /// Positions of the tail after each step
+    pub fn tail_positions(&self) -> &HashSet<Pos> {
+        &self.visited_tail_pos
+    }
+
+    /// Move the head one step at a time
+    pub fn mov(&mut self, moves: &[Move]) -> Result<()> {
+        for mov in moves.iter() {
+            for _ in 0..mov.n {
+                self.step_head(mov.direction);
+            }
+        }
+        Ok(())
+    }
+
+    /// Move the head by one square and let the other knots follow
+    pub fn step_head(&mut self, direction: Direction) {
+        let lead_knot = self.knots.first_mut().unwrap();
+        *lead_knot += direction.delta();
+
+        for j in 1..self.knots.len() {
+            let i = j - 1;
+            let lead_knot = &self.knots[i];
+            let knot = &self.knots[j];
+
+            let dist = lead_knot.euclidean(knot);
+            if dist > 1.5 {
+                let mut best_dist = 1e6;
+                let mut best_pos = None;
+                for y in -1..=1 {
+                    for x in -1..=1 {
+                        let new_pos = knot.offset(x, y);
+                        let new_dist = lead_knot.euclidean(&new_pos);
+                        if new_dist < best_dist {
+                            best_pos = Some(new_pos);
+                            best_dist = new_dist;
+                        }
+                    }
+                }
+                let knot = self.knots.get_mut(j).unwrap();
+                *knot = best_pos.unwrap();
+            }
+        }
+        self.visited_tail_pos.insert(*self.knots.last().unwrap());
+    }
+}
+
+/// The rope following the moves one square at a time
+#[derive(Clone)]
+pub struct RopeSimulation {
+    rope: Rope,
+    directions: Vec<Direction>,
+    steps: usize,
+}
+
+impl Simulation for RopeSimulation {
+    fn step(&mut self) -> bool {
+        let Some(&direction) = self.directions.get(self.steps) else {
+            return false;
+        };
+        self.rope.step_head(direction);
+        self.steps += 1;
+        true
+    }
+
+    fn frame(&self) -> Frame {
+        let start = Pos::new(0, 0);
+        let points = self.rope.visited_tail_pos.iter().chain(&self.rope.knots);
+        let mut frame = Frame::bounding(points.copied().chain([start]));
+        for &p in self.rope.visited_tail_pos.iter() {
+            frame.set(p, Pixel::new('#', Colour::Blue));
+        }
+        frame.set(start, Pixel::new('s', Colour::White));
+        // Draw the tail first so that the head stays on top
+        for (i, &knot) in self.rope.knots.iter().enumerate().rev() {
+            let ch = match i {
+                0 => 'H',
+                _ if self.rope.knots.len() == 2 => 'T',
+                i => char::from_digit(i as u32, 36).unwrap_or('*'),
+            };
+            let colour = if i == 0 { Colour::Red } else { Colour::Yellow };
+            frame.set(knot, Pixel::new(ch, colour));
+        }
+        frame.with_focus(self.rope.knots[0]).with_status(format!(
+            "move {}/{}, {} tail positions",
+            self.steps,
+            self.directions.len(),
+            self.rope.visited_tail_pos.len()
+        ))
+    }
+
+    fn inspect(&self) -> Vec<(&'static str, String)> {
+        let (head, tail) = (
+            self.rope.knots[0],
+            self.rope.knots[self.rope.knots.len() - 1],
+        );
+        let last = self.steps.checked_sub(1).map(|i| self.directions[i]);
+        vec![
+            ("move", format!("{}/{}", self.steps, self.directions.len())),
+            (
+                "direction",
+                last.map_or("-".to_string(), |d| format!("{:?}", d)),
+            ),
+            ("head", format!("{},{}", head.x, head.y)),
+            ("tail", format!("{},{}", tail.x, tail.y)),
+            (
+                "tail positions",
+                self.rope.visited_tail_pos.len().to_string(),
+            ),
+        ]
+    }
+
+    fn snapshot(&self) -> Option<Box<dyn Simulation>> {
+        Some(Box::new(self.clone()))
+    }
+}
+
+pub struct Day9;
+
+impl Solution for Day9 {
+    const DAY: u8 = 9;
+
+    type Input = Vec<Move>;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(parse::lines(input, str::parse)?)
+    }
+
+    fn part1(moves: &Self::Input) -> Result<Answer> {
+        let mut rope = Rope::new(2);
+        rope.mov(moves)?;
+        Ok(rope.visited_tail_pos.len().into())
+    }
+
+    fn part2(moves: &Self::Input) -> Result<Answer> {
+        let mut rope = Rope::new(10);
+        rope.mov(moves)?;
+        Ok(rope.visited_tail_pos.len().into())
+    }
+}
+
+impl Animate for Day9 {
+    fn simulation(moves: &Self::Input, part: u8) -> Result<Box<dyn Simulation>> {
+        let knots = if part == 1 { 2 } else { 10 };
+        let directions = moves
+            .iter()
+            .flat_map(|m| std::iter::repeat_n(m.direction, m.n))
+            .collect();
+        Ok(Box::new(RopeSimulation {
+            rope: Rope::new(knots),
+            directions,
+            steps: 0,
+        }))
+    }
+}
+
+impl Generate for Day9 {
+    /// Motions of the head
+    const SIZE: usize = 2000;
+
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        (0..size)
+            .map(|_| {
+                format!(
+                    "{} {}\n",
+                    rng.pick(&["R", "L", "U", "D"]),
+                    rng.usize(1..=20)
+                )
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../example");
+    const LARGER_EXAMPLE: &str = include_str!("../example2");
+
+    #[test]
+    fn final_knots() {
+        let moves = Day9::parse(EXAMPLE).unwrap();
+        let mut rope = Rope::new(2);
+        rope.mov(&moves).unwrap();
+        assert_eq!(rope.knots, vec![Pos::new(2, -2), Pos::new(1, -2)]);
+    }
+
+    #[test]
+    fn part1() {
+        let moves = Day9::parse(EXAMPLE).unwrap();
+        assert_eq!(Day9::part1(&moves).unwrap().to_string(), "13");
+    }
+
+    #[test]
+    fn part2() {
+        let moves = Day9::parse(EXAMPLE).unwrap();
+        assert_eq!(Day9::part2(&moves).unwrap().to_string(), "1");
+        let moves = Day9::parse(LARGER_EXAMPLE).unwrap();
+        assert_eq!(Day9::part2(&moves).unwrap().to_string(), "36");
+    }
+}
@@ -0,0 +1,45 @@
+/// A cell that can be read from and drawn as a map character
+pub trait Cell: Sized {
+    fn from_char(c: char) -> Option<Self>;
+
+    fn to_char(&self) -> char;
+}
+
+impl Cell for char {
+    fn from_char(c: char) -> Option<Self> {
+        Some(c)
+    }
+
+    fn to_char(&self) -> char {
+        *self
+    }
+}
+
+impl Cell for u8 {
+    fn from_char(c: char) -> Option<Self> {
+        u8::try_from(c).ok()
+    }
+
+    fn to_char(&self) -> char {
+        *self as char
+    }
+}
+
+/// `#` for set cells, `.` for empty ones
+impl Cell for bool {
+    fn from_char(c: char) -> Option<Self> {
+        match c {
+            '#' => Some(true),
+            '.' => Some(false),
+            _ => None,
+        }
+    }
+
+    fn to_char(&self) -> char {
+        if *self {
+            '#'
+        } else {
+            '.'
+        }
+    }
+}
@@ -0,0 +1,95 @@
+use common::point::Point;
+
+/// Offsets of the 4 orthogonal neighbours: up, right, down, left
+pub const NEIGHBOURS4: [(isize, isize); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+
+/// Offsets of the 8 neighbours, clockwise starting from the top-left one
+pub const NEIGHBOURS8: [(isize, isize); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+];
+
+/// Cell coordinates, `usize` for dense grids and `isize` for sparse ones
+pub type Coord<T = usize> = Point<T>;
+
+/// Size of a rectangle starting at the origin
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Bounds {
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Bounds {
+    pub fn new(width: usize, height: usize) -> Self {
+        Bounds { width, height }
+    }
+
+    pub fn contains(&self, c: Coord) -> bool {
+        c.x < self.width && c.y < self.height
+    }
+
+    pub fn contains_signed(&self, c: Coord<isize>) -> bool {
+        c.unsigned().is_some_and(|c| self.contains(c))
+    }
+
+    pub fn is_edge(&self, c: Coord) -> bool {
+        self.contains(c)
+            && (c.x == 0 || c.y == 0 || c.x == self.width - 1 || c.y == self.height - 1)
+    }
+
+    /// Wrap a coordinate around the edges, like on a torus
+    pub fn wrap(&self, c: Coord<isize>) -> Coord {
+        Coord::new(
+            c.x.rem_euclid(self.width as isize) as usize,
+            c.y.rem_euclid(self.height as isize) as usize,
+        )
+    }
+
+    /// Move from `c` by the given offset, `None` when leaving the rectangle
+    pub fn step(&self, c: Coord, (dx, dy): (isize, isize)) -> Option<Coord> {
+        c.offset(dx, dy).filter(|&c| self.contains(c))
+    }
+
+    pub fn neighbours4(&self, c: Coord) -> impl Iterator<Item = Coord> + '_ {
+        NEIGHBOURS4.into_iter().filter_map(move |d| self.step(c, d))
+    }
+
+    pub fn neighbours8(&self, c: Coord) -> impl Iterator<Item = Coord> + '_ {
+        NEIGHBOURS8.into_iter().filter_map(move |d| self.step(c, d))
+    }
+
+    /// Walk from `c` (excluded) by `step` until leaving the rectangle
+    pub fn ray(&self, c: Coord, step: (isize, isize)) -> impl Iterator<Item = Coord> + '_ {
+        std::iter::successors(self.step(c, step), move |&c| self.step(c, step))
+    }
+
+    /// All the coordinates, row by row
+    pub fn coords(&self) -> impl Iterator<Item = Coord> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| Coord::new(x, y)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bounds() {
+        let bounds = Bounds::new(3, 2);
+        assert!(bounds.contains(Coord::new(2, 1)));
+        assert!(!bounds.contains(Coord::new(3, 0)));
+        assert!(!bounds.contains_signed(Coord::new(-1, 0)));
+        assert_eq!(bounds.wrap(Coord::new(-1, 5)), Coord::new(2, 1));
+        assert_eq!(bounds.step(Coord::new(0, 0), (-1, 0)), None);
+        assert_eq!(bounds.neighbours8(Coord::new(0, 0)).count(), 3);
+        assert_eq!(bounds.coords().count(), 6);
+        assert!(!Bounds::new(0, 0).is_edge(Coord::new(0, 0)));
+    }
+}
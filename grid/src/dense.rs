@@ -0,0 +1,327 @@
+use std::{
+    fmt::Display,
+    ops::{Index, IndexMut},
+    str::FromStr,
+};
+
+use anyhow::{anyhow, Result};
+use common::parse::ParseError;
+
+use crate::{Bounds, Cell, Coord};
+
+/// Rectangular grid storing every cell, row by row
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DenseGrid<T> {
+    cells: Vec<T>,
+    bounds: Bounds,
+}
+
+impl<T: Clone> DenseGrid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self {
+        DenseGrid {
+            cells: vec![fill; width * height],
+            bounds: Bounds::new(width, height),
+        }
+    }
+}
+
+impl<T> DenseGrid<T> {
+    pub fn from_vec(width: usize, height: usize, cells: Vec<T>) -> Result<Self> {
+        if cells.len() != width * height {
+            return Err(anyhow!(
+                "{} cells do not fit in a {}x{} grid",
+                cells.len(),
+                width,
+                height
+            ));
+        }
+        Ok(DenseGrid {
+            cells,
+            bounds: Bounds::new(width, height),
+        })
+    }
+
+    /// Parse a character map, every line must have the same length
+    pub fn parse_with(
+        s: &str,
+        mut f: impl FnMut(char) -> Result<T, ParseError>,
+    ) -> Result<Self, ParseError> {
+        let mut cells = vec![];
+        let mut width = None;
+        let mut height = 0;
+        for (y, line) in s.lines().enumerate() {
+            let len = line.chars().count();
+            match width {
+                None => width = Some(len),
+                Some(w) if w != len => {
+                    let message = format!("expected {} cells, found {}", w, len);
+                    return Err(ParseError::new(message, line).at(y + 1, 1));
+                }
+                _ => {}
+            }
+            for (x, c) in line.chars().enumerate() {
+                cells.push(f(c).map_err(|e| e.at(y + 1, x + 1))?);
+            }
+            height += 1;
+        }
+        let width = width.ok_or_else(|| ParseError::new("empty grid", s))?;
+        Ok(DenseGrid {
+            cells,
+            bounds: Bounds::new(width, height),
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.bounds.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.bounds.height
+    }
+
+    pub fn bounds(&self) -> Bounds {
+        self.bounds
+    }
+
+    pub fn in_bounds(&self, c: Coord) -> bool {
+        self.bounds.contains(c)
+    }
+
+    pub fn is_edge(&self, c: Coord) -> bool {
+        self.bounds.is_edge(c)
+    }
+
+    fn index_of(&self, c: Coord) -> Option<usize> {
+        self.in_bounds(c).then(|| c.y * self.bounds.width + c.x)
+    }
+
+    pub fn get(&self, c: Coord) -> Option<&T> {
+        self.index_of(c).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut(&mut self, c: Coord) -> Option<&mut T> {
+        self.index_of(c).map(|i| &mut self.cells[i])
+    }
+
+    /// Same as [`DenseGrid::get`] for coordinates that may be negative
+    pub fn get_signed(&self, c: Coord<isize>) -> Option<&T> {
+        self.get(c.unsigned()?)
+    }
+
+    /// Replace a cell, returning the old value. `None` if out of bounds
+    pub fn set(&mut self, c: Coord, value: T) -> Option<T> {
+        self.get_mut(c).map(|cell| std::mem::replace(cell, value))
+    }
+
+    pub fn neighbours4(&self, c: Coord) -> impl Iterator<Item = Coord> + '_ {
+        self.bounds.neighbours4(c)
+    }
+
+    pub fn neighbours8(&self, c: Coord) -> impl Iterator<Item = Coord> + '_ {
+        self.bounds.neighbours8(c)
+    }
+
+    /// Walk from `c` (excluded) by `step` until the edge of the grid
+    pub fn ray(&self, c: Coord, step: (isize, isize)) -> impl Iterator<Item = Coord> + '_ {
+        self.bounds.ray(c, step)
+    }
+
+    pub fn coords(&self) -> impl Iterator<Item = Coord> {
+        self.bounds.coords()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Coord, &T)> {
+        self.coords().zip(self.cells.iter())
+    }
+
+    pub fn cells(&self) -> &[T] {
+        &self.cells
+    }
+
+    /// Cells of row `y`, none when it is out of bounds
+    pub fn row(&self, y: usize) -> impl DoubleEndedIterator<Item = &T> {
+        let width = self.bounds.width;
+        let range = match y < self.bounds.height {
+            true => y * width..(y + 1) * width,
+            false => 0..0,
+        };
+        self.cells[range].iter()
+    }
+
+    /// Cells of column `x`, none when it is out of bounds
+    pub fn column(&self, x: usize) -> impl DoubleEndedIterator<Item = &T> {
+        let cells = match x < self.bounds.width {
+            true => &self.cells[x..],
+            false => &[],
+        };
+        cells.iter().step_by(self.bounds.width.max(1))
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.bounds.width.max(1))
+    }
+
+    /// Cells on the line going through `c` by `step` in both directions, from
+    /// its end in the `-step` direction. None when `c` is out of bounds
+    fn line(&self, c: Coord, step: (isize, isize)) -> impl Iterator<Item = &T> {
+        let back = (-step.0, -step.1);
+        let start = self
+            .in_bounds(c)
+            .then(|| self.ray(c, back).last().unwrap_or(c));
+        start
+            .into_iter()
+            .flat_map(move |start| std::iter::once(start).chain(self.ray(start, step)))
+            .map(|c| &self[c])
+    }
+
+    /// Top-left to bottom-right diagonal going through `c`, none when `c` is
+    /// out of bounds
+    pub fn diagonal(&self, c: Coord) -> impl Iterator<Item = &T> {
+        self.line(c, (1, 1))
+    }
+
+    /// Top-right to bottom-left diagonal going through `c`, none when `c` is
+    /// out of bounds
+    pub fn anti_diagonal(&self, c: Coord) -> impl Iterator<Item = &T> {
+        self.line(c, (-1, 1))
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> DenseGrid<U> {
+        DenseGrid {
+            cells: self.cells.iter().map(f).collect(),
+            bounds: self.bounds,
+        }
+    }
+
+    /// Render the grid with one character per cell
+    pub fn render_with(&self, mut f: impl FnMut(Coord, &T) -> char) -> String {
+        let mut s = String::with_capacity((self.bounds.width + 1) * self.bounds.height);
+        for (c, cell) in self.iter() {
+            s.push(f(c, cell));
+            if c.x + 1 == self.bounds.width {
+                s.push('\n');
+            }
+        }
+        s
+    }
+}
+
+impl<T> Index<Coord> for DenseGrid<T> {
+    type Output = T;
+
+    fn index(&self, c: Coord) -> &Self::Output {
+        self.get(c)
+            .unwrap_or_else(|| panic!("{:?} is out of the {:?} grid", c, self.bounds))
+    }
+}
+
+impl<T> IndexMut<Coord> for DenseGrid<T> {
+    fn index_mut(&mut self, c: Coord) -> &mut Self::Output {
+        let bounds = self.bounds;
+        self.get_mut(c)
+            .unwrap_or_else(|| panic!("{:?} is out of the {:?} grid", c, bounds))
+    }
+}
+
+impl<T: Cell> FromStr for DenseGrid<T> {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse_with(s, |c| {
+            T::from_char(c).ok_or_else(|| ParseError::new("invalid cell", &c.to_string()))
+        })
+    }
+}
+
+impl<T: Cell> Display for DenseGrid<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.render_with(|_, cell| cell.to_char()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 4x3 grid numbering its cells row by row
+    fn numbered() -> DenseGrid<usize> {
+        DenseGrid::from_vec(4, 3, (0..12).collect()).unwrap()
+    }
+
+    #[test]
+    fn parse_and_render() {
+        let grid: DenseGrid<char> = DenseGrid::parse_with("ab\ncd\n", Ok).unwrap();
+        assert_eq!((grid.width(), grid.height()), (2, 2));
+        assert_eq!(grid[Coord::new(1, 1)], 'd');
+        assert_eq!(grid.render_with(|_, &c| c), "ab\ncd\n");
+
+        let e = DenseGrid::parse_with("ab\nc\n", Ok).unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "line 2, column 1: expected 2 cells, found 1 in \"c\""
+        );
+        assert!(DenseGrid::parse_with("", Ok).is_err());
+        assert!(DenseGrid::from_vec(2, 2, vec![0; 3]).is_err());
+    }
+
+    #[test]
+    fn get_and_set() {
+        let mut grid = numbered();
+        assert_eq!(grid.get(Coord::new(3, 2)), Some(&11));
+        assert_eq!(grid.get(Coord::new(4, 0)), None);
+        assert_eq!(grid.get_signed(Coord::new(-1, 0)), None);
+        assert_eq!(grid.set(Coord::new(1, 0), 42), Some(1));
+        assert_eq!(grid.set(Coord::new(0, 3), 42), None);
+        assert_eq!(grid[Coord::new(1, 0)], 42);
+        assert!(grid.is_edge(Coord::new(3, 1)));
+        assert!(!grid.is_edge(Coord::new(1, 1)));
+    }
+
+    #[test]
+    fn rows_and_columns() {
+        let grid = numbered();
+        assert_eq!(grid.row(1).copied().collect::<Vec<_>>(), vec![4, 5, 6, 7]);
+        assert_eq!(grid.column(2).copied().collect::<Vec<_>>(), vec![2, 6, 10]);
+        assert_eq!(grid.column(2).next_back(), Some(&10));
+        assert_eq!(grid.row(3).count(), 0);
+        assert_eq!(grid.column(4).count(), 0);
+        assert_eq!(grid.rows().count(), 3);
+    }
+
+    #[test]
+    fn diagonals() {
+        let grid = numbered();
+        let diagonal = |c| grid.diagonal(c).copied().collect::<Vec<_>>();
+        let anti_diagonal = |c| grid.anti_diagonal(c).copied().collect::<Vec<_>>();
+        assert_eq!(diagonal(Coord::new(2, 1)), vec![1, 6, 11]);
+        assert_eq!(diagonal(Coord::new(0, 2)), vec![8]);
+        assert_eq!(anti_diagonal(Coord::new(1, 1)), vec![2, 5, 8]);
+        assert_eq!(anti_diagonal(Coord::new(3, 2)), vec![11]);
+        assert_eq!(diagonal(Coord::new(4, 0)), vec![]);
+        assert_eq!(anti_diagonal(Coord::new(4, 0)), vec![]);
+    }
+
+    #[test]
+    fn empty_grid() {
+        let grid: DenseGrid<u8> = DenseGrid::new(0, 0, 0);
+        assert_eq!(grid.row(0).count(), 0);
+        assert_eq!(grid.column(0).count(), 0);
+        assert_eq!(grid.rows().count(), 0);
+        assert_eq!(grid.diagonal(Coord::new(0, 0)).count(), 0);
+        assert_eq!(grid.anti_diagonal(Coord::new(0, 0)).count(), 0);
+        assert_eq!(grid.render_with(|_, _| '#'), "");
+    }
+
+    #[test]
+    fn neighbours() {
+        let grid = numbered();
+        let corner: Vec<_> = grid.neighbours4(Coord::new(0, 0)).collect();
+        assert_eq!(corner, vec![Coord::new(1, 0), Coord::new(0, 1)]);
+        assert_eq!(grid.neighbours8(Coord::new(1, 1)).count(), 8);
+        let ray: Vec<_> = grid.ray(Coord::new(0, 1), (1, 0)).collect();
+        assert_eq!(
+            ray,
+            vec![Coord::new(1, 1), Coord::new(2, 1), Coord::new(3, 1)]
+        );
+    }
+}
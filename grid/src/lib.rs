@@ -0,0 +1,14 @@
+//! 2D grids shared by the map based days.
+//!
+//! [`DenseGrid`] stores every cell of a rectangle in a flat `Vec`, while
+//! [`SparseGrid`] only stores occupied cells and can grow in any direction.
+
+mod cell;
+mod coord;
+mod dense;
+mod sparse;
+
+pub use cell::Cell;
+pub use coord::{Bounds, Coord, NEIGHBOURS4, NEIGHBOURS8};
+pub use dense::DenseGrid;
+pub use sparse::SparseGrid;
@@ -0,0 +1,198 @@
+use std::{collections::HashMap, fmt::Display, str::FromStr};
+
+use common::parse::ParseError;
+
+use crate::{Cell, Coord, NEIGHBOURS4, NEIGHBOURS8};
+
+/// Unbounded grid only storing the occupied cells
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SparseGrid<T> {
+    cells: HashMap<Coord<isize>, T>,
+}
+
+impl<T> Default for SparseGrid<T> {
+    fn default() -> Self {
+        SparseGrid {
+            cells: HashMap::new(),
+        }
+    }
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parse a character map, cells for which `f` returns `None` are left empty
+    pub fn parse_with(
+        s: &str,
+        mut f: impl FnMut(char) -> Result<Option<T>, ParseError>,
+    ) -> Result<Self, ParseError> {
+        let mut grid = SparseGrid::new();
+        for (y, line) in s.lines().enumerate() {
+            for (x, c) in line.chars().enumerate() {
+                if let Some(cell) = f(c).map_err(|e| e.at(y + 1, x + 1))? {
+                    grid.insert(Coord::new(x as isize, y as isize), cell);
+                }
+            }
+        }
+        Ok(grid)
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn contains(&self, c: Coord<isize>) -> bool {
+        self.cells.contains_key(&c)
+    }
+
+    pub fn get(&self, c: Coord<isize>) -> Option<&T> {
+        self.cells.get(&c)
+    }
+
+    pub fn get_mut(&mut self, c: Coord<isize>) -> Option<&mut T> {
+        self.cells.get_mut(&c)
+    }
+
+    pub fn insert(&mut self, c: Coord<isize>, value: T) -> Option<T> {
+        self.cells.insert(c, value)
+    }
+
+    pub fn remove(&mut self, c: Coord<isize>) -> Option<T> {
+        self.cells.remove(&c)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Coord<isize>, &T)> {
+        self.cells.iter().map(|(c, v)| (*c, v))
+    }
+
+    pub fn coords(&self) -> impl Iterator<Item = Coord<isize>> + '_ {
+        self.cells.keys().copied()
+    }
+
+    pub fn neighbours4(c: Coord<isize>) -> impl Iterator<Item = Coord<isize>> {
+        NEIGHBOURS4
+            .into_iter()
+            .map(move |(dx, dy)| c.offset(dx, dy))
+    }
+
+    pub fn neighbours8(c: Coord<isize>) -> impl Iterator<Item = Coord<isize>> {
+        NEIGHBOURS8
+            .into_iter()
+            .map(move |(dx, dy)| c.offset(dx, dy))
+    }
+
+    /// Occupied neighbours among the 8 surrounding cells
+    pub fn occupied_neighbours8(&self, c: Coord<isize>) -> impl Iterator<Item = Coord<isize>> + '_ {
+        Self::neighbours8(c).filter(|n| self.contains(*n))
+    }
+
+    /// Smallest rectangle containing every cell, as its `(min, max)` corners
+    pub fn bounds(&self) -> Option<(Coord<isize>, Coord<isize>)> {
+        let mut coords = self.cells.keys();
+        let first = *coords.next()?;
+        Some(coords.fold((first, first), |(min, max), c| {
+            (
+                Coord::new(min.x.min(c.x), min.y.min(c.y)),
+                Coord::new(max.x.max(c.x), max.y.max(c.y)),
+            )
+        }))
+    }
+
+    /// Occupied cells of a row, from left to right
+    pub fn row(&self, y: isize) -> impl DoubleEndedIterator<Item = (Coord<isize>, &T)> {
+        let mut row: Vec<_> = self.iter().filter(|(c, _)| c.y == y).collect();
+        row.sort_by_key(|(c, _)| c.x);
+        row.into_iter()
+    }
+
+    /// Occupied cells of a column, from top to bottom
+    pub fn column(&self, x: isize) -> impl DoubleEndedIterator<Item = (Coord<isize>, &T)> {
+        let mut column: Vec<_> = self.iter().filter(|(c, _)| c.x == x).collect();
+        column.sort_by_key(|(c, _)| c.y);
+        column.into_iter()
+    }
+
+    /// Render the bounding rectangle, `f` gets `None` for empty cells
+    pub fn render_with(&self, mut f: impl FnMut(Coord<isize>, Option<&T>) -> char) -> String {
+        let mut s = String::new();
+        if let Some((min, max)) = self.bounds() {
+            for y in min.y..=max.y {
+                for x in min.x..=max.x {
+                    let c = Coord::new(x, y);
+                    s.push(f(c, self.get(c)));
+                }
+                s.push('\n');
+            }
+        }
+        s
+    }
+}
+
+impl<T> FromIterator<(Coord<isize>, T)> for SparseGrid<T> {
+    fn from_iter<I: IntoIterator<Item = (Coord<isize>, T)>>(iter: I) -> Self {
+        SparseGrid {
+            cells: iter.into_iter().collect(),
+        }
+    }
+}
+
+/// Spaces and `.` are empty cells
+impl<T: Cell> FromStr for SparseGrid<T> {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse_with(s, |c| match c {
+            ' ' | '.' => Ok(None),
+            c => T::from_char(c)
+                .map(Some)
+                .ok_or_else(|| ParseError::new("invalid cell", &c.to_string())),
+        })
+    }
+}
+
+impl<T: Cell> Display for SparseGrid<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.render_with(|_, cell| cell.map_or('.', |c| c.to_char())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bounds_and_render() {
+        let mut grid =
+            SparseGrid::parse_with(".#.\n#..\n", |c| Ok((c == '#').then_some(c))).unwrap();
+        assert_eq!(grid.len(), 2);
+        assert_eq!(grid.bounds(), Some((Coord::new(0, 0), Coord::new(1, 1))));
+        grid.insert(Coord::new(-1, 2), '#');
+        assert_eq!(grid.bounds(), Some((Coord::new(-1, 0), Coord::new(1, 2))));
+        let render = grid.render_with(|_, c| c.copied().unwrap_or('.'));
+        assert_eq!(render, "..#\n.#.\n#..\n");
+        assert_eq!(SparseGrid::<char>::new().bounds(), None);
+    }
+
+    #[test]
+    fn rows_columns_and_neighbours() {
+        let grid: SparseGrid<u8> = [
+            (Coord::new(2, 0), 1),
+            (Coord::new(0, 0), 2),
+            (Coord::new(0, 1), 3),
+        ]
+        .into_iter()
+        .collect();
+        let row: Vec<_> = grid.row(0).map(|(_, &v)| v).collect();
+        assert_eq!(row, vec![2, 1]);
+        let column: Vec<_> = grid.column(0).map(|(_, &v)| v).collect();
+        assert_eq!(column, vec![2, 3]);
+        assert_eq!(grid.occupied_neighbours8(Coord::new(1, 0)).count(), 3);
+        assert_eq!(SparseGrid::<u8>::neighbours4(Coord::new(0, 0)).count(), 4);
+    }
+}
@@ -0,0 +1,50 @@
+use anyhow::Result;
+use common::gen::{Generate, Rng};
+use common::{Answer, Solution};
+
+pub struct DayX;
+
+impl Solution for DayX {
+    const DAY: u8 = 0;
+
+    type Input = String;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(input.to_string())
+    }
+
+    fn part1(_input: &Self::Input) -> Result<Answer> {
+        Ok(0usize.into())
+    }
+
+    fn part2(_input: &Self::Input) -> Result<Answer> {
+        Ok(0usize.into())
+    }
+}
+
+impl Generate for DayX {
+    const SIZE: usize = 0;
+
+    fn generate(_rng: &mut Rng, _size: usize) -> String {
+        String::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../example");
+
+    #[test]
+    fn part1() {
+        let input = DayX::parse(EXAMPLE).unwrap();
+        assert_eq!(DayX::part1(&input).unwrap().to_string(), "0");
+    }
+
+    #[test]
+    fn part2() {
+        let input = DayX::parse(EXAMPLE).unwrap();
+        assert_eq!(DayX::part2(&input).unwrap().to_string(), "0");
+    }
+}
@@ -1,28 +1,16 @@
-use std::time::Instant;
-
-use anyhow::{Context, Error, Result};
-
-fn part1(input: &str) -> Result<usize> {
-    Ok(0)
-}
-
-fn part2(input: &str) -> Result<usize> {
-    Ok(0)
-}
+use anyhow::Result;
+use common::Solution;
+use dayx::DayX;
 
 fn main() -> Result<()> {
-    let input = include_str!("../input");
+    let input = common::input::from_args(DayX::DAY)?;
+    let parsed = DayX::parse(&input)?;
 
-    let instant = Instant::now();
-    let res = part1(input)?;
-    let time = Instant::now() - instant;
-    println!("[*] part 1: {} ({:?})", res, time);
+    let res = DayX::part1(&parsed)?;
+    println!("[*] part 1: {}", res);
 
-    let instant = Instant::now();
-    let res = part2(input)?;
-    let time = Instant::now() - instant;
-    println!("[*] part 2: {} ({:?})", res, time);
+    let res = DayX::part2(&parsed)?;
+    println!("[*] part 2: {}", res);
 
     Ok(())
 }
-